@@ -5,13 +5,37 @@ use serde_valid::Validate;
 use crate::{
     create_add_election, create_add_vote, create_election, create_get_election, create_get_votes,
     elections::{self, CreateElection, CreateElectionResult},
+    models,
 };
 
-create_election!(AntiPluralityElection);
+use voting_systems::anti_plurality::{
+    AntiPluralityElection, AntiPluralityResult, AntiPluralityVote,
+};
+
+create_election!(
+    AntiPluralityElectionModeled,
+    anti_plurality_election,
+    AntiPluralityElection
+);
+
+impl AntiPluralityElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            anti_plurality_election: AntiPluralityElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
 
 create_get_election!(
     crate::schema::anti_plurality_elections,
-    AntiPluralityElection
+    AntiPluralityElectionModeled
 );
 
 #[derive(Serialize, Deserialize, Validate, Debug, Clone)]
@@ -24,87 +48,50 @@ pub struct AntiPluralityCreateElection {
 
 create_add_election!(
     AntiPluralityCreateElection,
-    crate::models::AntiPluralityElection,
+    models::AntiPluralityElection,
     crate::schema::anti_plurality_elections
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct AntiPluralityVote {
-    pub created_by: uuid::Uuid,
-    pub vote: usize,
-}
+pub struct AntiPluralityVoteModeled(pub AntiPluralityVote);
 
-impl From<crate::models::AntiPluralityVote> for AntiPluralityVote {
-    fn from(v: crate::models::AntiPluralityVote) -> Self {
+impl From<models::AntiPluralityVote> for AntiPluralityVote {
+    fn from(v: models::AntiPluralityVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             vote: v.vote as usize,
         }
     }
 }
 
-impl AntiPluralityVote {
-    fn make_model(&self, election_id: &uuid::Uuid) -> crate::models::AntiPluralityVote {
-        crate::models::AntiPluralityVote {
+impl AntiPluralityVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::AntiPluralityVote {
+        models::AntiPluralityVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            vote: self.vote as i32,
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            vote: self.0.vote as i32,
         }
     }
 }
 
 create_get_votes!(
     crate::schema::anti_plurality_votes,
-    crate::models::AntiPluralityVote,
+    models::AntiPluralityVote,
     AntiPluralityVote
 );
 
 create_add_vote!(
     crate::schema::anti_plurality_votes,
-    crate::models::AntiPluralityVote,
-    AntiPluralityVote
+    models::AntiPluralityVote,
+    AntiPluralityVoteModeled
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct AntiPluralityTally {
-    pub option_index: usize,
-    pub vote_count: usize,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct AntiPluralityResult {
-    pub options: Vec<String>,
-    pub votes_tally: Vec<AntiPluralityTally>,
-    pub winner: usize,
-    pub vote_count: usize,
-    pub votes: Vec<AntiPluralityVote>,
-}
-
 pub fn get_result(
-    election: &AntiPluralityElection,
+    election: &AntiPluralityElectionModeled,
     votes: &[AntiPluralityVote],
 ) -> AntiPluralityResult {
-    let mut count_tally = vec![0; election.options.len()];
-    for vote in votes {
-        count_tally[vote.vote] += 1;
-    }
-
-    let mut count_tally: Vec<AntiPluralityTally> = count_tally
-        .into_iter()
-        .enumerate()
-        .map(|(i, count)| AntiPluralityTally {
-            option_index: i,
-            vote_count: count,
-        })
-        .collect();
-    count_tally.sort_by(|a, b| a.vote_count.cmp(&b.vote_count));
-
-    AntiPluralityResult {
-        options: election.options.clone(),
-        winner: count_tally[0].option_index,
-        votes_tally: count_tally,
-        vote_count: votes.len(),
-        votes: votes.to_vec(),
-    }
+    voting_systems::anti_plurality::get_result(&election.anti_plurality_election, votes)
 }
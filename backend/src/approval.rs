@@ -4,11 +4,35 @@ use serde::{Deserialize, Serialize};
 use crate::{
     create_add_election, create_add_vote, create_election, create_get_election, create_get_votes,
     elections::{self, CreateElection, CreateElectionResult},
+    models,
 };
 
-create_election!(ApprovalElection);
+use voting_systems::{
+    approval::{ApprovalElection, ApprovalResult, ApprovalVote},
+    proportional_approval::{
+        ProportionalApprovalElection, ProportionalApprovalError, ProportionalApprovalMethod,
+        ProportionalApprovalResult,
+    },
+};
 
-create_get_election!(crate::schema::approval_elections, ApprovalElection);
+create_election!(ApprovalElectionModeled, approval_election, ApprovalElection);
+
+impl ApprovalElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            approval_election: ApprovalElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
+
+create_get_election!(crate::schema::approval_elections, ApprovalElectionModeled);
 
 #[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
 pub struct ApprovalCreateElection {
@@ -20,89 +44,63 @@ pub struct ApprovalCreateElection {
 
 create_add_election!(
     ApprovalCreateElection,
-    crate::models::ApprovalElection,
+    models::ApprovalElection,
     crate::schema::approval_elections
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ApprovalVote {
-    pub created_by: uuid::Uuid,
-    pub votes: Vec<bool>,
-}
+pub struct ApprovalVoteModeled(pub ApprovalVote);
 
-impl From<crate::models::ApprovalVote> for ApprovalVote {
-    fn from(v: crate::models::ApprovalVote) -> Self {
+impl From<models::ApprovalVote> for ApprovalVote {
+    fn from(v: models::ApprovalVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             votes: v.votes.into_iter().map(|i| i.unwrap()).collect(),
         }
     }
 }
 
-impl ApprovalVote {
-    fn make_model(&self, election_id: &uuid::Uuid) -> crate::models::ApprovalVote {
-        crate::models::ApprovalVote {
+impl ApprovalVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::ApprovalVote {
+        models::ApprovalVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            votes: self.votes.clone().into_iter().map(Some).collect(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().copied().map(Some).collect(),
         }
     }
 }
 
 create_get_votes!(
     crate::schema::approval_votes,
-    crate::models::ApprovalVote,
+    models::ApprovalVote,
     ApprovalVote
 );
 
 create_add_vote!(
     crate::schema::approval_votes,
-    crate::models::ApprovalVote,
-    ApprovalVote
+    models::ApprovalVote,
+    ApprovalVoteModeled
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ApprovalTally {
-    pub option_index: usize,
-    pub approval_count: usize,
+pub fn get_result(election: &ApprovalElectionModeled, votes: &[ApprovalVote]) -> ApprovalResult {
+    voting_systems::approval::get_result(&election.approval_election, votes)
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ApprovalResult {
-    pub options: Vec<String>,
-    pub winner: usize,
-    pub approve_tally: Vec<ApprovalTally>,
-    pub vote_count: usize,
-    pub votes: Vec<ApprovalVote>,
-}
-
-pub fn get_result(election: &ApprovalElection, votes: &[ApprovalVote]) -> ApprovalResult {
-    let mut vote_tally = vec![0; election.options.len()];
-    for vote in votes {
-        for (option_index, v) in vote.votes.iter().enumerate() {
-            if *v {
-                vote_tally[option_index] += 1;
-            }
-        }
-    }
-
-    let mut approve_tally: Vec<ApprovalTally> = vote_tally
-        .iter()
-        .enumerate()
-        .map(|(option_index, approval_count)| ApprovalTally {
-            option_index,
-            approval_count: *approval_count,
-        })
-        .collect();
-
-    approve_tally.sort_by(|a, b| b.approval_count.cmp(&a.approval_count));
-
-    ApprovalResult {
-        options: election.options.clone(),
-        winner: approve_tally[0].option_index,
-        approve_tally,
-        vote_count: votes.len(),
-        votes: votes.to_vec(),
-    }
+pub fn get_proportional_result(
+    election: &ApprovalElectionModeled,
+    votes: &[ApprovalVote],
+    method: ProportionalApprovalMethod,
+    committee_size: usize,
+) -> Result<ProportionalApprovalResult, ProportionalApprovalError> {
+    let proportional_election = ProportionalApprovalElection {
+        options: election.approval_election.options.clone(),
+        committee_size,
+        method,
+        seed: crate::seed::get_seed(&election.election),
+        tie_break: election.approval_election.tie_break.clone(),
+    };
+    voting_systems::proportional_approval::get_result(&proportional_election, votes)
 }
@@ -7,9 +7,33 @@ use crate::{
     models,
 };
 
-create_election!(BordaCountElection);
+use voting_systems::borda_count::{BordaCountElection, BordaCountResult, BordaCountVote};
 
-create_get_election!(crate::schema::borda_count_elections, BordaCountElection);
+create_election!(
+    BordaCountElectionModeled,
+    borda_count_election,
+    BordaCountElection
+);
+
+impl BordaCountElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            borda_count_election: BordaCountElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
+
+create_get_election!(
+    crate::schema::borda_count_elections,
+    BordaCountElectionModeled
+);
 
 #[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
 pub struct BordaCountCreateElection {
@@ -25,33 +49,27 @@ create_add_election!(
     crate::schema::borda_count_elections
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct BordaCountVote {
-    pub created_by: uuid::Uuid,
-    pub votes: Vec<usize>,
-}
+pub struct BordaCountVoteModeled(pub BordaCountVote);
 
 impl From<models::BordaCountVote> for BordaCountVote {
     fn from(v: models::BordaCountVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             votes: v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
         }
     }
 }
 
-impl BordaCountVote {
+impl BordaCountVoteModeled {
     fn make_model(&self, election_id: &uuid::Uuid) -> models::BordaCountVote {
         models::BordaCountVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            votes: self
-                .votes
-                .clone()
-                .into_iter()
-                .map(|i| Some(i as i32))
-                .collect(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
         }
     }
 }
@@ -65,48 +83,12 @@ create_get_votes!(
 create_add_vote!(
     crate::schema::borda_count_votes,
     models::BordaCountVote,
-    BordaCountVote
+    BordaCountVoteModeled
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct BordaCountTally {
-    pub option_index: usize,
-    pub vote_count: usize,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct BordaCountResult {
-    pub options: Vec<String>,
-    pub winner: usize,
-    pub vote_tally: Vec<BordaCountTally>,
-    pub votes: Vec<BordaCountVote>,
-    pub vote_count: usize,
-}
-
-pub fn get_result(election: &BordaCountElection, votes: &[BordaCountVote]) -> BordaCountResult {
-    let mut vote_tally = vec![0; election.options.len()];
-    for vote in votes {
-        for (option_index, v) in vote.votes.iter().enumerate() {
-            vote_tally[option_index] += v;
-        }
-    }
-
-    let mut vote_tally: Vec<BordaCountTally> = vote_tally
-        .iter()
-        .enumerate()
-        .map(|(option_index, vote_count)| BordaCountTally {
-            option_index: option_index,
-            vote_count: *vote_count,
-        })
-        .collect();
-
-    vote_tally.sort_by(|a, b| b.vote_count.cmp(&a.vote_count));
-
-    BordaCountResult {
-        options: election.options.clone(),
-        winner: vote_tally.first().unwrap().option_index,
-        vote_tally,
-        votes: votes.to_vec(),
-        vote_count: votes.len(),
-    }
+pub fn get_result(
+    election: &BordaCountElectionModeled,
+    votes: &[BordaCountVote],
+) -> BordaCountResult {
+    voting_systems::borda_count::get_result(&election.borda_count_election, votes)
 }
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::{
+    bucklin::{BucklinElection, BucklinMajorityRule, BucklinMethod, BucklinResult},
+    preferential_voting::PreferentialVote,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BucklinElectionModeled {
+    pub election: models::Election,
+    pub bucklin_election: BucklinElection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicBucklinElection {
+    pub id: String,
+    pub title: String,
+    pub options: Vec<String>,
+    pub require_token: bool,
+    pub majority_rule: BucklinMajorityRule,
+}
+
+impl From<BucklinElectionModeled> for PublicBucklinElection {
+    fn from(val: BucklinElectionModeled) -> Self {
+        PublicBucklinElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.bucklin_election.options,
+            require_token: val.election.requires_token,
+            majority_rule: val.bucklin_election.majority_rule,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<BucklinElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::bucklin_elections;
+    let bucklin_election: models::BucklinElection = bucklin_elections::table
+        .filter(bucklin_elections::election_id.eq(id))
+        .first::<models::BucklinElection>(c)
+        .optional()
+        .unwrap()?;
+
+    Some(BucklinElectionModeled {
+        bucklin_election: BucklinElection {
+            options: bucklin_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            method: BucklinMethod::Standard,
+            majority_rule: BucklinMajorityRule::from_str(&bucklin_election.majority_rule).unwrap(),
+            approval_depth: 0,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct BucklinCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+    pub majority_rule: BucklinMajorityRule,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: BucklinCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::bucklin_elections;
+    diesel::insert_into(bucklin_elections::table)
+        .values(models::BucklinElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            majority_rule: arg.majority_rule.as_ref().to_string(),
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub struct BucklinVoteModeled(pub PreferentialVote);
+
+impl From<models::BucklinVote> for PreferentialVote {
+    fn from(v: models::BucklinVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
+        }
+    }
+}
+
+impl BucklinVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::BucklinVote {
+        models::BucklinVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::bucklin_votes,
+    models::BucklinVote,
+    PreferentialVote
+);
+
+create_add_vote!(
+    crate::schema::bucklin_votes,
+    models::BucklinVote,
+    BucklinVoteModeled
+);
+
+pub fn get_result(election: &BucklinElectionModeled, votes: &[PreferentialVote]) -> BucklinResult {
+    voting_systems::bucklin::get_result(&election.bucklin_election, votes)
+}
+
+pub fn get_approval_runoff_result(
+    election: &BucklinElectionModeled,
+    votes: &[PreferentialVote],
+    approval_depth: usize,
+) -> BucklinResult {
+    let approval_runoff_election = BucklinElection {
+        method: BucklinMethod::ApprovalRunoff,
+        approval_depth,
+        ..election.bucklin_election.clone()
+    };
+    voting_systems::bucklin::get_result(&approval_runoff_election, votes)
+}
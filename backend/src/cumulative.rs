@@ -7,22 +7,21 @@ use crate::{
     models,
 };
 
-#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
-pub struct CumulativeElection {
+use voting_systems::cumulative::{CumulativeElection, CumulativeResult, CumulativeVote};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CumulativeElectionModeled {
     pub election: models::Election,
-    #[validate(max_items = 100)]
-    #[validate(custom = crate::elections::valid_election_option)]
-    pub options: Vec<String>,
-    pub max_votes: usize,
+    pub cumulative_election: CumulativeElection,
 }
 
-impl Into<PublicElection> for CumulativeElection {
-    fn into(self) -> PublicElection {
+impl From<CumulativeElectionModeled> for PublicElection {
+    fn from(val: CumulativeElectionModeled) -> Self {
         PublicElection {
-            id: self.election.id.to_string(),
-            title: self.election.title.to_string(),
-            options: self.options,
-            require_token: self.election.requires_token,
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.cumulative_election.options,
+            require_token: val.election.requires_token,
         }
     }
 }
@@ -30,31 +29,28 @@ impl Into<PublicElection> for CumulativeElection {
 pub fn get_election(
     c: &mut diesel::PgConnection,
     election_id: &uuid::Uuid,
-) -> Option<CumulativeElection> {
-    let base_election = match elections::get_election(c, election_id) {
-        Some(election) => election,
-        None => return None,
-    };
+) -> Option<CumulativeElectionModeled> {
+    let base_election = elections::get_election(c, election_id)?;
 
     use crate::schema::cumulative_elections;
-    let cumulative_election: models::CumulativeElection = match cumulative_elections::table
+    let cumulative_election: models::CumulativeElection = cumulative_elections::table
         .filter(cumulative_elections::election_id.eq(election_id))
         .first::<models::CumulativeElection>(c)
         .optional()
-        .unwrap()
-    {
-        Some(election) => election,
-        None => return None,
-    };
+        .unwrap()?;
 
-    Some(CumulativeElection {
+    Some(CumulativeElectionModeled {
+        cumulative_election: CumulativeElection {
+            options: cumulative_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            max_votes: cumulative_election.max_votes as usize,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
         election: base_election,
-        options: cumulative_election
-            .options
-            .into_iter()
-            .map(|i| i.unwrap())
-            .collect(),
-        max_votes: cumulative_election.max_votes as usize,
     })
 }
 
@@ -73,6 +69,8 @@ pub fn add_election(
         c,
         &arg.election_base.title,
         arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
     );
 
     use crate::schema::cumulative_elections;
@@ -85,30 +83,32 @@ pub fn add_election(
         .execute(c)
         .unwrap();
 
-    return result;
+    result
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CumulativeVote {
-    pub created_by: uuid::Uuid,
-    pub votes: Vec<usize>,
-}
+pub struct CumulativeVoteModeled(pub CumulativeVote);
 
-impl CumulativeVote {
+impl CumulativeVoteModeled {
     pub fn make_model(&self, election_id: &uuid::Uuid) -> models::CumulativeVote {
         models::CumulativeVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            votes: self.votes.iter().map(|i| Some(*i as i32)).collect(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
         }
     }
 }
 
-impl From<crate::models::CumulativeVote> for CumulativeVote {
-    fn from(vote: crate::models::CumulativeVote) -> Self {
+impl From<models::CumulativeVote> for CumulativeVote {
+    fn from(vote: models::CumulativeVote) -> Self {
         CumulativeVote {
             created_by: vote.created_by,
+            weight: crate::elections::weight_from_model(
+                vote.weight_numerator,
+                vote.weight_denominator,
+            ),
             votes: vote
                 .votes
                 .into_iter()
@@ -120,54 +120,19 @@ impl From<crate::models::CumulativeVote> for CumulativeVote {
 
 create_get_votes!(
     crate::schema::cumulative_votes,
-    crate::models::CumulativeVote,
+    models::CumulativeVote,
     CumulativeVote
 );
 
 create_add_vote!(
     crate::schema::cumulative_votes,
-    crate::models::CumulativeVote,
-    CumulativeVote
+    models::CumulativeVote,
+    CumulativeVoteModeled
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CumulativeTally {
-    pub option_index: usize,
-    pub vote_count: usize,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CumulativeResult {
-    pub options: Vec<String>,
-    pub votes_tally: Vec<CumulativeTally>,
-    pub winner: usize,
-    pub vote_count: usize,
-    pub votes: Vec<CumulativeVote>,
-}
-
-pub fn get_result(election: &CumulativeElection, votes: &[CumulativeVote]) -> CumulativeResult {
-    let mut points_tally = vec![0; election.options.len()];
-    for vote in votes {
-        for (i, points) in vote.votes.iter().enumerate() {
-            points_tally[i] += *points as usize;
-        }
-    }
-
-    let mut points_tally: Vec<CumulativeTally> = points_tally
-        .into_iter()
-        .enumerate()
-        .map(|(i, points)| CumulativeTally {
-            option_index: i,
-            vote_count: points,
-        })
-        .collect();
-    points_tally.sort_by(|a, b| b.vote_count.cmp(&a.vote_count));
-
-    CumulativeResult {
-        options: election.options.clone(),
-        winner: points_tally[0].option_index,
-        votes_tally: points_tally,
-        vote_count: votes.len(),
-        votes: votes.to_vec(),
-    }
+pub fn get_result(
+    election: &CumulativeElectionModeled,
+    votes: &[CumulativeVote],
+) -> CumulativeResult {
+    voting_systems::cumulative::get_result(&election.cumulative_election, votes)
 }
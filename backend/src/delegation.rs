@@ -0,0 +1,54 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+use crate::models;
+
+use voting_systems::delegation::{creates_cycle, Delegation};
+
+pub fn get_delegations(c: &mut diesel::PgConnection, election_id: &uuid::Uuid) -> Vec<Delegation> {
+    use crate::schema::delegations;
+    let rows: Vec<models::Delegation> = delegations::table
+        .filter(delegations::election_id.eq(election_id))
+        .get_results(c)
+        .unwrap();
+
+    rows.into_iter()
+        .map(|i| Delegation {
+            delegator: i.created_by,
+            delegate: i.delegate,
+            weight: crate::elections::weight_from_model(i.weight_numerator, i.weight_denominator),
+        })
+        .collect()
+}
+
+pub enum AddDelegationError {
+    AlreadyDelegated,
+    Cycle,
+}
+
+pub fn add_delegation(
+    c: &mut diesel::PgConnection,
+    election_id: &uuid::Uuid,
+    delegation: Delegation,
+) -> Result<(), AddDelegationError> {
+    if creates_cycle(&get_delegations(c, election_id), &delegation) {
+        return Err(AddDelegationError::Cycle);
+    }
+
+    use crate::schema::delegations;
+    if diesel::insert_into(delegations::table)
+        .values(models::Delegation {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: delegation.delegator,
+            delegate: delegation.delegate,
+            weight_numerator: delegation.weight.numerator as i64,
+            weight_denominator: delegation.weight.denominator as i64,
+        })
+        .execute(c)
+        .is_err()
+    {
+        return Err(AddDelegationError::AlreadyDelegated);
+    }
+
+    Ok(())
+}
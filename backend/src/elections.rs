@@ -1,13 +1,17 @@
-use std::collections::HashMap;
-
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use serde::{Deserialize, Serialize};
+use voting_systems::{tie_break::TieBreak, weight::Weight};
 
 #[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
 pub struct CreateElection {
     #[validate(max_length = 50)]
     pub title: String,
     pub requires_token: bool,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// SHA-256 of the organiser's seed secret
+    #[serde(default)]
+    pub seed_commitment: Option<String>,
 }
 
 pub fn get_election(
@@ -22,6 +26,17 @@ pub fn get_election(
         .unwrap()
 }
 
+/// Elections saved before tie break policies existed, or with a policy
+/// that no longer parses, fall back to the method's own rule
+pub fn tie_break_from_model(tie_break: &str) -> TieBreak {
+    tie_break.parse().unwrap_or_default()
+}
+
+/// Ballot and token weights are stored as a numerator and denominator
+pub fn weight_from_model(numerator: i64, denominator: i64) -> Weight {
+    Weight::new(numerator as u64, denominator as u64).unwrap_or(Weight::ONE)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateElectionResult {
     pub election_id: uuid::Uuid,
@@ -32,11 +47,14 @@ pub fn add_election(
     c: &mut diesel::PgConnection,
     title: &str,
     requires_token: bool,
+    tie_break: &TieBreak,
+    seed_commitment: Option<&str>,
 ) -> CreateElectionResult {
     use crate::schema::elections;
 
     let token = uuid::Uuid::new_v4();
     let id = uuid::Uuid::new_v4();
+    let (seed_commitment, seed_secret) = crate::seed::new_seed(seed_commitment);
 
     diesel::insert_into(elections::table)
         .values(crate::models::Election {
@@ -47,6 +65,11 @@ pub fn add_election(
             created_time: None,
             public: false,
             voting_locked: false,
+            tie_break: tie_break.to_string(),
+            seed_commitment: Some(seed_commitment),
+            seed_secret,
+            seed_revealed: false,
+            seed_entropy: Vec::new(),
         })
         .execute(c)
         .unwrap();
@@ -65,41 +88,6 @@ pub struct PublicElection {
     pub require_token: bool,
 }
 
-pub trait RankedChoiceVote<T> {
-    fn ranked_votes(&self) -> Vec<T>;
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct RankedChoiceVoteTally<T> {
-    pub votes: Vec<T>,
-    pub count: usize,
-}
-
-pub fn tally_ranked_votes<T, J>(votes: &[T]) -> Vec<RankedChoiceVoteTally<J>>
-where
-    T: RankedChoiceVote<J>,
-    J: Eq + std::hash::Hash + Clone,
-{
-    let mut map: HashMap<Vec<J>, usize> = HashMap::new();
-
-    for vote in votes {
-        let individual_votes = vote.ranked_votes();
-        if !map.contains_key(&individual_votes) {
-            map.insert(individual_votes.clone(), 0);
-        }
-
-        *map.get_mut(&individual_votes).unwrap() += 1;
-    }
-
-    let mut result: Vec<RankedChoiceVoteTally<J>> = map
-        .into_iter()
-        .map(|(votes, count)| RankedChoiceVoteTally { votes, count })
-        .collect();
-    result.sort_by(|a, b| b.count.cmp(&a.count));
-
-    result
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ExtraElectionInfo {
     voting_lock: bool,
@@ -110,10 +98,7 @@ pub fn get_extra_election_info(
     c: &mut diesel::PgConnection,
     election_id: &uuid::Uuid,
 ) -> Option<ExtraElectionInfo> {
-    let election = match get_election(c, election_id) {
-        Some(election) => election,
-        None => return None,
-    };
+    let election = get_election(c, election_id)?;
 
     Some(ExtraElectionInfo {
         voting_lock: election.voting_locked,
@@ -184,28 +169,20 @@ pub fn valid_election_option(
 
 #[macro_export]
 macro_rules! create_election {
-    ($name:ident) => {
-        #[derive(serde::Serialize, serde::Deserialize, serde_valid::Validate, Debug, Clone)]
+    ($name:ident, $field:ident, $system_election:ty) => {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
         pub struct $name {
-            pub election: crate::models::Election,
-            #[validate(max_items = 100)]
-            #[validate(custom = crate::elections::valid_election_option)]
-            pub options: Vec<String>,
+            pub election: $crate::models::Election,
+            pub $field: $system_election,
         }
 
-        impl $name {
-            pub fn new(election: crate::models::Election, options: Vec<String>) -> Self {
-                Self { election, options }
-            }
-        }
-
-        impl Into<crate::elections::PublicElection> for $name {
-            fn into(self) -> crate::elections::PublicElection {
-                crate::elections::PublicElection {
-                    id: self.election.id.to_string(),
-                    title: self.election.title.to_string(),
-                    options: self.options,
-                    require_token: self.election.requires_token,
+        impl From<$name> for $crate::elections::PublicElection {
+            fn from(val: $name) -> Self {
+                $crate::elections::PublicElection {
+                    id: val.election.id.to_string(),
+                    title: val.election.title.to_string(),
+                    options: val.$field.options,
+                    require_token: val.election.requires_token,
                 }
             }
         }
@@ -255,10 +232,12 @@ macro_rules! create_add_election {
             c: &mut diesel::PgConnection,
             arg: $create_arg_t,
         ) -> CreateElectionResult {
-            let result = crate::elections::add_election(
+            let result = $crate::elections::add_election(
                 c,
                 &arg.election_base.title,
                 arg.election_base.requires_token,
+                &arg.election_base.tie_break,
+                arg.election_base.seed_commitment.as_deref(),
             );
 
             {
@@ -269,7 +248,7 @@ macro_rules! create_add_election {
                     .unwrap();
             }
 
-            return result;
+            result
         }
     };
 }
@@ -308,7 +287,11 @@ macro_rules! create_add_vote {
 
             {
                 use $votes_path::*;
-                if let Err(_) = diesel::insert_into(table).values(insert_value).execute(c) {
+                if diesel::insert_into(table)
+                    .values(insert_value)
+                    .execute(c)
+                    .is_err()
+                {
                     return Err(AddVoteError::AlreadyVoted);
                 }
             }
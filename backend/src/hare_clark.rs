@@ -0,0 +1,177 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::hare_clark::{
+    rotation_count, CountbackError, CountbackResult, HareClarkElection, HareClarkResult,
+    HareClarkVote,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HareClarkElectionModeled {
+    pub election: models::Election,
+    pub hare_clark_election: HareClarkElection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicHareClarkElection {
+    pub id: String,
+    pub title: String,
+    pub options: Vec<String>,
+    pub require_token: bool,
+    pub vacancies: usize,
+    pub rotation_count: usize,
+}
+
+impl From<HareClarkElectionModeled> for PublicHareClarkElection {
+    fn from(val: HareClarkElectionModeled) -> Self {
+        PublicHareClarkElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            rotation_count: rotation_count(val.hare_clark_election.options.len()),
+            options: val.hare_clark_election.options,
+            require_token: val.election.requires_token,
+            vacancies: val.hare_clark_election.vacancies,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<HareClarkElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::hare_clark_elections;
+    let hare_clark_election: models::HareClarkElection = hare_clark_elections::table
+        .filter(hare_clark_elections::election_id.eq(id))
+        .first::<models::HareClarkElection>(c)
+        .optional()
+        .unwrap()?;
+
+    Some(HareClarkElectionModeled {
+        hare_clark_election: HareClarkElection {
+            options: hare_clark_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            vacancies: hare_clark_election.vacancies as usize,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct HareClarkCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+    pub vacancies: usize,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: HareClarkCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::hare_clark_elections;
+    diesel::insert_into(hare_clark_elections::table)
+        .values(models::HareClarkElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            vacancies: arg.vacancies as i32,
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+/// The rotation for the next ballot paper handed out, going through every
+/// rotation in turn so each is printed equally often
+pub fn get_next_rotation(
+    c: &mut diesel::PgConnection,
+    election: &HareClarkElectionModeled,
+) -> usize {
+    use crate::schema::hare_clark_votes;
+    let vote_count: i64 = hare_clark_votes::table
+        .filter(hare_clark_votes::election_id.eq(election.election.id))
+        .count()
+        .get_result(c)
+        .unwrap();
+
+    vote_count as usize % rotation_count(election.hare_clark_election.options.len())
+}
+
+pub struct HareClarkVoteModeled(pub HareClarkVote);
+
+impl From<models::HareClarkVote> for HareClarkVote {
+    fn from(v: models::HareClarkVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
+            rotation: v.rotation as usize,
+        }
+    }
+}
+
+impl HareClarkVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::HareClarkVote {
+        models::HareClarkVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+            rotation: self.0.rotation as i32,
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::hare_clark_votes,
+    models::HareClarkVote,
+    HareClarkVote
+);
+
+create_add_vote!(
+    crate::schema::hare_clark_votes,
+    models::HareClarkVote,
+    HareClarkVoteModeled
+);
+
+pub fn get_result(election: &HareClarkElectionModeled, votes: &[HareClarkVote]) -> HareClarkResult {
+    voting_systems::hare_clark::get_result(&election.hare_clark_election, votes)
+}
+
+pub fn get_countback(
+    election: &HareClarkElectionModeled,
+    votes: &[HareClarkVote],
+    vacating: usize,
+    contesting: &[usize],
+) -> Result<CountbackResult, CountbackError> {
+    voting_systems::hare_clark::get_countback(
+        &election.hare_clark_election,
+        votes,
+        vacating,
+        contesting,
+    )
+}
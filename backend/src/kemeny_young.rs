@@ -0,0 +1,97 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_election, create_add_vote, create_election, create_get_election, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::kemeny_young::{
+    KemenyYoungElection, KemenyYoungError, KemenyYoungResult, KemenyYoungVote,
+};
+
+create_election!(
+    KemenyYoungElectionModeled,
+    kemeny_young_election,
+    KemenyYoungElection
+);
+
+impl KemenyYoungElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            kemeny_young_election: KemenyYoungElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
+
+create_get_election!(
+    crate::schema::kemeny_young_elections,
+    KemenyYoungElectionModeled
+);
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct KemenyYoungCreateElection {
+    pub election_base: CreateElection,
+    // Matches voting_systems::kemeny_young::MAX_OPTIONS
+    #[validate(max_items = 12)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+}
+
+create_add_election!(
+    KemenyYoungCreateElection,
+    models::KemenyYoungElection,
+    crate::schema::kemeny_young_elections
+);
+
+pub struct KemenyYoungVoteModeled(pub KemenyYoungVote);
+
+impl From<models::KemenyYoungVote> for KemenyYoungVote {
+    fn from(v: models::KemenyYoungVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|v| v.unwrap() as usize).collect(),
+        }
+    }
+}
+
+impl KemenyYoungVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::KemenyYoungVote {
+        models::KemenyYoungVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::kemeny_young_votes,
+    models::KemenyYoungVote,
+    KemenyYoungVote
+);
+
+create_add_vote!(
+    crate::schema::kemeny_young_votes,
+    models::KemenyYoungVote,
+    KemenyYoungVoteModeled
+);
+
+pub fn get_result(
+    election: &KemenyYoungElectionModeled,
+    votes: &[KemenyYoungVote],
+) -> Result<KemenyYoungResult, KemenyYoungError> {
+    voting_systems::kemeny_young::get_result(&election.kemeny_young_election, votes)
+}
@@ -11,22 +11,32 @@ extern crate diesel;
 mod anti_plurality;
 mod approval;
 mod borda_count;
+mod bucklin;
 mod condorcet_method;
 mod cumulative;
 mod db;
+mod delegation;
 mod elections;
+mod hare_clark;
+mod kemeny_young;
 mod majority_judgment;
 mod models;
+mod participatory_budgeting;
+mod party_list;
 mod preferential_voting;
+mod quadratic;
 mod quota_preferential_vic_labor_2024;
 mod routes;
 mod schema;
+mod schulze;
 mod score;
+mod seed;
 mod single_non_transferable_vote;
 mod single_party;
 mod single_transferable_vote;
 mod star;
 mod three_two_one;
+mod two_round;
 mod usual_judgment;
 
 lazy_static! {
@@ -1,12 +1,18 @@
 use crate::schema::{
     anti_plurality_elections, anti_plurality_votes, approval_elections, approval_votes,
-    borda_count_elections, borda_count_votes, condorcet_method_elections, condorcet_method_votes,
-    cumulative_elections, cumulative_votes, elections, majority_judgment_elections,
-    majority_judgment_votes, preferential_elections, preferential_votes, score_elections,
-    score_votes, single_non_transferable_elections, single_non_transferable_votes,
+    borda_count_elections, borda_count_votes, bucklin_elections, bucklin_votes,
+    condorcet_method_elections, condorcet_method_votes, cumulative_elections, cumulative_votes,
+    delegations, elections, entropy_contributions, hare_clark_elections, hare_clark_votes,
+    kemeny_young_elections, kemeny_young_votes, majority_judgment_elections,
+    majority_judgment_votes, participatory_budgeting_elections, participatory_budgeting_votes,
+    party_list_elections, party_list_votes, preferential_elections, preferential_votes,
+    quadratic_elections, quadratic_votes, quota_preferential_vic_labor_2024_candidate,
+    quota_preferential_vic_labor_2024_elections,
+    quota_preferential_vic_labor_2024_transferable_votes, schulze_elections, schulze_votes,
+    score_elections, score_votes, single_non_transferable_elections, single_non_transferable_votes,
     single_party_elections, single_party_votes, star_elections, star_votes, stv_elections,
-    stv_votes, three_two_one_elections, three_two_one_votes, usual_judgment_elections,
-    usual_judgment_votes, voting_tokens,
+    stv_votes, three_two_one_elections, three_two_one_votes, two_round_elections, two_round_votes,
+    usual_judgment_elections, usual_judgment_votes, voting_tokens,
 };
 use diesel::{Insertable, Queryable};
 use serde::{Deserialize, Serialize};
@@ -22,6 +28,15 @@ pub struct Election {
     pub created_time: Option<std::time::SystemTime>,
     pub public: bool,
     pub voting_locked: bool,
+    /// `TieBreak` policy in its string form
+    pub tie_break: String,
+    /// None for elections made before seeds were committed
+    pub seed_commitment: Option<String>,
+    /// Kept private until `seed_revealed`
+    pub seed_secret: Option<String>,
+    pub seed_revealed: bool,
+    /// Voter entropy mixed into the seed when it was revealed
+    pub seed_entropy: Vec<Option<String>>,
 }
 
 impl Eq for Election {}
@@ -31,6 +46,28 @@ impl Eq for Election {}
 pub struct VotingToken {
     pub election_id: uuid::Uuid,
     pub token: uuid::Uuid,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = delegations)]
+pub struct Delegation {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub delegate: uuid::Uuid,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = entropy_contributions)]
+pub struct EntropyContribution {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub entropy: String,
 }
 
 macro_rules! create_baseline_election {
@@ -65,6 +102,8 @@ create_baseline_election!(SinglePartyElection, single_party_elections);
 create_baseline_election!(ThreeTwoOneElection, three_two_one_elections);
 create_baseline_election!(CondorcetMethodElection, condorcet_method_elections);
 create_baseline_election!(MajorityJudgmentElection, majority_judgment_elections);
+create_baseline_election!(SchulzeElection, schulze_elections);
+create_baseline_election!(KemenyYoungElection, kemeny_young_elections);
 
 macro_rules! create_baseline_vote {
     ($name:ident, $table:expr, $votes_type:ty) => {
@@ -77,12 +116,15 @@ macro_rules! create_baseline_vote {
             pub election_id: uuid::Uuid,
             pub created_by: uuid::Uuid,
             pub votes: Vec<Option<$votes_type>>,
+            pub weight_numerator: i64,
+            pub weight_denominator: i64,
         }
     };
 }
 
 create_baseline_vote!(PreferentialVote, preferential_votes, i32);
 create_baseline_vote!(BordaCountVote, borda_count_votes, i32);
+create_baseline_vote!(BucklinVote, bucklin_votes, i32);
 create_baseline_vote!(ApprovalVote, approval_votes, bool);
 create_baseline_vote!(StarVote, star_votes, i32);
 create_baseline_vote!(ThreeTwoOneVote, three_two_one_votes, i32);
@@ -90,6 +132,14 @@ create_baseline_vote!(CondorcetMethodVote, condorcet_method_votes, i32);
 create_baseline_vote!(MajorityJudgmentVote, majority_judgment_votes, i32);
 create_baseline_vote!(ScoreVote, score_votes, i32);
 create_baseline_vote!(UsualJudgmentVote, usual_judgment_votes, i32);
+create_baseline_vote!(SchulzeVote, schulze_votes, i32);
+create_baseline_vote!(KemenyYoungVote, kemeny_young_votes, i32);
+create_baseline_vote!(QuadraticVote, quadratic_votes, i32);
+create_baseline_vote!(
+    ParticipatoryBudgetingVote,
+    participatory_budgeting_votes,
+    i32
+);
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
 #[diesel(table_name = score_elections)]
@@ -105,6 +155,8 @@ pub struct StvElection {
     pub election_id: uuid::Uuid,
     pub options: Vec<Option<String>>,
     pub elected_count: i32,
+    pub transfer: String,
+    pub quota: String,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
@@ -114,6 +166,8 @@ pub struct StvVote {
     pub election_id: uuid::Uuid,
     pub created_by: uuid::Uuid,
     pub votes: Vec<Option<i32>>,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
@@ -131,6 +185,8 @@ pub struct CumulativeVote {
     pub election_id: uuid::Uuid,
     pub created_by: uuid::Uuid,
     pub votes: Vec<Option<i32>>,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
@@ -140,6 +196,8 @@ pub struct AntiPluralityVote {
     pub election_id: uuid::Uuid,
     pub created_by: uuid::Uuid,
     pub vote: i32,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
@@ -149,6 +207,8 @@ pub struct SinglePartyVote {
     pub election_id: uuid::Uuid,
     pub created_by: uuid::Uuid,
     pub voted: bool,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
@@ -166,4 +226,135 @@ pub struct SNTVVote {
     pub election_id: uuid::Uuid,
     pub created_by: uuid::Uuid,
     pub votes: Vec<Option<bool>>,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = bucklin_elections)]
+pub struct BucklinElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub majority_rule: String,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = participatory_budgeting_elections)]
+pub struct ParticipatoryBudgetingElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub costs: Vec<Option<i64>>,
+    pub budget: i64,
+    pub max_score: i32,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = party_list_elections)]
+pub struct PartyListElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub seat_count: i32,
+    pub method: String,
+    pub threshold: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = party_list_votes)]
+pub struct PartyListVote {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub votes: Vec<Option<bool>>,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = two_round_elections)]
+pub struct TwoRoundElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub finalists: Option<Vec<Option<i32>>>,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = two_round_votes)]
+pub struct TwoRoundVote {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub round: i32,
+    pub vote: i32,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = hare_clark_elections)]
+pub struct HareClarkElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub vacancies: i32,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = hare_clark_votes)]
+pub struct HareClarkVote {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub votes: Vec<Option<i32>>,
+    pub rotation: i32,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = quadratic_elections)]
+pub struct QuadraticElection {
+    pub election_id: uuid::Uuid,
+    pub options: Vec<Option<String>>,
+    pub credits: i32,
+    pub allow_negative: bool,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = quota_preferential_vic_labor_2024_elections)]
+pub struct QuotaPreferentialVicLabor2024Election {
+    pub election_id: uuid::Uuid,
+    pub elected_count: i32,
+    pub percent_female: f64,
+    /// As `Quota::key` writes it
+    pub quota: String,
+    /// As `Arithmetic` displays it
+    pub arithmetic: String,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug)]
+pub struct QuotaPreferentialVicLabor2024Candidate {
+    pub id: i64,
+    pub election_id: uuid::Uuid,
+    pub candidate_name: String,
+    pub is_female: bool,
+}
+
+/// A candidate before the database has numbered it, candidates are listed
+/// in the order they were inserted
+#[derive(Debug, Insertable)]
+#[diesel(table_name = quota_preferential_vic_labor_2024_candidate)]
+pub struct NewQuotaPreferentialVicLabor2024Candidate {
+    pub election_id: uuid::Uuid,
+    pub candidate_name: String,
+    pub is_female: bool,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Debug, Insertable)]
+#[diesel(table_name = quota_preferential_vic_labor_2024_transferable_votes)]
+pub struct QuotaPreferentialVicLabor2024Vote {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub created_by: uuid::Uuid,
+    pub votes: Vec<Option<i64>>,
+    pub weight_numerator: i64,
+    pub weight_denominator: i64,
 }
@@ -0,0 +1,176 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::participatory_budgeting::{
+    BudgetingElection, BudgetingMethod, BudgetingResult, BudgetingVote,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParticipatoryBudgetingElectionModeled {
+    pub election: models::Election,
+    pub participatory_budgeting_election: BudgetingElection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicParticipatoryBudgetingElection {
+    pub id: String,
+    pub title: String,
+    pub options: Vec<String>,
+    pub require_token: bool,
+    pub costs: Vec<u64>,
+    pub budget: u64,
+    pub max_score: usize,
+}
+
+impl From<ParticipatoryBudgetingElectionModeled> for PublicParticipatoryBudgetingElection {
+    fn from(val: ParticipatoryBudgetingElectionModeled) -> Self {
+        PublicParticipatoryBudgetingElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.participatory_budgeting_election.options,
+            require_token: val.election.requires_token,
+            costs: val.participatory_budgeting_election.costs,
+            budget: val.participatory_budgeting_election.budget,
+            max_score: val.participatory_budgeting_election.max_score,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<ParticipatoryBudgetingElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::participatory_budgeting_elections;
+    let budgeting_election: models::ParticipatoryBudgetingElection =
+        participatory_budgeting_elections::table
+            .filter(participatory_budgeting_elections::election_id.eq(id))
+            .first::<models::ParticipatoryBudgetingElection>(c)
+            .optional()
+            .unwrap()?;
+
+    Some(ParticipatoryBudgetingElectionModeled {
+        participatory_budgeting_election: BudgetingElection {
+            options: budgeting_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            costs: budgeting_election
+                .costs
+                .into_iter()
+                .map(|i| i.unwrap() as u64)
+                .collect(),
+            budget: budgeting_election.budget as u64,
+            max_score: budgeting_election.max_score as usize,
+            method: BudgetingMethod::EqualShares,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct ParticipatoryBudgetingCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+    pub costs: Vec<u64>,
+    pub budget: u64,
+    pub max_score: usize,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: ParticipatoryBudgetingCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::participatory_budgeting_elections;
+    diesel::insert_into(participatory_budgeting_elections::table)
+        .values(models::ParticipatoryBudgetingElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            costs: arg.costs.into_iter().map(|i| Some(i as i64)).collect(),
+            budget: arg.budget as i64,
+            max_score: arg.max_score as i32,
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub struct ParticipatoryBudgetingVoteModeled(pub BudgetingVote);
+
+impl From<models::ParticipatoryBudgetingVote> for BudgetingVote {
+    fn from(v: models::ParticipatoryBudgetingVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
+        }
+    }
+}
+
+impl ParticipatoryBudgetingVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::ParticipatoryBudgetingVote {
+        models::ParticipatoryBudgetingVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::participatory_budgeting_votes,
+    models::ParticipatoryBudgetingVote,
+    BudgetingVote
+);
+
+create_add_vote!(
+    crate::schema::participatory_budgeting_votes,
+    models::ParticipatoryBudgetingVote,
+    ParticipatoryBudgetingVoteModeled
+);
+
+/// Method of Equal Shares with greedy completion
+pub fn get_result(
+    election: &ParticipatoryBudgetingElectionModeled,
+    votes: &[BudgetingVote],
+) -> BudgetingResult {
+    voting_systems::participatory_budgeting::get_result(
+        &election.participatory_budgeting_election,
+        votes,
+    )
+}
+
+pub fn get_greedy_result(
+    election: &ParticipatoryBudgetingElectionModeled,
+    votes: &[BudgetingVote],
+) -> BudgetingResult {
+    let greedy_election = BudgetingElection {
+        method: BudgetingMethod::Greedy,
+        ..election.participatory_budgeting_election.clone()
+    };
+    voting_systems::participatory_budgeting::get_result(&greedy_election, votes)
+}
@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult, PublicElection},
+    models,
+};
+
+use voting_systems::{
+    party_list::{PartyListElection, PartyListMethod, PartyListResult},
+    single_non_transferable_vote::SNTVVote,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartyListElectionModeled {
+    pub election: models::Election,
+    pub party_list_election: PartyListElection,
+}
+
+impl From<PartyListElectionModeled> for PublicElection {
+    fn from(val: PartyListElectionModeled) -> Self {
+        PublicElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.party_list_election.options,
+            require_token: val.election.requires_token,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<PartyListElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::party_list_elections;
+    let party_list_election: models::PartyListElection = party_list_elections::table
+        .filter(party_list_elections::election_id.eq(id))
+        .first::<models::PartyListElection>(c)
+        .optional()
+        .unwrap()?;
+
+    Some(PartyListElectionModeled {
+        party_list_election: PartyListElection {
+            options: party_list_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            seat_count: party_list_election.seat_count as usize,
+            method: PartyListMethod::from_str(&party_list_election.method).unwrap(),
+            threshold: party_list_election.threshold,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct PartyListCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+    pub seat_count: usize,
+    pub method: PartyListMethod,
+    pub threshold: Option<f64>,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: PartyListCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::party_list_elections;
+    diesel::insert_into(party_list_elections::table)
+        .values(models::PartyListElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            seat_count: arg.seat_count as i32,
+            method: arg.method.as_ref().to_string(),
+            threshold: arg.threshold,
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub struct PartyListVoteModeled(pub SNTVVote);
+
+impl From<models::PartyListVote> for SNTVVote {
+    fn from(v: models::PartyListVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|i| i.unwrap()).collect(),
+        }
+    }
+}
+
+impl PartyListVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::PartyListVote {
+        models::PartyListVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.clone().into_iter().map(Some).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::party_list_votes,
+    models::PartyListVote,
+    SNTVVote
+);
+
+create_add_vote!(
+    crate::schema::party_list_votes,
+    models::PartyListVote,
+    PartyListVoteModeled
+);
+
+pub fn get_result(election: &PartyListElectionModeled, votes: &[SNTVVote]) -> PartyListResult {
+    voting_systems::party_list::get_result(&election.party_list_election, votes)
+}
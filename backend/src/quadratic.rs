@@ -0,0 +1,146 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::quadratic::{QuadraticElection, QuadraticResult, QuadraticVote};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticElectionModeled {
+    pub election: models::Election,
+    pub quadratic_election: QuadraticElection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicQuadraticElection {
+    pub id: String,
+    pub title: String,
+    pub options: Vec<String>,
+    pub require_token: bool,
+    pub credits: usize,
+    pub allow_negative: bool,
+}
+
+impl From<QuadraticElectionModeled> for PublicQuadraticElection {
+    fn from(val: QuadraticElectionModeled) -> Self {
+        PublicQuadraticElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.quadratic_election.options,
+            require_token: val.election.requires_token,
+            credits: val.quadratic_election.credits,
+            allow_negative: val.quadratic_election.allow_negative,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<QuadraticElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::quadratic_elections;
+    let quadratic_election: models::QuadraticElection = quadratic_elections::table
+        .filter(quadratic_elections::election_id.eq(id))
+        .first::<models::QuadraticElection>(c)
+        .optional()
+        .unwrap()?;
+
+    Some(QuadraticElectionModeled {
+        quadratic_election: QuadraticElection {
+            options: quadratic_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            credits: quadratic_election.credits as usize,
+            allow_negative: quadratic_election.allow_negative,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticCreateElection {
+    pub election_base: CreateElection,
+    pub options: Vec<String>,
+    pub credits: usize,
+    pub allow_negative: bool,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: QuadraticCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::quadratic_elections;
+    diesel::insert_into(quadratic_elections::table)
+        .values(models::QuadraticElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            credits: arg.credits as i32,
+            allow_negative: arg.allow_negative,
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub struct QuadraticVoteModeled(pub QuadraticVote);
+
+impl QuadraticVoteModeled {
+    pub fn make_model(&self, election_id: &uuid::Uuid) -> models::QuadraticVote {
+        models::QuadraticVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+        }
+    }
+}
+
+impl From<models::QuadraticVote> for QuadraticVote {
+    fn from(vote: models::QuadraticVote) -> Self {
+        QuadraticVote {
+            created_by: vote.created_by,
+            weight: crate::elections::weight_from_model(
+                vote.weight_numerator,
+                vote.weight_denominator,
+            ),
+            votes: vote.votes.into_iter().map(|i| i.unwrap() as i64).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::quadratic_votes,
+    models::QuadraticVote,
+    QuadraticVote
+);
+
+create_add_vote!(
+    crate::schema::quadratic_votes,
+    models::QuadraticVote,
+    QuadraticVoteModeled
+);
+
+pub fn get_result(election: &QuadraticElectionModeled, votes: &[QuadraticVote]) -> QuadraticResult {
+    voting_systems::quadratic::get_result(&election.quadratic_election, votes)
+}
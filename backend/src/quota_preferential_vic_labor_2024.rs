@@ -1,2 +1,198 @@
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::{
+    numeric::Arithmetic,
+    quota_preferential_vic_labor_2024::{Candidate, Election, ElectionResult, Quota, Vote},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VicLaborElectionModeled {
+    pub election: models::Election,
+    pub vic_labor_election: Election,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicVicLaborElection {
+    pub id: String,
+    pub title: String,
+    pub candidates: Vec<Candidate>,
+    pub require_token: bool,
+    pub percent_female: f64,
+    pub elected_count: usize,
+    pub quota: Quota,
+    pub arithmetic: Arithmetic,
+}
+
+impl From<VicLaborElectionModeled> for PublicVicLaborElection {
+    fn from(val: VicLaborElectionModeled) -> Self {
+        PublicVicLaborElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            candidates: val.vic_labor_election.candidates,
+            require_token: val.election.requires_token,
+            percent_female: val.vic_labor_election.percent_female,
+            elected_count: val.vic_labor_election.elected_count,
+            quota: val.vic_labor_election.quota,
+            arithmetic: val.vic_labor_election.arithmetic,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<VicLaborElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::{
+        quota_preferential_vic_labor_2024_candidate as candidates,
+        quota_preferential_vic_labor_2024_elections as vic_labor_elections,
+    };
+    let vic_labor_election: models::QuotaPreferentialVicLabor2024Election =
+        vic_labor_elections::table
+            .filter(vic_labor_elections::election_id.eq(id))
+            .first::<models::QuotaPreferentialVicLabor2024Election>(c)
+            .optional()
+            .unwrap()?;
+    let candidate_rows: Vec<models::QuotaPreferentialVicLabor2024Candidate> = candidates::table
+        .filter(candidates::election_id.eq(id))
+        .order(candidates::id.asc())
+        .get_results(c)
+        .unwrap();
+
+    Some(VicLaborElectionModeled {
+        vic_labor_election: Election {
+            candidates: candidate_rows
+                .into_iter()
+                .map(|i| Candidate::new(i.candidate_name, i.is_female))
+                .collect(),
+            percent_female: vic_labor_election.percent_female,
+            elected_count: vic_labor_election.elected_count as usize,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+            // Rows that no longer parse fall back to the rules' own settings
+            arithmetic: vic_labor_election
+                .arithmetic
+                .parse()
+                .unwrap_or(Election::WHOLE_POINTS),
+            quota: vic_labor_election.quota.parse().unwrap_or_default(),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VicLaborCreateCandidate {
+    pub name: String,
+    pub is_female: bool,
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct VicLaborCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    pub candidates: Vec<VicLaborCreateCandidate>,
+    pub percent_female: f64,
+    pub elected_count: usize,
+    pub quota: Quota,
+    pub arithmetic: Arithmetic,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: VicLaborCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::{
+        quota_preferential_vic_labor_2024_candidate as candidates,
+        quota_preferential_vic_labor_2024_elections as vic_labor_elections,
+    };
+    diesel::insert_into(vic_labor_elections::table)
+        .values(models::QuotaPreferentialVicLabor2024Election {
+            election_id: result.election_id,
+            elected_count: arg.elected_count as i32,
+            percent_female: arg.percent_female,
+            quota: arg.quota.key().to_string(),
+            arithmetic: arg.arithmetic.to_string(),
+        })
+        .execute(c)
+        .unwrap();
+
+    // Inserted in one statement so the ids keep the order of the ballot paper
+    diesel::insert_into(candidates::table)
+        .values(
+            arg.candidates
+                .into_iter()
+                .map(|i| models::NewQuotaPreferentialVicLabor2024Candidate {
+                    election_id: result.election_id,
+                    candidate_name: i.name,
+                    is_female: i.is_female,
+                })
+                .collect::<Vec<_>>(),
+        )
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub struct VicLaborVoteModeled(pub Vote);
+
+impl From<models::QuotaPreferentialVicLabor2024Vote> for Vote {
+    fn from(v: models::QuotaPreferentialVicLabor2024Vote) -> Self {
+        Vote::new(
+            v.created_by,
+            v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
+        )
+        .with_weight(crate::elections::weight_from_model(
+            v.weight_numerator,
+            v.weight_denominator,
+        ))
+    }
+}
+
+impl VicLaborVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::QuotaPreferentialVicLabor2024Vote {
+        models::QuotaPreferentialVicLabor2024Vote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            votes: self.0.votes.iter().map(|i| Some(*i as i64)).collect(),
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::quota_preferential_vic_labor_2024_transferable_votes,
+    models::QuotaPreferentialVicLabor2024Vote,
+    Vote
+);
+
+create_add_vote!(
+    crate::schema::quota_preferential_vic_labor_2024_transferable_votes,
+    models::QuotaPreferentialVicLabor2024Vote,
+    VicLaborVoteModeled
+);
+
+pub fn get_result(election: &VicLaborElectionModeled, votes: &[Vote]) -> ElectionResult {
+    voting_systems::quota_preferential_vic_labor_2024::get_result(
+        &election.vic_labor_election,
+        votes,
+    )
+}
@@ -8,11 +8,12 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{anti_plurality::AntiPluralityVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     anti_plurality::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        AntiPluralityCreateElection, AntiPluralityElection, AntiPluralityVote,
+        AntiPluralityCreateElection, AntiPluralityElectionModeled, AntiPluralityVoteModeled,
     },
     create_get_election_id_endpoint, create_get_election_id_get_result_endpoint,
     create_post_election_id_new_vote_endpoint, create_post_endpoint,
@@ -27,16 +28,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<AntiPluralityCreateElection> for CreateElectionRequest {
-    fn into(self) -> AntiPluralityCreateElection {
+impl From<CreateElectionRequest> for AntiPluralityCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         AntiPluralityCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -52,19 +60,24 @@ struct AntiPluralityVoteRequest {
     vote: usize,
 }
 
-fn make_vote(request: &AntiPluralityVoteRequest, user_id: &uuid::Uuid) -> AntiPluralityVote {
-    AntiPluralityVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &AntiPluralityVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> AntiPluralityVoteModeled {
+    AntiPluralityVoteModeled(AntiPluralityVote {
+        created_by: *user_id,
+        weight,
         vote: request.vote,
-    }
+    })
 }
 
 fn vote_validation(
     request: &AntiPluralityVoteRequest,
-    election: &AntiPluralityElection,
+    election: &AntiPluralityElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if request.vote >= election.options.len() {
+    if request.vote >= election.anti_plurality_election.options.len() {
         return Err(NewVoteError::InvalidVoteOption);
     }
 
@@ -84,5 +97,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
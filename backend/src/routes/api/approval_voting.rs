@@ -8,11 +8,15 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{
+    approval::ApprovalVote, proportional_approval::ProportionalApprovalMethod, tie_break::TieBreak,
+    weight::Weight,
+};
 
 use crate::{
     approval::{
-        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        ApprovalCreateElection, ApprovalElection, ApprovalVote,
+        add_election, add_vote, get_election, get_proportional_result, get_result, get_votes,
+        AddVoteError, ApprovalCreateElection, ApprovalElectionModeled, ApprovalVoteModeled,
     },
     create_get_election_id_endpoint, create_get_election_id_get_result_endpoint,
     create_post_election_id_new_vote_endpoint, create_post_endpoint,
@@ -27,16 +31,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<ApprovalCreateElection> for CreateElectionRequest {
-    fn into(self) -> ApprovalCreateElection {
+impl From<CreateElectionRequest> for ApprovalCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         ApprovalCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,24 +58,77 @@ create_get_election_id_endpoint!(get_election);
 
 create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
+#[derive(Debug, Deserialize)]
+struct CommitteeResultQuery {
+    api_key: Option<String>,
+    committee_size: usize,
+}
+
+macro_rules! create_committee_result_endpoint {
+    ($path:literal, $endpoint:ident, $method:expr) => {
+        crate::create_get_election_id_get_result_endpoint!(
+            $path,
+            $endpoint,
+            CommitteeResultQuery,
+            get_election,
+            get_votes,
+            |election, votes, query: &CommitteeResultQuery| {
+                match voting_systems::weight::get_weighted_result(votes, |votes| {
+                    get_proportional_result(election, votes, $method, query.committee_size)
+                })
+                .transpose()
+                {
+                    Ok(result) => {
+                        crate::routes::api::common::respond_seeded(&election.election, result)
+                    }
+                    Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+                }
+            }
+        );
+    };
+}
+
+create_committee_result_endpoint!(
+    "/{election_id}/get_result/pav",
+    get_election_id_get_pav_result_endpoint,
+    ProportionalApprovalMethod::Pav
+);
+
+create_committee_result_endpoint!(
+    "/{election_id}/get_result/sequential_pav",
+    get_election_id_get_sequential_pav_result_endpoint,
+    ProportionalApprovalMethod::SequentialPav
+);
+
+create_committee_result_endpoint!(
+    "/{election_id}/get_result/phragmen",
+    get_election_id_get_phragmen_result_endpoint,
+    ProportionalApprovalMethod::Phragmen
+);
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 struct ApprovalVoteRequest {
     votes: Vec<bool>,
 }
 
-fn make_vote(request: &ApprovalVoteRequest, user_id: &uuid::Uuid) -> ApprovalVote {
-    ApprovalVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &ApprovalVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> ApprovalVoteModeled {
+    ApprovalVoteModeled(ApprovalVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
 fn vote_validation(
     request: &ApprovalVoteRequest,
-    election: &ApprovalElection,
+    election: &ApprovalElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.approval_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -84,5 +148,9 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_pav_result_endpoint)
+        .service(get_election_id_get_sequential_pav_result_endpoint)
+        .service(get_election_id_get_phragmen_result_endpoint)
         .service(post_election_id_new_vote)
 }
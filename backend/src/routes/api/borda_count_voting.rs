@@ -8,11 +8,12 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{borda_count::BordaCountVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     borda_count::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        BordaCountCreateElection, BordaCountElection, BordaCountVote,
+        BordaCountCreateElection, BordaCountElectionModeled, BordaCountVoteModeled,
     },
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
     create_post_endpoint, create_ranked_choice_vote_validation,
@@ -27,16 +28,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<BordaCountCreateElection> for CreateElectionRequest {
-    fn into(self) -> BordaCountCreateElection {
+impl From<CreateElectionRequest> for BordaCountCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         BordaCountCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -52,14 +60,23 @@ struct CreateBordaCountVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &CreateBordaCountVoteRequest, user_id: &uuid::Uuid) -> BordaCountVote {
-    BordaCountVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &CreateBordaCountVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> BordaCountVoteModeled {
+    BordaCountVoteModeled(BordaCountVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
-create_ranked_choice_vote_validation!(&CreateBordaCountVoteRequest, &BordaCountElection);
+create_ranked_choice_vote_validation!(
+    &CreateBordaCountVoteRequest,
+    &BordaCountElectionModeled,
+    borda_count_election
+);
 
 create_post_election_id_new_vote_endpoint!(
     CreateBordaCountVoteRequest,
@@ -74,5 +91,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
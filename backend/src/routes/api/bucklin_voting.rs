@@ -0,0 +1,133 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{
+    bucklin::BucklinMajorityRule, preferential_voting::PreferentialVote, tie_break::TieBreak,
+    weight::Weight,
+};
+
+use crate::{
+    bucklin::{
+        add_election, add_vote, get_approval_runoff_result, get_election, get_result, get_votes,
+        AddVoteError, BucklinCreateElection, BucklinElectionModeled, BucklinVoteModeled,
+        PublicBucklinElection,
+    },
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint, create_ranked_choice_vote_validation,
+    db::DbPool,
+    elections::CreateElection,
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    /// Defaults to the most votes at the level a majority was reached
+    #[serde(default)]
+    majority_rule: BucklinMajorityRule,
+}
+
+impl From<CreateElectionRequest> for BucklinCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        BucklinCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+            majority_rule: val.majority_rule,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicBucklinElection);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug, Deserialize)]
+struct ApprovalRunoffResultQuery {
+    api_key: Option<String>,
+    /// Preferences on each ballot counted as approvals
+    approval_depth: usize,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/approval_runoff",
+    get_election_id_get_approval_runoff_result_endpoint,
+    ApprovalRunoffResultQuery,
+    get_election,
+    get_votes,
+    |election: &BucklinElectionModeled, votes, query: &ApprovalRunoffResultQuery| {
+        if query.approval_depth == 0
+            || query.approval_depth > election.bucklin_election.options.len()
+        {
+            return HttpResponse::BadRequest().body("invalid approval depth");
+        }
+        crate::routes::api::common::respond_seeded(
+            &election.election,
+            voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_approval_runoff_result(election, votes, query.approval_depth)
+            }),
+        )
+    }
+);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateBucklinVoteRequest {
+    votes: Vec<usize>,
+}
+
+fn make_vote(
+    request: &CreateBucklinVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> BucklinVoteModeled {
+    BucklinVoteModeled(PreferentialVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
+}
+
+create_ranked_choice_vote_validation!(
+    &CreateBucklinVoteRequest,
+    &BucklinElectionModeled,
+    bucklin_election
+);
+
+create_post_election_id_new_vote_endpoint!(
+    CreateBucklinVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/bucklin")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_approval_runoff_result_endpoint)
+        .service(post_election_id_new_vote)
+}
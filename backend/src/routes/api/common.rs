@@ -1,11 +1,46 @@
 use serde::{Deserialize, Serialize};
+use validator::ValidationError;
+use voting_systems::seed::{is_commitment, SeededResult};
+
+pub fn validate_seed_commitment(commitment: &str) -> Result<(), ValidationError> {
+    if !is_commitment(&commitment.to_lowercase()) {
+        return Err(ValidationError::new(
+            "seed_commitment must be a hex encoded SHA-256 digest",
+        ));
+    }
+    Ok(())
+}
+
+/// Hands a result endpoint's closure its arguments, so their types are known
+/// inside the closure
+pub fn respond<E, V, Q>(
+    election: &E,
+    votes: &V,
+    query: &Q,
+    respond: impl FnOnce(&E, &V, &Q) -> actix_web::HttpResponse,
+) -> actix_web::HttpResponse {
+    respond(election, votes, query)
+}
+
+/// Responds with a result and the derivation of the election's seed. A
+/// revealed secret that doesn't match its commitment is a server error, the
+/// draws couldn't be checked by anyone
+pub fn respond_seeded<R: Serialize>(
+    election: &crate::models::Election,
+    result: R,
+) -> actix_web::HttpResponse {
+    match crate::seed::seed_derivation(election) {
+        Ok(seed) => actix_web::HttpResponse::Ok().json(SeededResult { result, seed }),
+        Err(err) => actix_web::HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
 
 #[macro_export]
 macro_rules! create_get_election_id_endpoint {
     ($get_election:ident, $response_ty:ty) => {
         #[get("/{election_id}")]
         async fn get_election_id_endpoint(
-            pool: actix_web::web::Data<crate::db::DbPool>,
+            pool: actix_web::web::Data<$crate::db::DbPool>,
             election_id: actix_web::web::Path<String>,
         ) -> actix_web::HttpResponse {
             let election_id = $crate::convert_into_uuid_or_fail!(election_id.as_str());
@@ -29,7 +64,7 @@ macro_rules! create_get_election_id_endpoint {
         }
     };
     ($get_election:ident) => {
-        create_get_election_id_endpoint!($get_election, crate::elections::PublicElection);
+        create_get_election_id_endpoint!($get_election, $crate::elections::PublicElection);
     };
 }
 
@@ -44,7 +79,7 @@ macro_rules! create_post_endpoint {
     ($add_election:ident, $request_struct:ident) => {
         #[post("")]
         async fn post_endpoint(
-            pool: actix_web::web::Data<crate::db::DbPool>,
+            pool: actix_web::web::Data<$crate::db::DbPool>,
             request: actix_web_validator::Json<$request_struct>,
         ) -> actix_web::HttpResponse {
             info!("Got new request to create a election {:?}", request.title);
@@ -59,7 +94,7 @@ macro_rules! create_post_endpoint {
             .unwrap();
 
             actix_web::HttpResponse::Ok()
-                .json(crate::routes::api::common::NewElectionResponse {
+                .json($crate::routes::api::common::NewElectionResponse {
                     id: result.election_id.to_string(),
                     key: result.manage_token.to_string(),
                 })
@@ -81,24 +116,25 @@ pub enum NewVoteError {
     InvalidVoteOption,
     DuplicatedVote,
     AlreadyVoted,
+    RoundClosed,
 }
 
 #[macro_export]
 macro_rules! create_ranked_choice_vote_validation {
-    ($request_type:ty, $election_type:ty) => {
+    ($request_type:ty, $election_type:ty, $field:ident) => {
         fn vote_validation(
             request: $request_type,
             election: $election_type,
-        ) -> Result<(), crate::routes::api::common::NewVoteError> {
-            use crate::routes::api::common::NewVoteError;
-            if election.options.len() != request.votes.len() {
+        ) -> Result<(), $crate::routes::api::common::NewVoteError> {
+            use $crate::routes::api::common::NewVoteError;
+            if election.$field.options.len() != request.votes.len() {
                 return Err(NewVoteError::InvalidVoteCount);
             }
 
             if request
                 .votes
                 .iter()
-                .any(|vote| *vote >= election.options.len())
+                .any(|vote| *vote >= election.$field.options.len())
             {
                 return Err(NewVoteError::InvalidVoteOption);
             }
@@ -122,7 +158,7 @@ macro_rules! create_post_election_id_new_vote_endpoint {
             req: actix_web::HttpRequest,
             request: actix_web_validator::Json<$request_type>,
             election_id: actix_web::web::Path<String>,
-            query: actix_web::web::Query<crate::routes::api::common::VoteTokenQuery>,
+            query: actix_web::web::Query<$crate::routes::api::common::VoteTokenQuery>,
         ) -> actix_web::HttpResponse {
             let election_id = $crate::convert_into_uuid_or_fail!(election_id.as_str());
             let vote_token = match query.vote_token.clone() {
@@ -130,9 +166,9 @@ macro_rules! create_post_election_id_new_vote_endpoint {
                 None => None,
             };
 
-            let user_id = crate::routes::auth::get_created_uuid(&req);
+            let user_id = $crate::routes::auth::get_created_uuid(&req);
 
-            use crate::routes::api::common::NewVoteError;
+            use $crate::routes::api::common::NewVoteError;
 
             let result = actix_web::web::block(move || {
                 let mut conn = pool.get().unwrap();
@@ -148,23 +184,28 @@ macro_rules! create_post_election_id_new_vote_endpoint {
 
                 let vote_token = vote_token.as_ref();
 
-                if election.election.requires_token
-                    && (vote_token.is_none()
-                        || !crate::routes::auth::is_vote_token_valid(
-                            &mut conn,
-                            &election_id,
-                            vote_token.unwrap(),
-                        ))
-                {
-                    log::info!("Invalid token given");
-                    return Err(NewVoteError::InvalidToken);
-                }
+                // The ballot takes the weight of the token it is cast with
+                let weight = if election.election.requires_token {
+                    match vote_token.and_then(|token| {
+                        $crate::routes::auth::get_vote_token_weight(&mut conn, &election_id, token)
+                    }) {
+                        Some(weight) => weight,
+                        None => {
+                            log::info!("Invalid token given");
+                            return Err(NewVoteError::InvalidToken);
+                        }
+                    }
+                } else {
+                    voting_systems::weight::Weight::ONE
+                };
 
-                if let Err(err) = $validate_vote(&request, &election) {
-                    return Err(err);
-                }
+                $validate_vote(&request, &election)?;
 
-                match add_vote(&mut conn, &election_id, $make_vote(&request, &user_id)) {
+                match add_vote(
+                    &mut conn,
+                    &election_id,
+                    $make_vote(&request, &user_id, weight),
+                ) {
                     Ok(_) => (),
                     Err(err) => match err {
                         AddVoteError::AlreadyVoted => return Err(NewVoteError::AlreadyVoted),
@@ -172,7 +213,7 @@ macro_rules! create_post_election_id_new_vote_endpoint {
                 }
 
                 if let Some(vote_token) = vote_token {
-                    crate::routes::auth::remove_vote_token(&mut conn, &election_id, vote_token);
+                    $crate::routes::auth::remove_vote_token(&mut conn, &election_id, vote_token);
                 }
 
                 Ok(())
@@ -201,6 +242,9 @@ macro_rules! create_post_election_id_new_vote_endpoint {
                     NewVoteError::AlreadyVoted => {
                         HttpResponse::BadRequest().body("already voted").into()
                     }
+                    NewVoteError::RoundClosed => {
+                        HttpResponse::BadRequest().body("round closed").into()
+                    }
                 },
             }
         }
@@ -215,11 +259,59 @@ pub struct OptionalAuth {
 #[macro_export]
 macro_rules! create_get_election_id_get_result_endpoint {
     ($get_election:ident, $get_votes:ident, $get_result:ident) => {
-        #[get("/{election_id}/get_result")]
-        async fn get_election_id_get_result_endpoint(
+        $crate::create_get_election_id_get_result_endpoint!(
+            "/{election_id}/get_result",
+            get_election_id_get_result_endpoint,
+            $crate::routes::api::common::OptionalAuth,
+            $get_election,
+            $get_votes,
+            |election, votes, _query| $crate::routes::api::common::respond_seeded(
+                &election.election,
+                voting_systems::weight::get_weighted_result(votes, |votes| {
+                    $get_result(election, votes)
+                })
+            )
+        );
+        $crate::create_get_election_id_get_delegated_result_endpoint!(
+            $get_election,
+            $get_votes,
+            $get_result
+        );
+    };
+    // For methods that can refuse to count, the error is returned as a bad request
+    ($get_election:ident, $get_votes:ident, $get_result:ident, fallible) => {
+        $crate::create_get_election_id_get_result_endpoint!(
+            "/{election_id}/get_result",
+            get_election_id_get_result_endpoint,
+            $crate::routes::api::common::OptionalAuth,
+            $get_election,
+            $get_votes,
+            |election, votes, _query| match voting_systems::weight::get_weighted_result(
+                votes,
+                |votes| $get_result(election, votes)
+            )
+            .transpose()
+            {
+                Ok(result) => {
+                    $crate::routes::api::common::respond_seeded(&election.election, result)
+                }
+                Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+            }
+        );
+        $crate::create_get_election_id_get_delegated_result_endpoint!(
+            $get_election,
+            $get_votes,
+            $get_result,
+            fallible
+        );
+    };
+    // Extra result endpoints, the query must have an `api_key: Option<String>` field
+    ($path:literal, $endpoint:ident, $query:ty, $get_election:ident, $get_votes:ident, $respond:expr) => {
+        #[get($path)]
+        async fn $endpoint(
             pool: actix_web::web::Data<DbPool>,
             election_id: actix_web::web::Path<String>,
-            query: actix_web::web::Query<crate::routes::api::common::OptionalAuth>,
+            query: actix_web::web::Query<$query>,
         ) -> actix_web::HttpResponse {
             let election_id = $crate::convert_into_uuid_or_fail!(election_id.as_str());
             let api_key = if let Some(api_key) = query.api_key.as_ref() {
@@ -262,9 +354,112 @@ macro_rules! create_get_election_id_get_result_endpoint {
 
             let election = election.unwrap();
 
-            let result = $get_result(&election, &votes);
+            $crate::routes::api::common::respond(&election, &votes, &*query, $respond)
+        }
+    };
+}
+
+// Counts the votes like `get_result` once delegations have been followed,
+// returning the delegation graph with the result
+#[macro_export]
+macro_rules! create_get_election_id_get_delegated_result_endpoint {
+    (@respond $election:ident $delegated:ident) => {
+        $crate::routes::api::common::respond_seeded(&$election.election, $delegated)
+    };
+    (@respond $election:ident $delegated:ident fallible) => {
+        match $delegated.result.transpose() {
+            Ok(result) => $crate::routes::api::common::respond_seeded(
+                &$election.election,
+                voting_systems::delegation::DelegatedResult {
+                    delegation: $delegated.delegation,
+                    result,
+                },
+            ),
+            Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+        }
+    };
+    ($get_election:ident, $get_votes:ident, $get_result:ident $(, $fallible:ident)?) => {
+        $crate::create_get_election_id_get_delegated_result_endpoint!(
+            "/{election_id}/get_result/delegated",
+            get_election_id_get_delegated_result_endpoint,
+            $get_election,
+            $get_votes,
+            $get_result
+            $(, $fallible)?
+        );
+    };
+    // For methods with more than one result endpoint, each delegated result
+    // needs its own path
+    (
+        $path:literal,
+        $endpoint:ident,
+        $get_election:ident,
+        $get_votes:ident,
+        $get_result:ident
+        $(, $fallible:ident)?
+    ) => {
+        #[get($path)]
+        async fn $endpoint(
+            pool: actix_web::web::Data<DbPool>,
+            election_id: actix_web::web::Path<String>,
+            query: actix_web::web::Query<$crate::routes::api::common::OptionalAuth>,
+        ) -> actix_web::HttpResponse {
+            let election_id = $crate::convert_into_uuid_or_fail!(election_id.as_str());
+            let api_key = if let Some(api_key) = query.api_key.as_ref() {
+                Some($crate::convert_into_uuid_or_fail!(api_key.as_str()))
+            } else {
+                None
+            };
+
+            let result = actix_web::web::block(move || {
+                let mut conn = pool.get().unwrap();
+
+                let election = $get_election(&mut conn, &election_id);
+
+                if let Some(election) = election.as_ref() {
+                    if !election.election.public {
+                        if let Some(api_key) = api_key {
+                            $crate::check_key!(&mut conn, &election_id, &api_key);
+                        } else {
+                            return Err($crate::routes::auth::CheckError::InvalidApiKey);
+                        }
+                    }
+                }
+
+                Ok((
+                    election,
+                    $get_votes(&mut conn, &election_id),
+                    $crate::delegation::get_delegations(&mut conn, &election_id),
+                ))
+            })
+            .await
+            .unwrap();
+
+            if let Err(err) = result {
+                $crate::handle_check_error!(err);
+            }
+
+            let (election, votes, delegations) = result.unwrap();
+
+            let election = match election {
+                Some(election) => election,
+                None => {
+                    return HttpResponse::NotFound()
+                        .body("election doesn't exist")
+                        .into()
+                }
+            };
 
-            HttpResponse::Ok().json(result).into()
+            let delegated =
+                voting_systems::delegation::get_delegated_result(&votes, &delegations, |votes| {
+                    voting_systems::weight::get_weighted_result(votes, |votes| {
+                        $get_result(&election, votes)
+                    })
+                });
+            $crate::create_get_election_id_get_delegated_result_endpoint!(
+                @respond election delegated $($fallible)?
+            )
+            .into()
         }
     };
 }
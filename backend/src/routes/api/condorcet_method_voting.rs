@@ -8,11 +8,16 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{
+    condorcet_method::CondorcetMethodVote, copeland::is_valid_tie_score, minimax::MinimaxVariant,
+    tie_break::TieBreak, weight::Weight,
+};
 
 use crate::{
     condorcet_method::{
-        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        CondorcetMethodCreateElection, CondorcetMethodElection, CondorcetMethodVote,
+        add_election, add_vote, get_black_result, get_copeland_result, get_election,
+        get_minimax_result, get_result, get_votes, AddVoteError, CondorcetMethodCreateElection,
+        CondorcetMethodElectionModeled, CondorcetMethodVoteModeled,
     },
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
     create_post_endpoint, create_ranked_choice_vote_validation,
@@ -27,16 +32,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<CondorcetMethodCreateElection> for CreateElectionRequest {
-    fn into(self) -> CondorcetMethodCreateElection {
+impl From<CreateElectionRequest> for CondorcetMethodCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         CondorcetMethodCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,6 +59,74 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
+#[derive(Debug, Deserialize)]
+struct CopelandResultQuery {
+    api_key: Option<String>,
+    /// Defaults to half a point for a tied matchup
+    tie_score: Option<f64>,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/copeland",
+    get_election_id_get_copeland_result_endpoint,
+    CopelandResultQuery,
+    get_election,
+    get_votes,
+    |election, votes, query: &CopelandResultQuery| {
+        let tie_score = query.tie_score.unwrap_or(0.5);
+        if !is_valid_tie_score(tie_score) {
+            return HttpResponse::BadRequest().body("tie_score must be from 0 to 1");
+        }
+        crate::routes::api::common::respond_seeded(
+            &election.election,
+            voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_copeland_result(election, votes, tie_score)
+            }),
+        )
+    }
+);
+
+#[derive(Debug, Deserialize)]
+struct MinimaxResultQuery {
+    api_key: Option<String>,
+    /// Defaults to winning votes
+    variant: Option<MinimaxVariant>,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/minimax",
+    get_election_id_get_minimax_result_endpoint,
+    MinimaxResultQuery,
+    get_election,
+    get_votes,
+    |election, votes, query: &MinimaxResultQuery| {
+        crate::routes::api::common::respond_seeded(
+            &election.election,
+            voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_minimax_result(
+                    election,
+                    votes,
+                    query.variant.unwrap_or(MinimaxVariant::WinningVotes),
+                )
+            }),
+        )
+    }
+);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/black",
+    get_election_id_get_black_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_black_result(
+            election, votes
+        ))
+    )
+);
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 struct CreateCondorcetMethodVoteRequest {
     votes: Vec<usize>,
@@ -55,14 +135,20 @@ struct CreateCondorcetMethodVoteRequest {
 fn make_vote(
     request: &CreateCondorcetMethodVoteRequest,
     user_id: &uuid::Uuid,
-) -> CondorcetMethodVote {
-    CondorcetMethodVote {
-        created_by: user_id.clone(),
+    weight: Weight,
+) -> CondorcetMethodVoteModeled {
+    CondorcetMethodVoteModeled(CondorcetMethodVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
-create_ranked_choice_vote_validation!(&CreateCondorcetMethodVoteRequest, &CondorcetMethodElection);
+create_ranked_choice_vote_validation!(
+    &CreateCondorcetMethodVoteRequest,
+    &CondorcetMethodElectionModeled,
+    condorcet_method_election
+);
 
 create_post_election_id_new_vote_endpoint!(
     CreateCondorcetMethodVoteRequest,
@@ -77,5 +163,9 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_copeland_result_endpoint)
+        .service(get_election_id_get_minimax_result_endpoint)
+        .service(get_election_id_get_black_result_endpoint)
         .service(post_election_id_new_vote)
 }
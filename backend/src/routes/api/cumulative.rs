@@ -8,12 +8,13 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{cumulative::CumulativeVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     convert_into_uuid_or_fail, create_post_election_id_new_vote_endpoint, create_post_endpoint,
     cumulative::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        CumulativeCreateElection, CumulativeElection, CumulativeVote,
+        CumulativeCreateElection, CumulativeElectionModeled, CumulativeVoteModeled,
     },
     db::DbPool,
     elections::CreateElection,
@@ -26,19 +27,26 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
     #[validate(range(min = 1, max = 100))]
     max_votes: usize,
 }
 
-impl Into<CumulativeCreateElection> for CreateElectionRequest {
-    fn into(self) -> CumulativeCreateElection {
+impl From<CreateElectionRequest> for CumulativeCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         CumulativeCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
-            max_votes: self.max_votes,
+            options: val.options,
+            max_votes: val.max_votes,
         }
     }
 }
@@ -73,14 +81,12 @@ async fn get_election_id_endpoint(
             .json(PublicCumulativeElection {
                 id: election.election.id.to_string(),
                 title: election.election.title,
-                options: election.options,
+                options: election.cumulative_election.options,
                 require_token: election.election.requires_token,
-                max_votes: election.max_votes,
-            })
-            .into(),
+                max_votes: election.cumulative_election.max_votes,
+            }),
         None => actix_web::HttpResponse::NotFound()
-            .body("election doesn't exist")
-            .into(),
+            .body("election doesn't exist"),
     }
 }
 
@@ -92,23 +98,28 @@ struct CumulativeVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &CumulativeVoteRequest, user_id: &uuid::Uuid) -> CumulativeVote {
-    CumulativeVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &CumulativeVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> CumulativeVoteModeled {
+    CumulativeVoteModeled(CumulativeVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
 fn vote_validation(
     request: &CumulativeVoteRequest,
-    election: &CumulativeElection,
+    election: &CumulativeElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.cumulative_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
-    if request.votes.iter().sum::<usize>() > election.max_votes {
+    if request.votes.iter().sum::<usize>() > election.cumulative_election.max_votes {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -128,5 +139,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{delegation::Delegation, weight::Weight};
+
+use crate::{
+    convert_into_uuid_or_fail,
+    db::DbPool,
+    delegation::{add_delegation, AddDelegationError},
+    elections,
+    routes::{api::common::VoteTokenQuery, auth},
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct DelegateRequest {
+    /// The voter identifier to hand this voter's vote to
+    pub delegate: String,
+}
+
+#[derive(Debug)]
+pub enum DelegateError {
+    ElectionNotFound,
+    InvalidToken,
+    AlreadyDelegated,
+    Cycle,
+}
+
+/// Hands the caller's vote to another voter instead of voting. Uses up the
+/// vote token the same way casting a ballot does
+#[post("/{election_id}/delegate")]
+async fn post_election_id_delegate(
+    pool: web::Data<DbPool>,
+    req: HttpRequest,
+    request: actix_web_validator::Json<DelegateRequest>,
+    election_id: web::Path<String>,
+    query: web::Query<VoteTokenQuery>,
+) -> HttpResponse {
+    let election_id = convert_into_uuid_or_fail!(election_id.as_str());
+    let delegate = convert_into_uuid_or_fail!(request.delegate.as_str());
+    let vote_token = match query.vote_token.as_ref() {
+        Some(token) => Some(convert_into_uuid_or_fail!(token.as_str())),
+        None => None,
+    };
+
+    let user_id = auth::get_created_uuid(&req);
+    if user_id == delegate {
+        return HttpResponse::BadRequest().body("can't delegate to yourself");
+    }
+    info!("Got new delegation for {}", election_id);
+
+    let result = web::block(move || {
+        let mut conn = pool.get().unwrap();
+
+        let election = match elections::get_election(&mut conn, &election_id) {
+            Some(election) if !election.voting_locked => election,
+            _ => return Err(DelegateError::ElectionNotFound),
+        };
+
+        let vote_token = vote_token.as_ref();
+        // The delegate carries the weight of the delegator's token
+        let weight = if election.requires_token {
+            match vote_token
+                .and_then(|token| auth::get_vote_token_weight(&mut conn, &election_id, token))
+            {
+                Some(weight) => weight,
+                None => return Err(DelegateError::InvalidToken),
+            }
+        } else {
+            Weight::ONE
+        };
+
+        let delegation = Delegation {
+            delegator: user_id,
+            delegate,
+            weight,
+        };
+        match add_delegation(&mut conn, &election_id, delegation) {
+            Ok(_) => (),
+            Err(AddDelegationError::AlreadyDelegated) => {
+                return Err(DelegateError::AlreadyDelegated)
+            }
+            Err(AddDelegationError::Cycle) => return Err(DelegateError::Cycle),
+        }
+
+        if let Some(vote_token) = vote_token {
+            auth::remove_vote_token(&mut conn, &election_id, vote_token);
+        }
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => match err {
+            DelegateError::ElectionNotFound => {
+                HttpResponse::NotFound().body("election doesn't exist")
+            }
+            DelegateError::InvalidToken => HttpResponse::Unauthorized().body("invalid token"),
+            DelegateError::AlreadyDelegated => HttpResponse::BadRequest().body("already delegated"),
+            DelegateError::Cycle => HttpResponse::BadRequest().body("delegation cycle"),
+        },
+    }
+}
@@ -0,0 +1,225 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{
+    hare_clark::{robson_rotation, rotation_count, HareClarkVote},
+    tie_break::TieBreak,
+    weight::Weight,
+};
+
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint,
+    db::DbPool,
+    elections::CreateElection,
+    hare_clark::{
+        add_election, add_vote, get_countback, get_election, get_next_rotation, get_result,
+        get_votes, AddVoteError, HareClarkCreateElection, HareClarkElectionModeled,
+        HareClarkVoteModeled, PublicHareClarkElection,
+    },
+    routes::api::common::NewVoteError,
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_vacancies", skip_on_field_errors = false))]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    #[validate(range(min = 1, max = 100))]
+    vacancies: u32,
+}
+
+fn validate_vacancies(request: &CreateElectionRequest) -> Result<(), validator::ValidationError> {
+    if request.vacancies as usize >= request.options.len() {
+        return Err(validator::ValidationError::new(
+            "vacancies must be fewer than the options",
+        ));
+    }
+    Ok(())
+}
+
+impl From<CreateElectionRequest> for HareClarkCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        HareClarkCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+            vacancies: val.vacancies as usize,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicHareClarkElection);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BallotPaper {
+    rotation: usize,
+    /// The order to print the options in
+    order: Vec<usize>,
+}
+
+/// Issues the voter a ballot paper in the next Robson rotation, the rotation
+/// is sent back with their vote
+#[get("/{election_id}/ballot")]
+async fn get_election_id_ballot_endpoint(
+    pool: web::Data<DbPool>,
+    election_id: web::Path<String>,
+) -> HttpResponse {
+    let election_id = crate::convert_into_uuid_or_fail!(election_id.as_str());
+
+    let ballot = web::block(move || {
+        let mut conn = pool.get().unwrap();
+        let election = get_election(&mut conn, &election_id)?;
+        let rotation = get_next_rotation(&mut conn, &election);
+        Some(BallotPaper {
+            rotation,
+            order: robson_rotation(election.hare_clark_election.options.len(), rotation),
+        })
+    })
+    .await
+    .unwrap();
+
+    match ballot {
+        Some(ballot) => HttpResponse::Ok().json(ballot),
+        None => HttpResponse::NotFound().body("election doesn't exist"),
+    }
+}
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug, Deserialize)]
+struct CountbackQuery {
+    api_key: Option<String>,
+    /// The elected candidate leaving their seat
+    vacating: usize,
+    /// Comma separated candidates standing for the seat, defaults to every
+    /// candidate not elected
+    contesting: Option<String>,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/countback",
+    get_election_id_get_countback_result_endpoint,
+    CountbackQuery,
+    get_election,
+    get_votes,
+    |election: &HareClarkElectionModeled, votes: &Vec<HareClarkVote>, query: &CountbackQuery| {
+        let contesting = match query.contesting.as_ref() {
+            Some(contesting) => {
+                match contesting
+                    .split(",")
+                    .map(|i| i.parse::<usize>())
+                    .collect::<Result<Vec<usize>, _>>()
+                {
+                    Ok(contesting) => contesting,
+                    Err(_) => {
+                        return HttpResponse::BadRequest()
+                            .body("contesting must be a comma separated list of numbers");
+                    }
+                }
+            }
+            None => {
+                let result = get_result(election, votes);
+                (0..election.hare_clark_election.options.len())
+                    .filter(|i| !result.elected.contains(i))
+                    .collect()
+            }
+        };
+
+        match voting_systems::weight::get_weighted_result(votes, |votes| {
+            get_countback(election, votes, query.vacating, &contesting)
+        })
+        .transpose()
+        {
+            Ok(result) => crate::routes::api::common::respond_seeded(&election.election, result),
+            Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+        }
+    }
+);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateHareClarkVoteRequest {
+    /// Options in order of preference
+    votes: Vec<usize>,
+    /// The rotation of the ballot paper the voter was issued
+    rotation: usize,
+}
+
+fn vote_validation(
+    request: &CreateHareClarkVoteRequest,
+    election: &HareClarkElectionModeled,
+) -> Result<(), NewVoteError> {
+    let option_count = election.hare_clark_election.options.len();
+
+    // Voters have to number at least as many boxes as there are vacancies
+    if request.votes.len() < election.hare_clark_election.vacancies.min(option_count)
+        || request.votes.len() > option_count
+    {
+        return Err(NewVoteError::InvalidVoteCount);
+    }
+
+    if request.votes.iter().any(|vote| *vote >= option_count)
+        || request.rotation >= rotation_count(option_count)
+    {
+        return Err(NewVoteError::InvalidVoteOption);
+    }
+
+    if (1..request.votes.len()).any(|i| request.votes[i..].contains(&request.votes[i - 1])) {
+        return Err(NewVoteError::DuplicatedVote);
+    }
+
+    Ok(())
+}
+
+fn make_vote(
+    request: &CreateHareClarkVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> HareClarkVoteModeled {
+    HareClarkVoteModeled(HareClarkVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+        rotation: request.rotation,
+    })
+}
+
+create_post_election_id_new_vote_endpoint!(
+    CreateHareClarkVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/hare_clark")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_ballot_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_countback_result_endpoint)
+        .service(post_election_id_new_vote)
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{kemeny_young::KemenyYoungVote, tie_break::TieBreak, weight::Weight};
+
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint, create_ranked_choice_vote_validation,
+    db::DbPool,
+    elections::CreateElection,
+    kemeny_young::{
+        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
+        KemenyYoungCreateElection, KemenyYoungElectionModeled, KemenyYoungVoteModeled,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 1, max = 12))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+}
+
+impl From<CreateElectionRequest> for KemenyYoungCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        KemenyYoungCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result, fallible);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateKemenyYoungVoteRequest {
+    votes: Vec<usize>,
+}
+
+fn make_vote(
+    request: &CreateKemenyYoungVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> KemenyYoungVoteModeled {
+    KemenyYoungVoteModeled(KemenyYoungVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
+}
+
+create_ranked_choice_vote_validation!(
+    &CreateKemenyYoungVoteRequest,
+    &KemenyYoungElectionModeled,
+    kemeny_young_election
+);
+
+create_post_election_id_new_vote_endpoint!(
+    CreateKemenyYoungVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/kemeny_young")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(post_election_id_new_vote)
+}
@@ -8,6 +8,11 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
+use voting_systems::{
+    majority_judgment::{MajorityJudgmentVote, Rating},
+    tie_break::TieBreak,
+    weight::Weight,
+};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -16,7 +21,8 @@ use crate::{
     elections::CreateElection,
     majority_judgment::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        MajorityJudgmentCreateElection, MajorityJudgmentElection, MajorityJudgmentVote, Rating,
+        MajorityJudgmentCreateElection, MajorityJudgmentElectionModeled,
+        MajorityJudgmentVoteModeled,
     },
 };
 
@@ -27,16 +33,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<MajorityJudgmentCreateElection> for CreateElectionRequest {
-    fn into(self) -> MajorityJudgmentCreateElection {
+impl From<CreateElectionRequest> for MajorityJudgmentCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         MajorityJudgmentCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,7 +60,7 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
-pub fn validate_majority_judgment_votes(votes: &Vec<usize>) -> Result<(), ValidationError> {
+pub fn validate_majority_judgment_votes(votes: &[usize]) -> Result<(), ValidationError> {
     if votes.iter().any(|i| *i > 5) {
         return Err(ValidationError::new(
             "invalid three_two_one must be between 0 and 5",
@@ -66,19 +79,24 @@ struct MajorityJudgmentVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &MajorityJudgmentVoteRequest, user_id: &uuid::Uuid) -> MajorityJudgmentVote {
-    MajorityJudgmentVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &MajorityJudgmentVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> MajorityJudgmentVoteModeled {
+    MajorityJudgmentVoteModeled(MajorityJudgmentVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.iter().map(|i| (*i as i32).into()).collect(),
-    }
+    })
 }
 
 fn vote_validation(
     request: &MajorityJudgmentVoteRequest,
-    election: &MajorityJudgmentElection,
+    election: &MajorityJudgmentElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.majority_judgment_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -105,5 +123,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -5,6 +5,7 @@ use validator::Validate;
 use actix_web::{get, HttpResponse};
 use log::info;
 use serde::{Deserialize, Serialize};
+use voting_systems::weight::Weight;
 
 use crate::{
     check_key, convert_into_uuid_or_fail,
@@ -17,23 +18,43 @@ use crate::{
 mod anti_plurality_voting;
 mod approval_voting;
 mod borda_count_voting;
+mod bucklin_voting;
 mod common;
 mod condorcet_method_voting;
 mod cumulative;
+mod delegation;
+mod hare_clark_voting;
+mod kemeny_young_voting;
 mod majority_judgment_voting;
+mod participatory_budgeting_voting;
+mod party_list_voting;
 mod preferential_voting;
+mod quadratic_voting;
 mod quota_preferential_vic_labor_2024;
+mod schulze_voting;
 mod score_voting;
+mod seed;
 mod single_non_transferable_voting;
 mod single_party_voting;
 mod single_transferable_vote;
 mod star_voting;
 mod three_two_one_vote;
+mod two_round_voting;
 mod usual_judgment_voting;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct MakeElectionTokenResponse {
     token: String,
+    weight: Weight,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetTokenQuery {
+    api_key: String,
+    /// What the ballot cast with the token is worth, a whole number or a
+    /// fraction like 5/2, one when not given. Neither part can be above
+    /// `weight::MAX_PART` so the weight always fits the token's columns
+    weight: Option<String>,
 }
 
 #[derive(Debug)]
@@ -47,11 +68,16 @@ pub enum GetTokenError {
 async fn get_token(
     pool: web::Data<DbPool>,
     election_id: web::Path<String>,
-    auth: web::Query<Auth>,
+    query: web::Query<GetTokenQuery>,
 ) -> HttpResponse {
     uuid::Uuid::from_str(election_id.as_str()).unwrap();
     let election_id = convert_into_uuid_or_fail!(election_id.as_str());
-    let api_key = convert_into_uuid_or_fail!(auth.api_key.as_str());
+    let api_key = convert_into_uuid_or_fail!(query.api_key.as_str());
+    let weight = match query.weight.as_deref().map(Weight::from_str) {
+        None => Weight::ONE,
+        Some(Ok(weight)) => weight,
+        Some(Err(err)) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
     info!("Got new request to make a token for {}", election_id);
 
     let result = web::block(move || {
@@ -68,7 +94,7 @@ async fn get_token(
             return Err(GetTokenError::ElectionDoesNotRequireToken);
         }
 
-        Ok(create_election_token(&mut conn, &election_id))
+        Ok(create_election_token(&mut conn, &election_id, weight))
     })
     .await
     .unwrap();
@@ -76,6 +102,7 @@ async fn get_token(
     match result {
         Ok(token) => HttpResponse::Ok().json(MakeElectionTokenResponse {
             token: token.to_string(),
+            weight,
         }),
         Err(err) => match err {
             GetTokenError::NoElection => HttpResponse::NotFound().finish(),
@@ -135,12 +162,18 @@ async fn get_extra(
 pub struct ChangePublic {
     pub new_public: Option<bool>,
     pub new_voting_locked: Option<bool>,
+    /// The seed secret behind the commitment, needed to lock voting unless
+    /// the server made up the secret
+    #[validate(length(max = 100))]
+    pub seed_secret: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum ChangeExtraError {
     NoElection,
     CheckError(crate::routes::auth::CheckError),
+    RevealSeed(crate::seed::RevealSeedError),
+    SeedRevealed,
 }
 
 #[patch("/{election_id}/change_extra")]
@@ -170,11 +203,22 @@ async fn election_id_change_extra(
         );
         info!("api key checked");
 
-        match elections::get_election(&mut conn, &election_id) {
+        let election = match elections::get_election(&mut conn, &election_id) {
             Some(election) => election,
             None => return Err(ChangeExtraError::NoElection),
         };
 
+        // Votes cast after the seed is known could be chosen to steer the draw
+        if request.new_voting_locked == Some(false) && election.seed_revealed {
+            return Err(ChangeExtraError::SeedRevealed);
+        }
+
+        // The seed is revealed as voting locks, so a wrong secret leaves it unlocked
+        if request.new_voting_locked == Some(true) {
+            crate::seed::reveal_seed(&mut conn, &election, request.seed_secret.as_deref())
+                .map_err(ChangeExtraError::RevealSeed)?;
+        }
+
         if let Some(new_public) = request.new_public.as_ref() {
             change_election_public(&mut conn, &election_id, *new_public);
         }
@@ -191,6 +235,16 @@ async fn election_id_change_extra(
         Err(err) => match err {
             ChangeExtraError::NoElection => HttpResponse::NotFound().finish(),
             ChangeExtraError::CheckError(err) => handle_check_error!(err),
+            ChangeExtraError::RevealSeed(err) => match err {
+                crate::seed::RevealSeedError::SecretRequired => {
+                    HttpResponse::BadRequest().body("seed secret required to lock voting")
+                }
+                crate::seed::RevealSeedError::Seed(err) => {
+                    HttpResponse::BadRequest().body(err.to_string())
+                }
+            },
+            ChangeExtraError::SeedRevealed => HttpResponse::BadRequest()
+                .body("voting can't be unlocked once the seed is revealed"),
         },
     }
 }
@@ -200,6 +254,8 @@ pub fn routes() -> Scope {
         .service(get_token)
         .service(get_extra)
         .service(election_id_change_extra)
+        .service(delegation::post_election_id_delegate)
+        .service(seed::post_election_id_entropy)
         .service(preferential_voting::routes())
         .service(single_transferable_vote::routes())
         .service(borda_count_voting::routes())
@@ -215,4 +271,12 @@ pub fn routes() -> Scope {
         .service(usual_judgment_voting::routes())
         .service(single_non_transferable_voting::routes())
         .service(quota_preferential_vic_labor_2024::routes())
+        .service(schulze_voting::routes())
+        .service(kemeny_young_voting::routes())
+        .service(party_list_voting::routes())
+        .service(participatory_budgeting_voting::routes())
+        .service(bucklin_voting::routes())
+        .service(two_round_voting::routes())
+        .service(hare_clark_voting::routes())
+        .service(quadratic_voting::routes())
 }
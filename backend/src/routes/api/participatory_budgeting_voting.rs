@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+use voting_systems::{participatory_budgeting::BudgetingVote, tie_break::TieBreak, weight::Weight};
+
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint,
+    db::DbPool,
+    elections::CreateElection,
+    participatory_budgeting::{
+        add_election, add_vote, get_election, get_greedy_result, get_result, get_votes,
+        AddVoteError, ParticipatoryBudgetingCreateElection, ParticipatoryBudgetingElectionModeled,
+        ParticipatoryBudgetingVoteModeled, PublicParticipatoryBudgetingElection,
+    },
+};
+
+fn validate_election_request(election: &CreateElectionRequest) -> Result<(), ValidationError> {
+    if election.costs.len() != election.options.len() {
+        return Err(ValidationError::new("costs must have one entry per option"));
+    }
+    if election
+        .costs
+        .iter()
+        .any(|i| *i == 0 || *i > election.budget)
+    {
+        return Err(ValidationError::new(
+            "costs must be more than 0 and no more than the budget",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_election_request", skip_on_field_errors = false))]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    costs: Vec<u64>,
+    #[validate(range(min = 1, max = 1_000_000_000_000))]
+    budget: u64,
+    /// 1 for approval ballots
+    #[validate(range(min = 1, max = 100))]
+    max_score: usize,
+}
+
+impl From<CreateElectionRequest> for ParticipatoryBudgetingCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        ParticipatoryBudgetingCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+            costs: val.costs,
+            budget: val.budget,
+            max_score: val.max_score,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicParticipatoryBudgetingElection);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/greedy",
+    get_election_id_get_greedy_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_greedy_result(
+            election, votes
+        ))
+    )
+);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct ParticipatoryBudgetingVoteRequest {
+    #[validate(length(min = 2, max = 100))]
+    votes: Vec<usize>,
+}
+
+fn make_vote(
+    request: &ParticipatoryBudgetingVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> ParticipatoryBudgetingVoteModeled {
+    ParticipatoryBudgetingVoteModeled(BudgetingVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
+}
+
+fn vote_validation(
+    request: &ParticipatoryBudgetingVoteRequest,
+    election: &ParticipatoryBudgetingElectionModeled,
+) -> Result<(), crate::routes::api::common::NewVoteError> {
+    use crate::routes::api::common::NewVoteError;
+    let budgeting_election = &election.participatory_budgeting_election;
+    if budgeting_election.options.len() != request.votes.len() {
+        return Err(NewVoteError::InvalidVoteCount);
+    }
+
+    if request
+        .votes
+        .iter()
+        .any(|i| *i > budgeting_election.max_score)
+    {
+        return Err(NewVoteError::InvalidVoteOption);
+    }
+
+    Ok(())
+}
+
+create_post_election_id_new_vote_endpoint!(
+    ParticipatoryBudgetingVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/participatory_budgeting")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_greedy_result_endpoint)
+        .service(post_election_id_new_vote)
+}
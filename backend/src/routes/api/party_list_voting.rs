@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{
+    party_list::PartyListMethod, single_non_transferable_vote::SNTVVote, tie_break::TieBreak,
+    weight::Weight,
+};
+
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint,
+    db::DbPool,
+    elections::CreateElection,
+    party_list::{
+        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
+        PartyListCreateElection, PartyListElectionModeled, PartyListVoteModeled,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 1, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    #[validate(range(min = 1, max = 1000))]
+    seat_count: usize,
+    method: PartyListMethod,
+    /// Percentage of the vote a party needs to win seats
+    #[validate(range(min = 0.0, max = 100.0))]
+    threshold: Option<f64>,
+}
+
+impl From<CreateElectionRequest> for PartyListCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        PartyListCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+            seat_count: val.seat_count,
+            method: val.method,
+            threshold: val.threshold,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct PartyListVoteRequest {
+    votes: Vec<bool>,
+}
+
+fn make_vote(
+    request: &PartyListVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> PartyListVoteModeled {
+    PartyListVoteModeled(SNTVVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
+}
+
+fn vote_validation(
+    request: &PartyListVoteRequest,
+    election: &PartyListElectionModeled,
+) -> Result<(), crate::routes::api::common::NewVoteError> {
+    use crate::routes::api::common::NewVoteError;
+    if election.party_list_election.options.len() != request.votes.len() {
+        return Err(NewVoteError::InvalidVoteCount);
+    }
+
+    if request.votes.iter().filter(|x| **x).count() != 1 {
+        return Err(NewVoteError::InvalidVoteCount);
+    }
+
+    Ok(())
+}
+
+create_post_election_id_new_vote_endpoint!(
+    PartyListVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/party_list")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(post_election_id_new_vote)
+}
@@ -8,6 +8,10 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{
+    preferential_voting::PreferentialVote, sequential_elimination::EliminationMethod,
+    tie_break::TieBreak, weight::Weight,
+};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -15,8 +19,9 @@ use crate::{
     db::DbPool,
     elections::CreateElection,
     preferential_voting::{
-        add_election, add_vote, get_election, get_election_winner, get_votes, AddVoteError,
-        PreferentialCreateElection, PreferentialElection, PreferentialVote,
+        add_election, add_vote, get_contingent_result, get_election, get_election_winner,
+        get_elimination_result, get_votes, AddVoteError, PreferentialCreateElection,
+        PreferentialElectionModeled, PreferentialVoteModeled,
     },
 };
 
@@ -27,16 +32,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<PreferentialCreateElection> for CreateElectionRequest {
-    fn into(self) -> PreferentialCreateElection {
+impl From<CreateElectionRequest> for PreferentialCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         PreferentialCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,19 +59,106 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_election_winner);
 
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/baldwin",
+    get_election_id_get_baldwin_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_elimination_result(
+            election,
+            votes,
+            EliminationMethod::Baldwin
+        ))
+    )
+);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/nanson",
+    get_election_id_get_nanson_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_elimination_result(
+            election,
+            votes,
+            EliminationMethod::Nanson
+        ))
+    )
+);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/coombs",
+    get_election_id_get_coombs_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_elimination_result(
+            election,
+            votes,
+            EliminationMethod::Coombs
+        ))
+    )
+);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/tideman_alternative",
+    get_election_id_get_tideman_alternative_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_elimination_result(
+            election,
+            votes,
+            EliminationMethod::TidemanAlternative
+        ))
+    )
+);
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/contingent",
+    get_election_id_get_contingent_result_endpoint,
+    crate::routes::api::common::OptionalAuth,
+    get_election,
+    get_votes,
+    |election, votes, _query| crate::routes::api::common::respond_seeded(
+        &election.election,
+        voting_systems::weight::get_weighted_result(votes, |votes| get_contingent_result(
+            election, votes
+        ))
+    )
+);
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 struct CreatePreferentialVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &CreatePreferentialVoteRequest, user_id: &uuid::Uuid) -> PreferentialVote {
-    PreferentialVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &CreatePreferentialVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> PreferentialVoteModeled {
+    PreferentialVoteModeled(PreferentialVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
-create_ranked_choice_vote_validation!(&CreatePreferentialVoteRequest, &PreferentialElection);
+create_ranked_choice_vote_validation!(
+    &CreatePreferentialVoteRequest,
+    &PreferentialElectionModeled,
+    preferential_election
+);
 
 create_post_election_id_new_vote_endpoint!(
     CreatePreferentialVoteRequest,
@@ -69,18 +168,16 @@ create_post_election_id_new_vote_endpoint!(
     add_vote
 );
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PublicElection {
-    pub id: String,
-    pub title: String,
-    pub options: Vec<String>,
-    pub require_token: bool,
-}
-
 pub fn routes() -> Scope {
     web::scope("/preferential_voting")
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_baldwin_result_endpoint)
+        .service(get_election_id_get_nanson_result_endpoint)
+        .service(get_election_id_get_coombs_result_endpoint)
+        .service(get_election_id_get_tideman_alternative_result_endpoint)
+        .service(get_election_id_get_contingent_result_endpoint)
         .service(post_election_id_new_vote)
 }
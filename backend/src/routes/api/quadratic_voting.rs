@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{
+    quadratic::{validate_vote, QuadraticVote, QuadraticVoteError},
+    tie_break::TieBreak,
+    weight::Weight,
+};
+
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint,
+    db::DbPool,
+    elections::CreateElection,
+    quadratic::{
+        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
+        PublicQuadraticElection, QuadraticCreateElection, QuadraticElectionModeled,
+        QuadraticVoteModeled,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    /// Credits each voter can spend
+    #[validate(range(min = 1, max = 10000))]
+    credits: usize,
+    /// Whether voters can spend credits against an option
+    #[serde(default)]
+    allow_negative: bool,
+}
+
+impl From<CreateElectionRequest> for QuadraticCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        QuadraticCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+            credits: val.credits,
+            allow_negative: val.allow_negative,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicQuadraticElection);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct QuadraticVoteRequest {
+    #[validate(length(min = 2, max = 100))]
+    votes: Vec<i64>,
+}
+
+fn make_vote(
+    request: &QuadraticVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> QuadraticVoteModeled {
+    QuadraticVoteModeled(QuadraticVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
+}
+
+fn vote_validation(
+    request: &QuadraticVoteRequest,
+    election: &QuadraticElectionModeled,
+) -> Result<(), crate::routes::api::common::NewVoteError> {
+    use crate::routes::api::common::NewVoteError;
+    match validate_vote(&election.quadratic_election, &request.votes) {
+        Ok(_) => Ok(()),
+        Err(QuadraticVoteError::NegativeVote { .. }) => Err(NewVoteError::InvalidVoteOption),
+        Err(QuadraticVoteError::WrongOptionCount { .. })
+        | Err(QuadraticVoteError::OverBudget { .. }) => Err(NewVoteError::InvalidVoteCount),
+    }
+}
+
+create_post_election_id_new_vote_endpoint!(
+    QuadraticVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/quadratic")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(post_election_id_new_vote)
+}
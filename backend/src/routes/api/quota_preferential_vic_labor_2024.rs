@@ -1,13 +1,32 @@
+use std::str::FromStr;
+
 use actix_web::{
-    post,
+    get, post,
     web::{self},
     HttpResponse, Scope,
 };
+use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
-use voting_systems::quota_preferential_vic_labor_2024::{get_result, ElectionResult, Vote};
+use voting_systems::quota_preferential_vic_labor_2024::{ElectionResult, Vote};
+
+use voting_systems::numeric::Arithmetic;
+use voting_systems::quota_preferential_vic_labor_2024::{Candidate, Election, Quota};
+use voting_systems::tie_break::TieBreak;
+use voting_systems::weight::Weight;
 
-use voting_systems::quota_preferential_vic_labor_2024::{Candidate, Election};
+use crate::{
+    create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
+    create_post_endpoint,
+    db::DbPool,
+    elections::CreateElection,
+    quota_preferential_vic_labor_2024::{
+        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
+        PublicVicLaborElection, VicLaborCreateCandidate, VicLaborCreateElection,
+        VicLaborElectionModeled, VicLaborVoteModeled,
+    },
+    routes::api::common::NewVoteError,
+};
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CandidateRequest {
@@ -34,18 +53,33 @@ pub struct ElectionRequest {
     percent_female: f64,
     #[validate(range(min = 1, max = 100))]
     elected_count: usize,
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    seed: String,
+    #[serde(default)]
+    tie_break: TieBreak,
+    /// Whole points rounded down as the rules say when left out
+    #[serde(default)]
+    arithmetic: Option<Arithmetic>,
+    /// Droop rounded up as rule 2.3 says when left out
+    #[serde(default)]
+    quota: Quota,
 }
 
-impl Into<Election> for ElectionRequest {
-    fn into(self) -> Election {
+impl From<ElectionRequest> for Election {
+    fn from(val: ElectionRequest) -> Self {
         Election {
-            candidates: self
+            candidates: val
                 .candidates
                 .into_iter()
                 .map(|c| Candidate::new(c.name, c.is_female))
                 .collect(),
-            percent_female: self.percent_female,
-            elected_count: self.elected_count,
+            percent_female: val.percent_female,
+            elected_count: val.elected_count,
+            seed: val.seed,
+            tie_break: val.tie_break,
+            arithmetic: val.arithmetic.unwrap_or(Election::WHOLE_POINTS),
+            quota: val.quota,
         }
     }
 }
@@ -105,7 +139,7 @@ async fn post_test_election(
 
     let election: Election = request.election.into();
 
-    let result = get_result(&election, &votes);
+    let result = voting_systems::quota_preferential_vic_labor_2024::get_result(&election, &votes);
 
     HttpResponse::Ok().json(ElectionResultResponse {
         vote_count: votes.len(),
@@ -115,6 +149,126 @@ async fn post_test_election(
     })
 }
 
+fn validate_create_election_request(
+    request: &CreateElectionRequest,
+) -> Result<(), ValidationError> {
+    if request.elected_count > request.candidates.len() {
+        return Err(ValidationError::new(
+            "elected_count must be less than or equal to the number of candidates",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(
+    function = "validate_create_election_request",
+    skip_on_field_errors = false
+))]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    #[validate]
+    candidates: Vec<CandidateRequest>,
+    require_token: bool,
+    #[validate(range(min = 0.0, max = 1.0))]
+    percent_female: f64,
+    #[validate(range(min = 1, max = 100))]
+    elected_count: usize,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+    /// Whole points rounded down as the rules say when left out
+    #[serde(default)]
+    arithmetic: Option<Arithmetic>,
+    /// Droop rounded up as rule 2.3 says when left out
+    #[serde(default)]
+    quota: Quota,
+}
+
+impl From<CreateElectionRequest> for VicLaborCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        VicLaborCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            candidates: val
+                .candidates
+                .into_iter()
+                .map(|c| VicLaborCreateCandidate {
+                    name: c.name,
+                    is_female: c.is_female,
+                })
+                .collect(),
+            percent_female: val.percent_female,
+            elected_count: val.elected_count,
+            quota: val.quota,
+            arithmetic: val.arithmetic.unwrap_or(Election::WHOLE_POINTS),
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicVicLaborElection);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateVicLaborVoteRequest {
+    /// Candidates in order of preference, numbering every box is optional
+    votes: Vec<usize>,
+}
+
+fn vote_validation(
+    request: &CreateVicLaborVoteRequest,
+    election: &VicLaborElectionModeled,
+) -> Result<(), NewVoteError> {
+    let candidate_count = election.vic_labor_election.candidates.len();
+
+    if request.votes.is_empty() || request.votes.len() > candidate_count {
+        return Err(NewVoteError::InvalidVoteCount);
+    }
+
+    if request.votes.iter().any(|vote| *vote >= candidate_count) {
+        return Err(NewVoteError::InvalidVoteOption);
+    }
+
+    if (1..request.votes.len()).any(|i| request.votes[i..].contains(&request.votes[i - 1])) {
+        return Err(NewVoteError::DuplicatedVote);
+    }
+
+    Ok(())
+}
+
+fn make_vote(
+    request: &CreateVicLaborVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> VicLaborVoteModeled {
+    VicLaborVoteModeled(Vote::new(*user_id, request.votes.clone()).with_weight(weight))
+}
+
+create_post_election_id_new_vote_endpoint!(
+    CreateVicLaborVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
 pub fn routes() -> Scope {
-    web::scope("/quota_preferential_vic_labor_2024").service(post_test_election)
+    web::scope("/quota_preferential_vic_labor_2024")
+        .service(post_test_election)
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(post_election_id_new_vote)
 }
@@ -8,6 +8,10 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
+use voting_systems::{
+    proportional_score::ProportionalScoreMethod, score::ScoreVote, tie_break::TieBreak,
+    weight::Weight,
+};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -15,8 +19,9 @@ use crate::{
     db::DbPool,
     elections::CreateElection,
     score::{
-        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        PublicScoreElection, ScoreCreateElection, ScoreElection, ScoreVote,
+        add_election, add_vote, get_election, get_proportional_result, get_result, get_votes,
+        AddVoteError, PublicScoreElection, ScoreCreateElection, ScoreElectionModeled,
+        ScoreVoteModeled,
     },
 };
 
@@ -27,19 +32,26 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
     #[validate(range(min = 2, max = 100))]
     max_score: usize,
 }
 
-impl Into<ScoreCreateElection> for CreateElectionRequest {
-    fn into(self) -> ScoreCreateElection {
+impl From<CreateElectionRequest> for ScoreCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         ScoreCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
-            max_score: self.max_score,
+            options: val.options,
+            max_score: val.max_score,
         }
     }
 }
@@ -50,7 +62,75 @@ create_get_election_id_endpoint!(get_election, PublicScoreElection);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
-pub fn validate_score_votes(votes: &Vec<usize>) -> Result<(), ValidationError> {
+#[derive(Debug, Deserialize)]
+struct ReweightedRangeResultQuery {
+    api_key: Option<String>,
+    seat_count: usize,
+    /// Defaults to 1
+    k: Option<f64>,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/reweighted_range",
+    get_election_id_get_reweighted_range_result_endpoint,
+    ReweightedRangeResultQuery,
+    get_election,
+    get_votes,
+    |election: &ScoreElectionModeled, votes, query: &ReweightedRangeResultQuery| {
+        let k = query.k.unwrap_or(1.0);
+        if query.seat_count == 0 || query.seat_count > election.score_election.options.len() {
+            return HttpResponse::BadRequest().body("invalid seat count");
+        }
+        if !(k > 0.0 && k.is_finite()) {
+            return HttpResponse::BadRequest().body("k must be more than 0");
+        }
+        crate::routes::api::common::respond_seeded(
+            &election.election,
+            voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_proportional_result(
+                    election,
+                    votes,
+                    ProportionalScoreMethod::ReweightedRange,
+                    query.seat_count,
+                    k,
+                )
+            }),
+        )
+    }
+);
+
+#[derive(Debug, Deserialize)]
+struct SequentiallySpentScoreResultQuery {
+    api_key: Option<String>,
+    seat_count: usize,
+}
+
+crate::create_get_election_id_get_result_endpoint!(
+    "/{election_id}/get_result/sequentially_spent_score",
+    get_election_id_get_sequentially_spent_score_result_endpoint,
+    SequentiallySpentScoreResultQuery,
+    get_election,
+    get_votes,
+    |election: &ScoreElectionModeled, votes, query: &SequentiallySpentScoreResultQuery| {
+        if query.seat_count == 0 || query.seat_count > election.score_election.options.len() {
+            return HttpResponse::BadRequest().body("invalid seat count");
+        }
+        crate::routes::api::common::respond_seeded(
+            &election.election,
+            voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_proportional_result(
+                    election,
+                    votes,
+                    ProportionalScoreMethod::SequentiallySpentScore,
+                    query.seat_count,
+                    1.0,
+                )
+            }),
+        )
+    }
+);
+
+pub fn validate_score_votes(votes: &[usize]) -> Result<(), ValidationError> {
     if votes.iter().any(|i| *i > 100) {
         return Err(ValidationError::new(
             "invalid three_two_one must be between 0 and 5",
@@ -66,23 +146,28 @@ struct ScoreVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &ScoreVoteRequest, user_id: &uuid::Uuid) -> ScoreVote {
-    ScoreVote {
-        created_by: user_id.clone(),
-        votes: request.votes.iter().map(|i| *i).collect(),
-    }
+fn make_vote(request: &ScoreVoteRequest, user_id: &uuid::Uuid, weight: Weight) -> ScoreVoteModeled {
+    ScoreVoteModeled(ScoreVote {
+        created_by: *user_id,
+        weight,
+        votes: request.votes.clone(),
+    })
 }
 
 fn vote_validation(
     request: &ScoreVoteRequest,
-    election: &ScoreElection,
+    election: &ScoreElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.score_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
-    if request.votes.iter().any(|i| *i > election.max_score) {
+    if request
+        .votes
+        .iter()
+        .any(|i| *i > election.score_election.max_score)
+    {
         return Err(NewVoteError::InvalidVoteOption);
     }
 
@@ -102,5 +187,8 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_reweighted_range_result_endpoint)
+        .service(get_election_id_get_sequentially_spent_score_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    convert_into_uuid_or_fail,
+    db::DbPool,
+    elections,
+    routes::{api::common::VoteTokenQuery, auth},
+    seed::{add_entropy, AddEntropyError},
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EntropyRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub entropy: String,
+}
+
+#[derive(Debug)]
+pub enum EntropyError {
+    ElectionNotFound,
+    InvalidToken,
+    SeedRevealed,
+    AlreadyContributed,
+}
+
+/// Mixes the caller's entropy into the election's seed until the seed is
+/// revealed. Elections that require a token take it once per valid token, the
+/// token isn't used up. Other elections take it once per voter id, which
+/// anyone can get more of, so there it isn't sybil-resistant: someone who
+/// knows the secret could keep adding entropy until the seed suits them
+#[post("/{election_id}/entropy")]
+async fn post_election_id_entropy(
+    pool: web::Data<DbPool>,
+    req: HttpRequest,
+    request: actix_web_validator::Json<EntropyRequest>,
+    election_id: web::Path<String>,
+    query: web::Query<VoteTokenQuery>,
+) -> HttpResponse {
+    let election_id = convert_into_uuid_or_fail!(election_id.as_str());
+    let vote_token = match query.vote_token.as_ref() {
+        Some(token) => Some(convert_into_uuid_or_fail!(token.as_str())),
+        None => None,
+    };
+    // A made up id for each request would get round once per voter
+    let user_id = match auth::get_existing_uuid(&req) {
+        Some(user_id) => user_id,
+        None => return HttpResponse::BadRequest().body("no voter id"),
+    };
+    info!("Got new seed entropy for {}", election_id);
+
+    let result = web::block(move || {
+        let mut conn = pool.get().unwrap();
+
+        let election = match elections::get_election(&mut conn, &election_id) {
+            Some(election) => election,
+            None => return Err(EntropyError::ElectionNotFound),
+        };
+        if election.voting_locked || election.seed_revealed {
+            return Err(EntropyError::SeedRevealed);
+        }
+        // Tokens are handed out one per voter, so they limit contributions
+        // where a voter id can't
+        let contributor = match vote_token {
+            Some(token) if election.requires_token => {
+                if auth::get_vote_token_weight(&mut conn, &election_id, &token).is_none() {
+                    return Err(EntropyError::InvalidToken);
+                }
+                token
+            }
+            None if election.requires_token => return Err(EntropyError::InvalidToken),
+            _ => user_id,
+        };
+
+        match add_entropy(&mut conn, &election_id, &contributor, &request.entropy) {
+            Ok(_) => Ok(()),
+            Err(AddEntropyError::AlreadyContributed) => Err(EntropyError::AlreadyContributed),
+        }
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => match err {
+            EntropyError::ElectionNotFound => {
+                HttpResponse::NotFound().body("election doesn't exist")
+            }
+            EntropyError::InvalidToken => HttpResponse::Unauthorized().body("invalid token"),
+            EntropyError::SeedRevealed => HttpResponse::BadRequest().body("seed already revealed"),
+            EntropyError::AlreadyContributed => {
+                HttpResponse::BadRequest().body("already contributed entropy")
+            }
+        },
+    }
+}
@@ -8,7 +8,7 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
-use voting_systems::single_non_transferable_vote::SNTVVote;
+use voting_systems::{single_non_transferable_vote::SNTVVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -28,19 +28,26 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
     #[validate(range(min = 1, max = 100))]
     elected_count: usize,
 }
 
-impl Into<SNTVCreateElection> for CreateElectionRequest {
-    fn into(self) -> SNTVCreateElection {
+impl From<CreateElectionRequest> for SNTVCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         SNTVCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
-            elected_count: self.elected_count,
+            options: val.options,
+            elected_count: val.elected_count,
         }
     }
 }
@@ -56,9 +63,10 @@ struct SNTVVoteRequest {
     votes: Vec<bool>,
 }
 
-fn make_vote(request: &SNTVVoteRequest, user_id: &uuid::Uuid) -> SNTVVoteModeled {
+fn make_vote(request: &SNTVVoteRequest, user_id: &uuid::Uuid, weight: Weight) -> SNTVVoteModeled {
     SNTVVoteModeled(SNTVVote {
-        created_by: user_id.clone(),
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
     })
 }
@@ -92,5 +100,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -8,6 +8,7 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use voting_systems::{single_party::SinglePartyVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -16,7 +17,7 @@ use crate::{
     elections::CreateElection,
     single_party::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        SinglePartyCreateElection, SinglePartyElection, SinglePartyVote,
+        SinglePartyCreateElection, SinglePartyElectionModeled, SinglePartyVoteModeled,
     },
 };
 
@@ -27,16 +28,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 1))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<SinglePartyCreateElection> for CreateElectionRequest {
-    fn into(self) -> SinglePartyCreateElection {
+impl From<CreateElectionRequest> for SinglePartyCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         SinglePartyCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -52,16 +60,21 @@ struct SinglePartyVoteRequest {
     voted: bool,
 }
 
-fn make_vote(request: &SinglePartyVoteRequest, user_id: &uuid::Uuid) -> SinglePartyVote {
-    SinglePartyVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &SinglePartyVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> SinglePartyVoteModeled {
+    SinglePartyVoteModeled(SinglePartyVote {
+        created_by: *user_id,
+        weight,
         voted: request.voted,
-    }
+    })
 }
 
 fn vote_validation(
     _: &SinglePartyVoteRequest,
-    _: &SinglePartyElection,
+    _: &SinglePartyElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     Ok(())
 }
@@ -79,5 +92,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -7,7 +7,14 @@ use actix_web::{
 };
 use log::info;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+use voting_systems::{
+    meek_stv::MeekStvResult,
+    numeric::Arithmetic,
+    single_transferable_vote::{StvQuota, StvResult, StvTransfer, StvVote},
+    tie_break::TieBreak,
+    weight::Weight,
+};
 
 use crate::{
     check_key, convert_into_uuid_or_fail, create_get_election_id_endpoint,
@@ -17,8 +24,8 @@ use crate::{
     elections::CreateElection,
     handle_check_error,
     single_transferable_vote::{
-        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        CreateStvElection, StvElection, StvVote,
+        add_election, add_vote, get_election, get_meek_result, get_result, get_votes, AddVoteError,
+        CreateStvElection, StvElectionModeled, StvVoteModeled,
     },
 };
 
@@ -29,19 +36,32 @@ struct CreateElectionRequest {
     #[validate(length(min = 1, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
     #[validate(range(min = 1, max = 100))]
     elected_count: u32,
+    #[serde(default)]
+    transfer: StvTransfer,
+    #[serde(default)]
+    quota: StvQuota,
 }
 
-impl Into<CreateStvElection> for CreateElectionRequest {
-    fn into(self) -> CreateStvElection {
+impl From<CreateElectionRequest> for CreateStvElection {
+    fn from(val: CreateElectionRequest) -> Self {
         CreateStvElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
-            elected_count: self.elected_count as usize,
+            options: val.options,
+            elected_count: val.elected_count as usize,
+            transfer: val.transfer,
+            quota: val.quota,
         }
     }
 }
@@ -54,31 +74,37 @@ create_get_election_id_endpoint!(get_election);
 pub struct GetResultQuery {
     pub api_key: String,
     pub pre_eliminated_candidates: Option<String>,
+    /// Counts with another transfer than the election was created with
+    pub transfer: Option<StvTransfer>,
+    /// Counts with another quota than the election was created with
+    pub quota: Option<StvQuota>,
+    /// How the Gregory transfers keep fractions, `exact` or
+    /// `fixed_point:places:rounding`. Exact when left out
+    pub arithmetic: Option<String>,
 }
 
-#[get("/{election_id}/get_result")]
-async fn get_election_id_get_result_endpoint(
+/// Checks the key, loads the election and its votes, and passes them on with
+/// the pre-eliminated candidates that exist in the election
+async fn respond_with_result(
     pool: web::Data<DbPool>,
-    election_id: web::Path<String>,
-    query: web::Query<GetResultQuery>,
+    election_id: &str,
+    api_key: &str,
+    pre_eliminated_candidates: Option<&str>,
+    respond: impl FnOnce(StvElectionModeled, &[StvVote], Vec<usize>) -> HttpResponse,
 ) -> HttpResponse {
+    let pre_eliminated_candidates = match pre_eliminated_candidates
+        .map(|i| i.split(',').map(|i| i.parse::<usize>()).collect())
+        .transpose()
     {
-        if query.pre_eliminated_candidates.is_some()
-            && query
-                .pre_eliminated_candidates
-                .as_ref()
-                .unwrap()
-                .split(",")
-                .any(|i| i.parse::<usize>().is_err())
-        {
+        Ok(pre_eliminated_candidates) => pre_eliminated_candidates.unwrap_or_else(Vec::new),
+        Err(_) => {
             return HttpResponse::BadRequest()
-                .body("pre_eliminated_candidates must be a comma separated list of numbers")
-                .into();
+                .body("pre_eliminated_candidates must be a comma separated list of numbers");
         }
-    }
+    };
 
-    let election_id = convert_into_uuid_or_fail!(election_id.as_str());
-    let api_key = convert_into_uuid_or_fail!(query.api_key.as_str());
+    let election_id = convert_into_uuid_or_fail!(election_id);
+    let api_key = convert_into_uuid_or_fail!(api_key);
 
     let result = web::block(move || {
         let mut conn = pool.get().unwrap();
@@ -100,48 +126,147 @@ async fn get_election_id_get_result_endpoint(
     let (election, votes) = result.unwrap();
 
     if election.is_none() {
-        return HttpResponse::NotFound()
-            .body("election doesn't exist")
-            .into();
+        return HttpResponse::NotFound().body("election doesn't exist");
     }
 
     let election = election.unwrap();
 
-    let eliminated_candidates =
-        if let Some(pre_eliminated_candidates) = query.pre_eliminated_candidates.as_ref() {
-            pre_eliminated_candidates
-                .split(",")
-                .map(|i| i.parse::<usize>().unwrap())
-                .filter(|i| *i < election.options.len())
-                .map(|i| i)
-                .collect::<Vec<usize>>()
-        } else {
-            vec![]
-        };
-
-    let result = get_result(&election, &votes, &eliminated_candidates);
-
-    HttpResponse::Ok().json(result).into()
+    let eliminated_candidates = pre_eliminated_candidates
+        .into_iter()
+        .filter(|i| *i < election.stv_election.options.len())
+        .collect::<Vec<usize>>();
+
+    respond(election, &votes, eliminated_candidates)
+}
+
+#[get("/{election_id}/get_result")]
+async fn get_election_id_get_result_endpoint(
+    pool: web::Data<DbPool>,
+    election_id: web::Path<String>,
+    query: web::Query<GetResultQuery>,
+) -> HttpResponse {
+    let arithmetic = match query.arithmetic.as_deref().map(Arithmetic::from_str) {
+        None => None,
+        Some(Ok(arithmetic)) => Some(arithmetic),
+        Some(Err(err)) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
+
+    respond_with_result(
+        pool,
+        &election_id,
+        &query.api_key,
+        query.pre_eliminated_candidates.as_deref(),
+        |mut election, votes, eliminated_candidates| {
+            if let Some(arithmetic) = arithmetic {
+                election.stv_election.arithmetic = arithmetic;
+            }
+            if let Some(transfer) = query.transfer {
+                election.stv_election.transfer = transfer;
+            }
+            if let Some(quota) = query.quota {
+                election.stv_election.quota = quota;
+            }
+
+            let result = voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_result(&election, votes, &eliminated_candidates)
+            });
+
+            crate::routes::api::common::respond_seeded(&election.election, result)
+        },
+    )
+    .await
+}
+
+/// Surplus left on elected candidates once the Meek keep values are settled
+const DEFAULT_MEEK_TOLERANCE: f64 = 0.00001;
+
+fn validate_tolerance(tolerance: f64) -> Result<(), ValidationError> {
+    if !(tolerance > 0.0 && tolerance <= 1.0) {
+        return Err(ValidationError::new(
+            "tolerance must be more than 0 and no more than 1",
+        ));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
-struct CreatePreferentialVoteRequest {
-    votes: Vec<usize>,
+pub struct GetMeekResultQuery {
+    pub api_key: String,
+    pub pre_eliminated_candidates: Option<String>,
+    #[validate(custom = "validate_tolerance")]
+    pub tolerance: Option<f64>,
+}
+
+#[get("/{election_id}/get_result/meek")]
+async fn get_election_id_get_meek_result_endpoint(
+    pool: web::Data<DbPool>,
+    election_id: web::Path<String>,
+    query: actix_web_validator::Query<GetMeekResultQuery>,
+) -> HttpResponse {
+    let tolerance = query.tolerance.unwrap_or(DEFAULT_MEEK_TOLERANCE);
+
+    respond_with_result(
+        pool,
+        &election_id,
+        &query.api_key,
+        query.pre_eliminated_candidates.as_deref(),
+        |election, votes, eliminated_candidates| {
+            let result = voting_systems::weight::get_weighted_result(votes, |votes| {
+                get_meek_result(&election, votes, &eliminated_candidates, tolerance)
+            });
+
+            crate::routes::api::common::respond_seeded(&election.election, result)
+        },
+    )
+    .await
+}
+
+/// The delegated results count with the election's own settings and no
+/// candidates eliminated up front
+fn get_delegated_result(election: &StvElectionModeled, votes: &[StvVote]) -> StvResult {
+    get_result(election, votes, &[])
 }
 
+fn get_delegated_meek_result(election: &StvElectionModeled, votes: &[StvVote]) -> MeekStvResult {
+    get_meek_result(election, votes, &[], DEFAULT_MEEK_TOLERANCE)
+}
+
+crate::create_get_election_id_get_delegated_result_endpoint!(
+    get_election,
+    get_votes,
+    get_delegated_result
+);
+
+crate::create_get_election_id_get_delegated_result_endpoint!(
+    "/{election_id}/get_result/meek/delegated",
+    get_election_id_get_delegated_meek_result_endpoint,
+    get_election,
+    get_votes,
+    get_delegated_meek_result
+);
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 struct CreateStvElectionVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &CreateStvElectionVoteRequest, user_id: &uuid::Uuid) -> StvVote {
-    StvVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &CreateStvElectionVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> StvVoteModeled {
+    StvVoteModeled(StvVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
-create_ranked_choice_vote_validation!(&CreateStvElectionVoteRequest, &StvElection);
+create_ranked_choice_vote_validation!(
+    &CreateStvElectionVoteRequest,
+    &StvElectionModeled,
+    stv_election
+);
 
 create_post_election_id_new_vote_endpoint!(
     CreateStvElectionVoteRequest,
@@ -156,5 +281,8 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_meek_result_endpoint)
+        .service(get_election_id_get_delegated_meek_result_endpoint)
         .service(post_election_id_new_vote)
 }
@@ -8,6 +8,9 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
+use voting_systems::{
+    multi_winner_star::MultiStarMethod, star::StarVote, tie_break::TieBreak, weight::Weight,
+};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -15,8 +18,8 @@ use crate::{
     db::DbPool,
     elections::CreateElection,
     star::{
-        add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        StarCreateElection, StarElection, StarVote,
+        add_election, add_vote, get_election, get_multi_winner_result, get_result, get_votes,
+        AddVoteError, StarCreateElection, StarElectionModeled, StarVoteModeled,
     },
 };
 
@@ -27,16 +30,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<StarCreateElection> for CreateElectionRequest {
-    fn into(self) -> StarCreateElection {
+impl From<CreateElectionRequest> for StarCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         StarCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,7 +57,49 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
-pub fn validate_star_votes(votes: &Vec<usize>) -> Result<(), ValidationError> {
+#[derive(Debug, Deserialize)]
+struct SeatCountResultQuery {
+    api_key: Option<String>,
+    seat_count: usize,
+}
+
+macro_rules! create_multi_winner_result_endpoint {
+    ($path:literal, $endpoint:ident, $method:expr) => {
+        crate::create_get_election_id_get_result_endpoint!(
+            $path,
+            $endpoint,
+            SeatCountResultQuery,
+            get_election,
+            get_votes,
+            |election: &StarElectionModeled, votes, query: &SeatCountResultQuery| {
+                if query.seat_count == 0 || query.seat_count > election.star_election.options.len()
+                {
+                    return HttpResponse::BadRequest().body("invalid seat count");
+                }
+                crate::routes::api::common::respond_seeded(
+                    &election.election,
+                    voting_systems::weight::get_weighted_result(votes, |votes| {
+                        get_multi_winner_result(election, votes, $method, query.seat_count)
+                    }),
+                )
+            }
+        );
+    };
+}
+
+create_multi_winner_result_endpoint!(
+    "/{election_id}/get_result/bloc",
+    get_election_id_get_bloc_result_endpoint,
+    MultiStarMethod::Bloc
+);
+
+create_multi_winner_result_endpoint!(
+    "/{election_id}/get_result/allocated_score",
+    get_election_id_get_allocated_score_result_endpoint,
+    MultiStarMethod::AllocatedScore
+);
+
+pub fn validate_star_votes(votes: &[usize]) -> Result<(), ValidationError> {
     if votes.iter().any(|i| *i > 5) {
         return Err(ValidationError::new("invalid star must be between 0 and 5"));
     }
@@ -61,19 +113,20 @@ struct StarVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &StarVoteRequest, user_id: &uuid::Uuid) -> StarVote {
-    StarVote {
-        created_by: user_id.clone(),
+fn make_vote(request: &StarVoteRequest, user_id: &uuid::Uuid, weight: Weight) -> StarVoteModeled {
+    StarVoteModeled(StarVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.clone(),
-    }
+    })
 }
 
 fn vote_validation(
     request: &StarVoteRequest,
-    election: &StarElection,
+    election: &StarElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.star_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -93,5 +146,8 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(get_election_id_get_bloc_result_endpoint)
+        .service(get_election_id_get_allocated_score_result_endpoint)
         .service(post_election_id_new_vote)
 }
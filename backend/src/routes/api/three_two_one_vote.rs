@@ -8,6 +8,7 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
+use voting_systems::{three_two_one::ThreeTwoOneVote, tie_break::TieBreak, weight::Weight};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -16,7 +17,7 @@ use crate::{
     elections::CreateElection,
     three_two_one::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        ThreeTwoOneCreateElection, ThreeTwoOneElection, ThreeTwoOneVote,
+        ThreeTwoOneCreateElection, ThreeTwoOneElectionModeled, ThreeTwoOneVoteModeled,
     },
 };
 
@@ -27,16 +28,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 3, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<ThreeTwoOneCreateElection> for CreateElectionRequest {
-    fn into(self) -> ThreeTwoOneCreateElection {
+impl From<CreateElectionRequest> for ThreeTwoOneCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         ThreeTwoOneCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,7 +55,7 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
-pub fn validate_three_two_one_votes(votes: &Vec<usize>) -> Result<(), ValidationError> {
+pub fn validate_three_two_one_votes(votes: &[usize]) -> Result<(), ValidationError> {
     if votes.iter().any(|i| *i > 2) {
         return Err(ValidationError::new(
             "invalid three_two_one must be between 0 and 5",
@@ -63,19 +71,24 @@ struct ThreeTwoOneVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &ThreeTwoOneVoteRequest, user_id: &uuid::Uuid) -> ThreeTwoOneVote {
-    ThreeTwoOneVote {
-        created_by: user_id.clone(),
+fn make_vote(
+    request: &ThreeTwoOneVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> ThreeTwoOneVoteModeled {
+    ThreeTwoOneVoteModeled(ThreeTwoOneVote {
+        created_by: *user_id,
+        weight,
         votes: request.votes.iter().map(|i| (*i as i32).into()).collect(),
-    }
+    })
 }
 
 fn vote_validation(
     request: &ThreeTwoOneVoteRequest,
-    election: &ThreeTwoOneElection,
+    election: &ThreeTwoOneElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.three_two_one_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -95,5 +108,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
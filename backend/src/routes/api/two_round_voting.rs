@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get, post,
+    web::{self},
+    HttpResponse, Scope,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use voting_systems::{tie_break::TieBreak, two_round::TwoRoundVote, weight::Weight};
+
+use crate::{
+    check_key, convert_into_uuid_or_fail, create_get_election_id_endpoint,
+    create_post_election_id_new_vote_endpoint, create_post_endpoint,
+    db::{DbConnection, DbPool},
+    elections::CreateElection,
+    handle_check_error,
+    routes::{api::common::NewVoteError, auth::Auth},
+    two_round::{
+        add_election, add_vote, get_election, get_result, get_votes, open_second_round,
+        AddVoteError, PublicTwoRoundElection, TwoRoundCreateElection, TwoRoundElectionModeled,
+        TwoRoundVoteModeled,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateElectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    title: String,
+    #[validate(length(min = 2, max = 100))]
+    options: Vec<String>,
+    require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
+}
+
+impl From<CreateElectionRequest> for TwoRoundCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
+        TwoRoundCreateElection {
+            election_base: CreateElection {
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
+            },
+            options: val.options,
+        }
+    }
+}
+
+create_post_endpoint!(add_election, CreateElectionRequest);
+
+create_get_election_id_endpoint!(get_election, PublicTwoRoundElection);
+
+crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
+
+#[derive(Debug)]
+pub enum OpenSecondRoundError {
+    NoElection,
+    AlreadyOpen,
+    FirstRoundDecided,
+    NoVotes,
+    CheckError(crate::routes::auth::CheckError),
+}
+
+/// Closes the first round and opens a ballot between its top two. Vote
+/// tokens are used up in the first round so new ones are needed
+#[post("/{election_id}/open_second_round")]
+async fn post_election_id_open_second_round(
+    pool: web::Data<DbPool>,
+    election_id: web::Path<String>,
+    auth: web::Query<Auth>,
+) -> HttpResponse {
+    let election_id = convert_into_uuid_or_fail!(election_id.as_str());
+    let api_key = convert_into_uuid_or_fail!(auth.api_key.as_str());
+    info!(
+        "Got new request to open the second round for {}",
+        election_id
+    );
+
+    let result = web::block(move || {
+        let mut conn: DbConnection = pool.get().unwrap();
+
+        check_key!(
+            &mut conn,
+            &election_id,
+            &api_key,
+            OpenSecondRoundError::CheckError
+        );
+
+        let election = match get_election(&mut conn, &election_id) {
+            Some(election) => election,
+            None => return Err(OpenSecondRoundError::NoElection),
+        };
+
+        if election.round() != 1 {
+            return Err(OpenSecondRoundError::AlreadyOpen);
+        }
+
+        let result = get_result(&election, &get_votes(&mut conn, &election_id));
+        if result.first_round_winner.is_some() {
+            return Err(OpenSecondRoundError::FirstRoundDecided);
+        }
+        let finalists = match result.finalists {
+            Some(finalists) => finalists,
+            None => return Err(OpenSecondRoundError::NoVotes),
+        };
+
+        open_second_round(&mut conn, &election_id, &finalists);
+        Ok(finalists)
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(finalists) => HttpResponse::Ok().json(finalists),
+        Err(err) => match err {
+            OpenSecondRoundError::NoElection => HttpResponse::NotFound().finish(),
+            OpenSecondRoundError::AlreadyOpen => {
+                HttpResponse::BadRequest().body("second round already open")
+            }
+            OpenSecondRoundError::FirstRoundDecided => {
+                HttpResponse::BadRequest().body("first round has a majority")
+            }
+            OpenSecondRoundError::NoVotes => {
+                HttpResponse::BadRequest().body("first round has no votes")
+            }
+            OpenSecondRoundError::CheckError(err) => handle_check_error!(err),
+        },
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+struct CreateTwoRoundVoteRequest {
+    /// The round the ballot is for, rejected once that round has closed
+    round: usize,
+    vote: usize,
+}
+
+fn vote_validation(
+    request: &CreateTwoRoundVoteRequest,
+    election: &TwoRoundElectionModeled,
+) -> Result<(), NewVoteError> {
+    if request.round != election.round() {
+        return Err(NewVoteError::RoundClosed);
+    }
+
+    if request.vote >= election.two_round_election.options.len() {
+        return Err(NewVoteError::InvalidVoteOption);
+    }
+
+    if let Some(finalists) = election.two_round_election.finalists.as_ref() {
+        if !finalists.contains(&request.vote) {
+            return Err(NewVoteError::InvalidVoteOption);
+        }
+    }
+
+    Ok(())
+}
+
+fn make_vote(
+    request: &CreateTwoRoundVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> TwoRoundVoteModeled {
+    TwoRoundVoteModeled(TwoRoundVote {
+        created_by: *user_id,
+        weight,
+        round: request.round,
+        vote: request.vote,
+    })
+}
+
+create_post_election_id_new_vote_endpoint!(
+    CreateTwoRoundVoteRequest,
+    get_election,
+    vote_validation,
+    make_vote,
+    add_vote
+);
+
+pub fn routes() -> Scope {
+    web::scope("/two_round")
+        .service(post_endpoint)
+        .service(get_election_id_endpoint)
+        .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
+        .service(post_election_id_open_second_round)
+        .service(post_election_id_new_vote)
+}
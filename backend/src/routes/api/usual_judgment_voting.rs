@@ -8,6 +8,7 @@ use actix_web::{
 use log::info;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
+use voting_systems::{tie_break::TieBreak, usual_judgment::UsualJudgmentVote, weight::Weight};
 
 use crate::{
     create_get_election_id_endpoint, create_post_election_id_new_vote_endpoint,
@@ -16,7 +17,7 @@ use crate::{
     elections::CreateElection,
     usual_judgment::{
         add_election, add_vote, get_election, get_result, get_votes, AddVoteError,
-        UsualJudgmentCreateElection, UsualJudgmentElection, UsualJudgmentVote,
+        UsualJudgmentCreateElection, UsualJudgmentElectionModeled,
     },
 };
 
@@ -27,16 +28,23 @@ struct CreateElectionRequest {
     #[validate(length(min = 2, max = 100))]
     options: Vec<String>,
     require_token: bool,
+    #[serde(default)]
+    tie_break: TieBreak,
+    #[serde(default)]
+    #[validate(custom = "crate::routes::api::common::validate_seed_commitment")]
+    seed_commitment: Option<String>,
 }
 
-impl Into<UsualJudgmentCreateElection> for CreateElectionRequest {
-    fn into(self) -> UsualJudgmentCreateElection {
+impl From<CreateElectionRequest> for UsualJudgmentCreateElection {
+    fn from(val: CreateElectionRequest) -> Self {
         UsualJudgmentCreateElection {
             election_base: CreateElection {
-                title: self.title,
-                requires_token: self.require_token,
+                title: val.title,
+                requires_token: val.require_token,
+                tie_break: val.tie_break,
+                seed_commitment: val.seed_commitment,
             },
-            options: self.options,
+            options: val.options,
         }
     }
 }
@@ -47,7 +55,7 @@ create_get_election_id_endpoint!(get_election);
 
 crate::create_get_election_id_get_result_endpoint!(get_election, get_votes, get_result);
 
-pub fn validate_usual_judgment_votes(votes: &Vec<usize>) -> Result<(), ValidationError> {
+pub fn validate_usual_judgment_votes(votes: &[usize]) -> Result<(), ValidationError> {
     if votes.iter().any(|i| *i > 7) {
         return Err(ValidationError::new(
             "invalid usual judgment must be between 0 and 7",
@@ -63,19 +71,24 @@ struct UsualJudgmentVoteRequest {
     votes: Vec<usize>,
 }
 
-fn make_vote(request: &UsualJudgmentVoteRequest, user_id: &uuid::Uuid) -> UsualJudgmentVote {
+fn make_vote(
+    request: &UsualJudgmentVoteRequest,
+    user_id: &uuid::Uuid,
+    weight: Weight,
+) -> UsualJudgmentVote {
     UsualJudgmentVote {
-        created_by: user_id.clone(),
+        created_by: *user_id,
+        weight,
         votes: request.votes.iter().map(|i| (*i as i32).into()).collect(),
     }
 }
 
 fn vote_validation(
     request: &UsualJudgmentVoteRequest,
-    election: &UsualJudgmentElection,
+    election: &UsualJudgmentElectionModeled,
 ) -> Result<(), crate::routes::api::common::NewVoteError> {
     use crate::routes::api::common::NewVoteError;
-    if election.options.len() != request.votes.len() {
+    if election.usual_judgment_election.options.len() != request.votes.len() {
         return Err(NewVoteError::InvalidVoteCount);
     }
 
@@ -95,5 +108,6 @@ pub fn routes() -> Scope {
         .service(post_endpoint)
         .service(get_election_id_endpoint)
         .service(get_election_id_get_result_endpoint)
+        .service(get_election_id_get_delegated_result_endpoint)
         .service(post_election_id_new_vote)
 }
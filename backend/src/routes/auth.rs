@@ -4,6 +4,7 @@ use actix_web::HttpRequest;
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use log::info;
 use serde::Deserialize;
+use voting_systems::weight::Weight;
 
 #[derive(Deserialize)]
 pub struct Auth {
@@ -37,24 +38,25 @@ macro_rules! convert_into_uuid_or_fail {
     };
 }
 
-pub fn is_vote_token_valid(
+/// The weight of the ballot the token lets someone cast, None when the token
+/// isn't valid for the election
+pub fn get_vote_token_weight(
     c: &mut diesel::PgConnection,
     election_id: &uuid::Uuid,
     token: &uuid::Uuid,
-) -> bool {
-    info!(
-        "Checking if vote token {:?} is valid for {:?} in the db",
-        token, election_id
-    );
+) -> Option<Weight> {
     use crate::schema::voting_tokens;
     voting_tokens::table
-        .select(voting_tokens::token)
+        .select((
+            voting_tokens::weight_numerator,
+            voting_tokens::weight_denominator,
+        ))
         .filter(voting_tokens::election_id.eq(election_id))
         .filter(voting_tokens::token.eq(token))
-        .first::<uuid::Uuid>(c)
+        .first::<(i64, i64)>(c)
         .optional()
         .unwrap()
-        .is_some()
+        .map(|(numerator, denominator)| crate::elections::weight_from_model(numerator, denominator))
 }
 
 pub fn remove_vote_token(
@@ -100,21 +102,23 @@ pub enum CheckError {
 #[macro_export]
 macro_rules! check_key {
     ($api_key:expr) => {
-        if $api_key != *crate::routes::auth::API_KEY.to_string() {
-            crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
+        if $api_key != *$crate::routes::auth::API_KEY.to_string() {
+            $crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
             return HttpResponse::Unauthorized().into();
         }
     };
     ($connection:expr, $election_id:expr, $api_key:expr, $err_wrapper:expr) => {
-        if !crate::routes::auth::is_election_token_valid($connection, $election_id, $api_key) {
-            crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
-            return Err($err_wrapper(crate::routes::auth::CheckError::InvalidApiKey));
+        if !$crate::routes::auth::is_election_token_valid($connection, $election_id, $api_key) {
+            $crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
+            return Err($err_wrapper(
+                $crate::routes::auth::CheckError::InvalidApiKey,
+            ));
         }
     };
     ($connection:expr, $election_id:expr, $api_key:expr) => {
-        if !crate::routes::auth::is_election_token_valid($connection, $election_id, $api_key) {
-            crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
-            return Err(crate::routes::auth::CheckError::InvalidApiKey);
+        if !$crate::routes::auth::is_election_token_valid($connection, $election_id, $api_key) {
+            $crate::routes::auth::sleep_for_between_x_and_y_ms(1000, 5000);
+            return Err($crate::routes::auth::CheckError::InvalidApiKey);
         }
     };
 }
@@ -123,19 +127,25 @@ macro_rules! check_key {
 macro_rules! handle_check_error {
     ($err:expr) => {
         match $err {
-            crate::routes::auth::CheckError::InvalidApiKey => {
+            $crate::routes::auth::CheckError::InvalidApiKey => {
                 return HttpResponse::Unauthorized().into();
             }
         }
     };
 }
 
-pub fn create_election_token(c: &mut diesel::PgConnection, election_id: &uuid::Uuid) -> uuid::Uuid {
+pub fn create_election_token(
+    c: &mut diesel::PgConnection,
+    election_id: &uuid::Uuid,
+    weight: Weight,
+) -> uuid::Uuid {
     use crate::schema::voting_tokens;
     let token = uuid::Uuid::new_v4();
     let insert_value = crate::models::VotingToken {
-        election_id: election_id.clone(),
-        token: token.clone(),
+        election_id: *election_id,
+        token,
+        weight_numerator: weight.numerator as i64,
+        weight_denominator: weight.denominator as i64,
     };
 
     diesel::insert_into(voting_tokens::table)
@@ -155,3 +165,10 @@ pub fn get_created_uuid(request: &HttpRequest) -> uuid::Uuid {
 
     uuid::Uuid::from_str(&user_id).unwrap_or_default()
 }
+
+/// The voter id from the request's cookie, None rather than a new id when
+/// there is no cookie
+pub fn get_existing_uuid(request: &HttpRequest) -> Option<uuid::Uuid> {
+    let cookie = request.cookie("pref_election_uuid")?;
+    uuid::Uuid::from_str(cookie.value()).ok()
+}
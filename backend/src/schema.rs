@@ -13,6 +13,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         vote -> Int4,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -29,6 +31,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Bool>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -45,6 +49,27 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    bucklin_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        majority_rule -> Text,
+    }
+}
+
+diesel::table! {
+    bucklin_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -61,6 +86,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -78,6 +105,19 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    delegations (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        delegate -> Uuid,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -90,6 +130,58 @@ diesel::table! {
         created_time -> Timestamp,
         public -> Bool,
         voting_locked -> Bool,
+        tie_break -> Text,
+        seed_commitment -> Nullable<Text>,
+        seed_secret -> Nullable<Text>,
+        seed_revealed -> Bool,
+        seed_entropy -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    entropy_contributions (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        entropy -> Text,
+    }
+}
+
+diesel::table! {
+    hare_clark_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        vacancies -> Int4,
+    }
+}
+
+diesel::table! {
+    hare_clark_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        rotation -> Int4,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    kemeny_young_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    kemeny_young_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -106,6 +198,50 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    participatory_budgeting_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        costs -> Array<Nullable<Int8>>,
+        budget -> Int8,
+        max_score -> Int4,
+    }
+}
+
+diesel::table! {
+    participatory_budgeting_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    party_list_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        seat_count -> Int4,
+        method -> Text,
+        threshold -> Nullable<Float8>,
+    }
+}
+
+diesel::table! {
+    party_list_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Bool>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -122,6 +258,28 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    quadratic_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        credits -> Int4,
+        allow_negative -> Bool,
+    }
+}
+
+diesel::table! {
+    quadratic_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -138,6 +296,9 @@ diesel::table! {
     quota_preferential_vic_labor_2024_elections (election_id) {
         election_id -> Uuid,
         elected_count -> Int4,
+        percent_female -> Float8,
+        quota -> Text,
+        arithmetic -> Text,
     }
 }
 
@@ -147,6 +308,26 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int8>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    schulze_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    schulze_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -164,6 +345,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -181,6 +364,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Bool>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -197,6 +382,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         voted -> Bool,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -213,6 +400,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -221,6 +410,8 @@ diesel::table! {
         election_id -> Uuid,
         options -> Array<Nullable<Text>>,
         elected_count -> Int4,
+        transfer -> Text,
+        quota -> Text,
     }
 }
 
@@ -230,6 +421,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -246,6 +439,28 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
+    }
+}
+
+diesel::table! {
+    two_round_elections (election_id) {
+        election_id -> Uuid,
+        options -> Array<Nullable<Text>>,
+        finalists -> Nullable<Array<Nullable<Int4>>>,
+    }
+}
+
+diesel::table! {
+    two_round_votes (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        created_by -> Uuid,
+        round -> Int4,
+        vote -> Int4,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -262,6 +477,8 @@ diesel::table! {
         election_id -> Uuid,
         created_by -> Uuid,
         votes -> Array<Nullable<Int4>>,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -269,6 +486,8 @@ diesel::table! {
     voting_tokens (election_id, token) {
         election_id -> Uuid,
         token -> Uuid,
+        weight_numerator -> Int8,
+        weight_denominator -> Int8,
     }
 }
 
@@ -278,17 +497,33 @@ diesel::joinable!(approval_elections -> elections (election_id));
 diesel::joinable!(approval_votes -> elections (election_id));
 diesel::joinable!(borda_count_elections -> elections (election_id));
 diesel::joinable!(borda_count_votes -> elections (election_id));
+diesel::joinable!(bucklin_elections -> elections (election_id));
+diesel::joinable!(bucklin_votes -> elections (election_id));
 diesel::joinable!(condorcet_method_elections -> elections (election_id));
 diesel::joinable!(condorcet_method_votes -> elections (election_id));
 diesel::joinable!(cumulative_elections -> elections (election_id));
 diesel::joinable!(cumulative_votes -> elections (election_id));
+diesel::joinable!(delegations -> elections (election_id));
+diesel::joinable!(entropy_contributions -> elections (election_id));
+diesel::joinable!(hare_clark_elections -> elections (election_id));
+diesel::joinable!(hare_clark_votes -> elections (election_id));
+diesel::joinable!(kemeny_young_elections -> elections (election_id));
+diesel::joinable!(kemeny_young_votes -> elections (election_id));
 diesel::joinable!(majority_judgment_elections -> elections (election_id));
 diesel::joinable!(majority_judgment_votes -> elections (election_id));
+diesel::joinable!(participatory_budgeting_elections -> elections (election_id));
+diesel::joinable!(participatory_budgeting_votes -> elections (election_id));
+diesel::joinable!(party_list_elections -> elections (election_id));
+diesel::joinable!(party_list_votes -> elections (election_id));
 diesel::joinable!(preferential_elections -> elections (election_id));
 diesel::joinable!(preferential_votes -> elections (election_id));
+diesel::joinable!(quadratic_elections -> elections (election_id));
+diesel::joinable!(quadratic_votes -> elections (election_id));
 diesel::joinable!(quota_preferential_vic_labor_2024_candidate -> elections (election_id));
 diesel::joinable!(quota_preferential_vic_labor_2024_elections -> elections (election_id));
 diesel::joinable!(quota_preferential_vic_labor_2024_transferable_votes -> elections (election_id));
+diesel::joinable!(schulze_elections -> elections (election_id));
+diesel::joinable!(schulze_votes -> elections (election_id));
 diesel::joinable!(score_elections -> elections (election_id));
 diesel::joinable!(score_votes -> elections (election_id));
 diesel::joinable!(single_non_transferable_elections -> elections (election_id));
@@ -301,6 +536,8 @@ diesel::joinable!(stv_elections -> elections (election_id));
 diesel::joinable!(stv_votes -> elections (election_id));
 diesel::joinable!(three_two_one_elections -> elections (election_id));
 diesel::joinable!(three_two_one_votes -> elections (election_id));
+diesel::joinable!(two_round_elections -> elections (election_id));
+diesel::joinable!(two_round_votes -> elections (election_id));
 diesel::joinable!(usual_judgment_elections -> elections (election_id));
 diesel::joinable!(usual_judgment_votes -> elections (election_id));
 diesel::joinable!(voting_tokens -> elections (election_id));
@@ -312,18 +549,34 @@ diesel::allow_tables_to_appear_in_same_query!(
     approval_votes,
     borda_count_elections,
     borda_count_votes,
+    bucklin_elections,
+    bucklin_votes,
     condorcet_method_elections,
     condorcet_method_votes,
     cumulative_elections,
     cumulative_votes,
+    delegations,
     elections,
+    entropy_contributions,
+    hare_clark_elections,
+    hare_clark_votes,
+    kemeny_young_elections,
+    kemeny_young_votes,
     majority_judgment_elections,
     majority_judgment_votes,
+    participatory_budgeting_elections,
+    participatory_budgeting_votes,
+    party_list_elections,
+    party_list_votes,
     preferential_elections,
     preferential_votes,
+    quadratic_elections,
+    quadratic_votes,
     quota_preferential_vic_labor_2024_candidate,
     quota_preferential_vic_labor_2024_elections,
     quota_preferential_vic_labor_2024_transferable_votes,
+    schulze_elections,
+    schulze_votes,
     score_elections,
     score_votes,
     single_non_transferable_elections,
@@ -336,6 +589,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     stv_votes,
     three_two_one_elections,
     three_two_one_votes,
+    two_round_elections,
+    two_round_votes,
     usual_judgment_elections,
     usual_judgment_votes,
     voting_tokens,
@@ -0,0 +1,84 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_election, create_add_vote, create_election, create_get_election, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::schulze::{SchulzeElection, SchulzeResult, SchulzeVote};
+
+create_election!(SchulzeElectionModeled, schulze_election, SchulzeElection);
+
+impl SchulzeElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            schulze_election: SchulzeElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
+
+create_get_election!(crate::schema::schulze_elections, SchulzeElectionModeled);
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct SchulzeCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+}
+
+create_add_election!(
+    SchulzeCreateElection,
+    models::SchulzeElection,
+    crate::schema::schulze_elections
+);
+
+pub struct SchulzeVoteModeled(pub SchulzeVote);
+
+impl From<models::SchulzeVote> for SchulzeVote {
+    fn from(v: models::SchulzeVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            votes: v.votes.into_iter().map(|v| v.unwrap() as usize).collect(),
+        }
+    }
+}
+
+impl SchulzeVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::SchulzeVote {
+        models::SchulzeVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::schulze_votes,
+    models::SchulzeVote,
+    SchulzeVote
+);
+
+create_add_vote!(
+    crate::schema::schulze_votes,
+    models::SchulzeVote,
+    SchulzeVoteModeled
+);
+
+pub fn get_result(election: &SchulzeElectionModeled, votes: &[SchulzeVote]) -> SchulzeResult {
+    voting_systems::schulze::get_result(&election.schulze_election, votes)
+}
@@ -0,0 +1,125 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+use crate::models;
+
+use voting_systems::seed::{commit, SeedDerivation, SeedError};
+
+/// The commitment and secret for a new election. Without a commitment from
+/// the organiser the server makes up the secret and reveals it itself
+pub fn new_seed(seed_commitment: Option<&str>) -> (String, Option<String>) {
+    match seed_commitment {
+        Some(commitment) => (commitment.to_lowercase(), None),
+        None => {
+            let secret = uuid::Uuid::new_v4().simple().to_string();
+            (commit(&secret), Some(secret))
+        }
+    }
+}
+
+/// Fails when the stored secret doesn't match the commitment, which the
+/// reveal never lets through, so only a changed row gets here
+pub fn seed_derivation(election: &models::Election) -> Result<SeedDerivation, SeedError> {
+    let commitment = match election.seed_commitment.as_ref() {
+        Some(commitment) => commitment,
+        None => return Ok(SeedDerivation::from_title(&election.title)),
+    };
+
+    match election.seed_secret.as_ref() {
+        Some(secret) if election.seed_revealed => {
+            let entropy: Vec<String> = election.seed_entropy.iter().flatten().cloned().collect();
+            SeedDerivation::reveal(commitment, secret, &entropy)
+        }
+        _ => Ok(SeedDerivation::committed(commitment)),
+    }
+}
+
+/// The seed the counts draw lots with. A secret that fails to match its
+/// commitment leaves the commitment as the seed, results counted with it are
+/// never sent, `respond_seeded` fails them
+pub fn get_seed(election: &models::Election) -> String {
+    match seed_derivation(election) {
+        Ok(derivation) => derivation.seed,
+        Err(_) => election.seed_commitment.clone().unwrap_or_default(),
+    }
+}
+
+pub fn get_entropy(c: &mut diesel::PgConnection, election_id: &uuid::Uuid) -> Vec<String> {
+    use crate::schema::entropy_contributions;
+    entropy_contributions::table
+        .filter(entropy_contributions::election_id.eq(election_id))
+        .select(entropy_contributions::entropy)
+        .get_results(c)
+        .unwrap()
+}
+
+pub enum AddEntropyError {
+    AlreadyContributed,
+}
+
+pub fn add_entropy(
+    c: &mut diesel::PgConnection,
+    election_id: &uuid::Uuid,
+    created_by: &uuid::Uuid,
+    entropy: &str,
+) -> Result<(), AddEntropyError> {
+    use crate::schema::entropy_contributions;
+    if diesel::insert_into(entropy_contributions::table)
+        .values(models::EntropyContribution {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: *created_by,
+            entropy: entropy.to_string(),
+        })
+        .execute(c)
+        .is_err()
+    {
+        return Err(AddEntropyError::AlreadyContributed);
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum RevealSeedError {
+    SecretRequired,
+    Seed(SeedError),
+}
+
+/// Fixes the seed when voting locks, mixing in the entropy given so far.
+/// The organiser has to give the secret unless the server made it up, and
+/// once revealed the seed never changes
+pub fn reveal_seed(
+    c: &mut diesel::PgConnection,
+    election: &models::Election,
+    secret: Option<&str>,
+) -> Result<(), RevealSeedError> {
+    let commitment = match election.seed_commitment.as_ref() {
+        Some(commitment) if !election.seed_revealed => commitment,
+        _ => return Ok(()),
+    };
+    let secret = match election.seed_secret.as_deref().or(secret) {
+        Some(secret) => secret,
+        None => return Err(RevealSeedError::SecretRequired),
+    };
+
+    let entropy = get_entropy(c, &election.id);
+    let derivation =
+        SeedDerivation::reveal(commitment, secret, &entropy).map_err(RevealSeedError::Seed)?;
+
+    use crate::schema::elections;
+    diesel::update(elections::table)
+        .filter(elections::id.eq(election.id))
+        .set((
+            elections::seed_secret.eq(derivation.secret),
+            elections::seed_revealed.eq(true),
+            elections::seed_entropy.eq(derivation
+                .entropy
+                .into_iter()
+                .map(Some)
+                .collect::<Vec<Option<String>>>()),
+        ))
+        .execute(c)
+        .unwrap();
+
+    Ok(())
+}
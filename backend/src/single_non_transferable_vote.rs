@@ -15,37 +15,29 @@ pub struct SNTVElectionModeled {
     pub sntv_election: SNTVElection,
 }
 
-impl Into<PublicElection> for SNTVElectionModeled {
-    fn into(self) -> PublicElection {
+impl From<SNTVElectionModeled> for PublicElection {
+    fn from(val: SNTVElectionModeled) -> Self {
         PublicElection {
-            id: self.election.id.to_string(),
-            title: self.election.title.to_string(),
-            options: self.sntv_election.options,
-            require_token: self.election.requires_token,
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            options: val.sntv_election.options,
+            require_token: val.election.requires_token,
         }
     }
 }
 
 pub fn get_election(c: &mut diesel::PgConnection, id: &uuid::Uuid) -> Option<SNTVElectionModeled> {
-    let base_election = match elections::get_election(c, id) {
-        Some(election) => election,
-        None => return None,
-    };
+    let base_election = elections::get_election(c, id)?;
 
     use crate::schema::single_non_transferable_elections;
     let single_non_transferable_elections: crate::models::SNTVElection =
-        match single_non_transferable_elections::table
+        single_non_transferable_elections::table
             .filter(single_non_transferable_elections::election_id.eq(id))
             .first::<crate::models::SNTVElection>(c)
             .optional()
-            .unwrap()
-        {
-            Some(e) => e,
-            None => return None,
-        };
+            .unwrap()?;
 
     Some(SNTVElectionModeled {
-        election: base_election,
         sntv_election: SNTVElection {
             options: single_non_transferable_elections
                 .options
@@ -53,7 +45,10 @@ pub fn get_election(c: &mut diesel::PgConnection, id: &uuid::Uuid) -> Option<SNT
                 .map(|i| i.unwrap())
                 .collect(),
             elected_count: single_non_transferable_elections.elected_count as usize,
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
         },
+        election: base_election,
     })
 }
 
@@ -71,6 +66,8 @@ pub fn add_election(c: &mut diesel::PgConnection, arg: SNTVCreateElection) -> Cr
         c,
         &arg.election_base.title,
         arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
     );
 
     use crate::schema::single_non_transferable_elections;
@@ -83,7 +80,7 @@ pub fn add_election(c: &mut diesel::PgConnection, arg: SNTVCreateElection) -> Cr
         .execute(c)
         .unwrap();
 
-    return result;
+    result
 }
 
 pub struct SNTVVoteModeled(pub SNTVVote);
@@ -92,6 +89,7 @@ impl From<crate::models::SNTVVote> for SNTVVote {
     fn from(v: crate::models::SNTVVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             votes: v.votes.into_iter().map(|i| i.unwrap()).collect(),
         }
     }
@@ -101,8 +99,10 @@ impl SNTVVoteModeled {
     fn make_model(&self, election_id: &uuid::Uuid) -> crate::models::SNTVVote {
         crate::models::SNTVVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
+            election_id: *election_id,
             created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
             votes: self.0.votes.clone().into_iter().map(Some).collect(),
         }
     }
@@ -7,9 +7,27 @@ use crate::{
     models,
 };
 
-create_election!(SinglePartyElection);
+use voting_systems::single_party::{SinglePartyElection, SinglePartyResult, SinglePartyVote};
 
-create_get_election!(crate::schema::single_party_elections, SinglePartyElection);
+create_election!(
+    SinglePartyElectionModeled,
+    single_party_election,
+    SinglePartyElection
+);
+
+impl SinglePartyElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        Self {
+            election,
+            single_party_election: SinglePartyElection { options },
+        }
+    }
+}
+
+create_get_election!(
+    crate::schema::single_party_elections,
+    SinglePartyElectionModeled
+);
 
 #[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
 pub struct SinglePartyCreateElection {
@@ -25,28 +43,27 @@ create_add_election!(
     crate::schema::single_party_elections
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SinglePartyVote {
-    pub created_by: uuid::Uuid,
-    pub voted: bool,
-}
+pub struct SinglePartyVoteModeled(pub SinglePartyVote);
 
 impl From<models::SinglePartyVote> for SinglePartyVote {
     fn from(v: models::SinglePartyVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             voted: v.voted,
         }
     }
 }
 
-impl SinglePartyVote {
+impl SinglePartyVoteModeled {
     fn make_model(&self, election_id: &uuid::Uuid) -> models::SinglePartyVote {
         models::SinglePartyVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            voted: self.voted,
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            voted: self.0.voted,
         }
     }
 }
@@ -60,26 +77,12 @@ create_get_votes!(
 create_add_vote!(
     crate::schema::single_party_votes,
     models::SinglePartyVote,
-    SinglePartyVote
+    SinglePartyVoteModeled
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SinglePartyResult {
-    pub options: Vec<String>,
-    pub filled_votes: usize,
-    pub blank_votes: usize,
-    pub won: bool,
-    pub vote_count: usize,
-}
-
-pub fn get_result(election: &SinglePartyElection, votes: &[SinglePartyVote]) -> SinglePartyResult {
-    let valid_votes = votes.iter().filter(|i| i.voted).count();
-
-    SinglePartyResult {
-        options: election.options.clone(),
-        filled_votes: valid_votes,
-        blank_votes: votes.len() - valid_votes,
-        won: valid_votes > votes.len() / 2,
-        vote_count: votes.len(),
-    }
+pub fn get_result(
+    election: &SinglePartyElectionModeled,
+    votes: &[SinglePartyVote],
+) -> SinglePartyResult {
+    voting_systems::single_party::get_result(&election.single_party_election, votes)
 }
@@ -4,11 +4,32 @@ use serde::{Deserialize, Serialize};
 use crate::{
     create_add_election, create_add_vote, create_election, create_get_election, create_get_votes,
     elections::{self, CreateElection, CreateElectionResult},
+    models,
 };
 
-create_election!(StarElection);
+use voting_systems::{
+    multi_winner_star::{MultiStarElection, MultiStarMethod, MultiStarResult},
+    star::{StarElection, StarResult, StarVote},
+};
+
+create_election!(StarElectionModeled, star_election, StarElection);
+
+impl StarElectionModeled {
+    pub fn new(election: models::Election, options: Vec<String>) -> Self {
+        let seed = crate::seed::get_seed(&election);
+        let tie_break = crate::elections::tie_break_from_model(&election.tie_break);
+        Self {
+            election,
+            star_election: StarElection {
+                options,
+                seed,
+                tie_break,
+            },
+        }
+    }
+}
 
-create_get_election!(crate::schema::star_elections, StarElection);
+create_get_election!(crate::schema::star_elections, StarElectionModeled);
 
 #[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
 pub struct StarCreateElection {
@@ -20,117 +41,55 @@ pub struct StarCreateElection {
 
 create_add_election!(
     StarCreateElection,
-    crate::models::StarElection,
+    models::StarElection,
     crate::schema::star_elections
 );
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StarVote {
-    pub created_by: uuid::Uuid,
-    pub votes: Vec<usize>,
-}
+pub struct StarVoteModeled(pub StarVote);
 
-impl From<crate::models::StarVote> for StarVote {
-    fn from(v: crate::models::StarVote) -> Self {
+impl From<models::StarVote> for StarVote {
+    fn from(v: models::StarVote) -> Self {
         Self {
             created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
             votes: v.votes.into_iter().map(|i| i.unwrap() as usize).collect(),
         }
     }
 }
 
-impl StarVote {
-    fn make_model(&self, election_id: &uuid::Uuid) -> crate::models::StarVote {
-        crate::models::StarVote {
+impl StarVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::StarVote {
+        models::StarVote {
             id: uuid::Uuid::new_v4(),
-            election_id: election_id.clone(),
-            created_by: self.created_by,
-            votes: self
-                .votes
-                .clone()
-                .into_iter()
-                .map(|i| Some(i as i32))
-                .collect(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            votes: self.0.votes.iter().map(|i| Some(*i as i32)).collect(),
         }
     }
 }
 
-create_get_votes!(crate::schema::star_votes, crate::models::StarVote, StarVote);
-
-create_add_vote!(crate::schema::star_votes, crate::models::StarVote, StarVote);
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StarTally {
-    pub option_index: usize,
-    pub points_count: usize,
-}
+create_get_votes!(crate::schema::star_votes, models::StarVote, StarVote);
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StarRunoffScore {
-    pub option_index: usize,
-    pub vote_count: usize,
-}
+create_add_vote!(crate::schema::star_votes, models::StarVote, StarVoteModeled);
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StarResult {
-    pub options: Vec<String>,
-    pub points_tally: Vec<StarTally>,
-    pub runoff: Vec<StarRunoffScore>,
-    pub winner: usize,
-    pub vote_count: usize,
-    pub votes: Vec<StarVote>,
+pub fn get_result(election: &StarElectionModeled, votes: &[StarVote]) -> StarResult {
+    voting_systems::star::get_result(&election.star_election, votes)
 }
 
-pub fn get_result(election: &StarElection, votes: &[StarVote]) -> StarResult {
-    let mut points_tally = vec![0; election.options.len()];
-    for vote in votes {
-        for (i, points) in vote.votes.iter().enumerate() {
-            points_tally[i] += *points as usize;
-        }
-    }
-
-    let mut points_tally: Vec<StarTally> = points_tally
-        .into_iter()
-        .enumerate()
-        .map(|(i, points)| StarTally {
-            option_index: i,
-            points_count: points,
-        })
-        .collect();
-    points_tally.sort_by(|a, b| b.points_count.cmp(&a.points_count));
-
-    // Run off
-    let mut a = StarRunoffScore {
-        option_index: points_tally[0].option_index,
-        vote_count: 0,
-    };
-    let mut b = StarRunoffScore {
-        option_index: points_tally[1].option_index,
-        vote_count: 0,
+pub fn get_multi_winner_result(
+    election: &StarElectionModeled,
+    votes: &[StarVote],
+    method: MultiStarMethod,
+    seat_count: usize,
+) -> MultiStarResult {
+    let multi_winner_election = MultiStarElection {
+        options: election.star_election.options.clone(),
+        seat_count,
+        method,
+        seed: crate::seed::get_seed(&election.election),
+        tie_break: election.star_election.tie_break.clone(),
     };
-    for vote in votes {
-        let a_votes = vote.votes[a.option_index];
-        let b_votes = vote.votes[b.option_index];
-        if a_votes == b_votes {
-            continue;
-        }
-
-        if a_votes > b_votes {
-            a.vote_count += 1;
-        } else {
-            b.vote_count += 1;
-        }
-    }
-
-    let mut runoff = vec![a, b];
-    runoff.sort_by(|a, b| b.vote_count.cmp(&a.vote_count));
-
-    StarResult {
-        options: election.options.clone(),
-        points_tally,
-        winner: runoff[0].option_index,
-        runoff,
-        vote_count: votes.len(),
-        votes: votes.to_vec(),
-    }
+    voting_systems::multi_winner_star::get_result(&multi_winner_election, votes)
 }
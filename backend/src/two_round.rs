@@ -0,0 +1,177 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    create_add_vote, create_get_votes,
+    elections::{self, CreateElection, CreateElectionResult},
+    models,
+};
+
+use voting_systems::two_round::{TwoRoundElection, TwoRoundResult, TwoRoundVote};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwoRoundElectionModeled {
+    pub election: models::Election,
+    pub two_round_election: TwoRoundElection,
+}
+
+impl TwoRoundElectionModeled {
+    /// The round being voted on, the second opens once finalists are stored
+    pub fn round(&self) -> usize {
+        if self.two_round_election.finalists.is_some() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicTwoRoundElection {
+    pub id: String,
+    pub title: String,
+    pub options: Vec<String>,
+    pub require_token: bool,
+    pub round: usize,
+    pub finalists: Option<Vec<usize>>,
+}
+
+impl From<TwoRoundElectionModeled> for PublicTwoRoundElection {
+    fn from(val: TwoRoundElectionModeled) -> Self {
+        PublicTwoRoundElection {
+            id: val.election.id.to_string(),
+            title: val.election.title.to_string(),
+            round: val.round(),
+            options: val.two_round_election.options,
+            require_token: val.election.requires_token,
+            finalists: val.two_round_election.finalists,
+        }
+    }
+}
+
+pub fn get_election(
+    c: &mut diesel::PgConnection,
+    id: &uuid::Uuid,
+) -> Option<TwoRoundElectionModeled> {
+    let base_election = elections::get_election(c, id)?;
+
+    use crate::schema::two_round_elections;
+    let two_round_election: models::TwoRoundElection = two_round_elections::table
+        .filter(two_round_elections::election_id.eq(id))
+        .first::<models::TwoRoundElection>(c)
+        .optional()
+        .unwrap()?;
+
+    Some(TwoRoundElectionModeled {
+        two_round_election: TwoRoundElection {
+            options: two_round_election
+                .options
+                .into_iter()
+                .map(|i| i.unwrap())
+                .collect(),
+            finalists: two_round_election
+                .finalists
+                .map(|finalists| finalists.into_iter().map(|i| i.unwrap() as usize).collect()),
+            seed: crate::seed::get_seed(&base_election),
+            tie_break: crate::elections::tie_break_from_model(&base_election.tie_break),
+        },
+        election: base_election,
+    })
+}
+
+#[derive(Serialize, Deserialize, serde_valid::Validate, Debug, Clone)]
+pub struct TwoRoundCreateElection {
+    pub election_base: CreateElection,
+    #[validate(max_items = 100)]
+    #[validate(custom = crate::elections::valid_election_option)]
+    pub options: Vec<String>,
+}
+
+pub fn add_election(
+    c: &mut diesel::PgConnection,
+    arg: TwoRoundCreateElection,
+) -> CreateElectionResult {
+    let result = crate::elections::add_election(
+        c,
+        &arg.election_base.title,
+        arg.election_base.requires_token,
+        &arg.election_base.tie_break,
+        arg.election_base.seed_commitment.as_deref(),
+    );
+
+    use crate::schema::two_round_elections;
+    diesel::insert_into(two_round_elections::table)
+        .values(models::TwoRoundElection {
+            election_id: result.election_id,
+            options: arg.options.into_iter().map(Some).collect(),
+            finalists: None,
+        })
+        .execute(c)
+        .unwrap();
+
+    result
+}
+
+pub fn open_second_round(
+    c: &mut diesel::PgConnection,
+    election_id: &uuid::Uuid,
+    finalists: &[usize],
+) {
+    use crate::schema::two_round_elections;
+
+    diesel::update(two_round_elections::table)
+        .filter(two_round_elections::election_id.eq(election_id))
+        .set(
+            two_round_elections::finalists.eq(Some(
+                finalists
+                    .iter()
+                    .map(|i| Some(*i as i32))
+                    .collect::<Vec<Option<i32>>>(),
+            )),
+        )
+        .execute(c)
+        .unwrap();
+}
+
+pub struct TwoRoundVoteModeled(pub TwoRoundVote);
+
+impl From<models::TwoRoundVote> for TwoRoundVote {
+    fn from(v: models::TwoRoundVote) -> Self {
+        Self {
+            created_by: v.created_by,
+            weight: crate::elections::weight_from_model(v.weight_numerator, v.weight_denominator),
+            round: v.round as usize,
+            vote: v.vote as usize,
+        }
+    }
+}
+
+impl TwoRoundVoteModeled {
+    fn make_model(&self, election_id: &uuid::Uuid) -> models::TwoRoundVote {
+        models::TwoRoundVote {
+            id: uuid::Uuid::new_v4(),
+            election_id: *election_id,
+            created_by: self.0.created_by,
+            weight_numerator: self.0.weight.numerator as i64,
+            weight_denominator: self.0.weight.denominator as i64,
+            round: self.0.round as i32,
+            vote: self.0.vote as i32,
+        }
+    }
+}
+
+create_get_votes!(
+    crate::schema::two_round_votes,
+    models::TwoRoundVote,
+    TwoRoundVote
+);
+
+create_add_vote!(
+    crate::schema::two_round_votes,
+    models::TwoRoundVote,
+    TwoRoundVoteModeled
+);
+
+pub fn get_result(election: &TwoRoundElectionModeled, votes: &[TwoRoundVote]) -> TwoRoundResult {
+    voting_systems::two_round::get_result(&election.two_round_election, votes)
+}
@@ -264,11 +264,17 @@ export interface CreateElectionResponse {
   key: string
 }
 
+export type StvTransfer = 'Random' | 'Gregory' | 'WeightedInclusiveGregory'
+
+export type StvQuota = 'Hare' | 'Droop' | 'HagenbachBischoff'
+
 export interface StvCreateElection {
   title: string
   options: string[]
   require_token: Boolean
   elected_count: number
+  transfer?: StvTransfer
+  quota?: StvQuota
 }
 
 export interface StvVote {
@@ -787,30 +793,51 @@ export interface QuotaPreferentialVicLabor2024Candidate {
   is_female: Boolean
 }
 
+export type QuotaPreferentialVicLabor2024Quota =
+  | 'DroopRoundedUp'
+  | 'DroopPlusOne'
+  | 'Hare'
+  | 'HagenbachBischoff'
+
+export type Arithmetic =
+  | { mode: 'exact' }
+  | { mode: 'fixed_point'; places: number; rounding: 'down' | 'up' | 'half_up' | 'half_even' }
+
 export interface QuotaPreferentialVicLabor2024CreateElection {
   title: string
   candidates: QuotaPreferentialVicLabor2024Candidate[]
   require_token: Boolean
   percent_female: number
   elected_count: number
+  quota?: QuotaPreferentialVicLabor2024Quota
+  arithmetic?: Arithmetic
 }
 
 export interface QuotaPreferentialVicLabor2024Paper {
   vote: QuotaPreferentialVicLabor2024Vote
-  value: number
+  value: string
 }
 
 export interface QuotaPreferentialVicLabor2024CountSheetEntry {
   option_index: number
   papers: QuotaPreferentialVicLabor2024Paper[]
   separated_papers: string[]
-  transfer_value: number
-  remainders: number
+  transfer_value: string
+  remainders: string
+}
+
+export interface QuotaPreferentialVicLabor2024SurplusTransfer {
+  from: number
+  surplus: string
+  papers: number
+  transfer_value: string
+  rounding_loss: string
 }
 
 export interface QuotaPreferentialVicLabor2024CountSheet {
   entries: Record<number, QuotaPreferentialVicLabor2024CountSheetEntry>
   exhausted_papers: QuotaPreferentialVicLabor2024Paper[]
+  transfers: QuotaPreferentialVicLabor2024SurplusTransfer[]
 }
 
 export interface QuotaPreferentialVicLabor2024CountSheetCollection {
@@ -824,7 +851,7 @@ export interface QuotaPreferentialVicLabor2024DecisionLog {
 
 export interface QuotaPreferentialVicLabor2024ElectionResult {
   elected_candidates: number[]
-  quota: number
+  quota: string
   count_sheet: QuotaPreferentialVicLabor2024CountSheetCollection
   decision_log: QuotaPreferentialVicLabor2024DecisionLog
 }
@@ -0,0 +1,2 @@
+DROP TABLE schulze_votes;
+DROP TABLE schulze_elections;
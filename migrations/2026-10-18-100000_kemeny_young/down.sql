@@ -0,0 +1,2 @@
+DROP TABLE kemeny_young_votes;
+DROP TABLE kemeny_young_elections;
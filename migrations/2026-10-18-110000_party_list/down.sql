@@ -0,0 +1,2 @@
+DROP TABLE party_list_votes;
+DROP TABLE party_list_elections;
@@ -0,0 +1,15 @@
+CREATE TABLE party_list_elections (
+    election_id UUID NOT NULL PRIMARY KEY REFERENCES elections(id),
+    options TEXT[] NOT NULL,
+    seat_count INTEGER NOT NULL,
+    method TEXT NOT NULL,
+    threshold DOUBLE PRECISION
+);
+
+CREATE TABLE party_list_votes (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    votes BOOLEAN[] NOT NULL,
+    UNIQUE (created_by, election_id)
+);
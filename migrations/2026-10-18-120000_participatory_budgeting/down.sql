@@ -0,0 +1,2 @@
+DROP TABLE participatory_budgeting_votes;
+DROP TABLE participatory_budgeting_elections;
@@ -0,0 +1,15 @@
+CREATE TABLE participatory_budgeting_elections (
+    election_id UUID NOT NULL PRIMARY KEY REFERENCES elections(id),
+    options TEXT[] NOT NULL,
+    costs BIGINT[] NOT NULL,
+    budget BIGINT NOT NULL,
+    max_score INTEGER NOT NULL
+);
+
+CREATE TABLE participatory_budgeting_votes (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    votes INTEGER[] NOT NULL,
+    UNIQUE (created_by, election_id)
+);
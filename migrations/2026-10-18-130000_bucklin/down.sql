@@ -0,0 +1,2 @@
+DROP TABLE bucklin_votes;
+DROP TABLE bucklin_elections;
@@ -0,0 +1,2 @@
+DROP TABLE two_round_votes;
+DROP TABLE two_round_elections;
@@ -0,0 +1,14 @@
+CREATE TABLE two_round_elections (
+    election_id UUID NOT NULL PRIMARY KEY REFERENCES elections(id),
+    options TEXT[] NOT NULL,
+    finalists INTEGER[]
+);
+
+CREATE TABLE two_round_votes (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    round INTEGER NOT NULL,
+    vote INTEGER NOT NULL,
+    UNIQUE (created_by, election_id, round)
+);
@@ -0,0 +1,2 @@
+DROP TABLE hare_clark_votes;
+DROP TABLE hare_clark_elections;
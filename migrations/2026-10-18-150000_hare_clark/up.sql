@@ -0,0 +1,14 @@
+CREATE TABLE hare_clark_elections (
+    election_id UUID NOT NULL PRIMARY KEY REFERENCES elections(id),
+    options TEXT[] NOT NULL,
+    vacancies INTEGER NOT NULL
+);
+
+CREATE TABLE hare_clark_votes (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    votes INTEGER[] NOT NULL,
+    rotation INTEGER NOT NULL,
+    UNIQUE (created_by, election_id)
+);
@@ -0,0 +1,2 @@
+DROP TABLE quadratic_votes;
+DROP TABLE quadratic_elections;
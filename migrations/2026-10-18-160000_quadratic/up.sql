@@ -0,0 +1,14 @@
+CREATE TABLE quadratic_elections (
+    election_id UUID NOT NULL PRIMARY KEY REFERENCES elections(id),
+    options TEXT[] NOT NULL,
+    credits INTEGER NOT NULL,
+    allow_negative BOOLEAN NOT NULL
+);
+
+CREATE TABLE quadratic_votes (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    votes INTEGER[] NOT NULL,
+    UNIQUE (created_by, election_id)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE delegations (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    delegate UUID NOT NULL,
+    UNIQUE (created_by, election_id)
+);
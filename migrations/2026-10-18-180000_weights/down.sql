@@ -0,0 +1,99 @@
+ALTER TABLE usual_judgment_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE two_round_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE three_two_one_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE stv_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE star_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE single_party_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE single_non_transferable_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE score_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE schulze_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE quota_preferential_vic_labor_2024_transferable_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE quadratic_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE preferential_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE party_list_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE participatory_budgeting_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE majority_judgment_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE kemeny_young_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE hare_clark_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE cumulative_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE condorcet_method_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE bucklin_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE borda_count_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE approval_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE anti_plurality_votes
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE delegations
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
+
+ALTER TABLE voting_tokens
+    DROP COLUMN weight_numerator,
+    DROP COLUMN weight_denominator;
@@ -0,0 +1,99 @@
+ALTER TABLE voting_tokens
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE delegations
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE anti_plurality_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE approval_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE borda_count_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE bucklin_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE condorcet_method_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE cumulative_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE hare_clark_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE kemeny_young_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE majority_judgment_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE participatory_budgeting_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE party_list_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE preferential_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE quadratic_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE quota_preferential_vic_labor_2024_transferable_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE schulze_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE score_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE single_non_transferable_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE single_party_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE star_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE stv_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE three_two_one_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE two_round_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
+
+ALTER TABLE usual_judgment_votes
+    ADD COLUMN weight_numerator BIGINT NOT NULL DEFAULT 1 CHECK (weight_numerator > 0),
+    ADD COLUMN weight_denominator BIGINT NOT NULL DEFAULT 1 CHECK (weight_denominator > 0);
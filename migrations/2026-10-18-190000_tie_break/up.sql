@@ -0,0 +1,2 @@
+ALTER TABLE elections
+    ADD COLUMN tie_break TEXT NOT NULL DEFAULT 'method';
@@ -0,0 +1,7 @@
+DROP TABLE entropy_contributions;
+
+ALTER TABLE elections
+    DROP COLUMN seed_commitment,
+    DROP COLUMN seed_secret,
+    DROP COLUMN seed_revealed,
+    DROP COLUMN seed_entropy;
@@ -0,0 +1,13 @@
+ALTER TABLE elections
+    ADD COLUMN seed_commitment TEXT,
+    ADD COLUMN seed_secret TEXT,
+    ADD COLUMN seed_revealed BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN seed_entropy TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE entropy_contributions (
+    id UUID NOT NULL PRIMARY KEY,
+    election_id UUID NOT NULL REFERENCES elections(id),
+    created_by UUID NOT NULL,
+    entropy TEXT NOT NULL,
+    UNIQUE (created_by, election_id)
+);
@@ -0,0 +1,6 @@
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    DROP COLUMN arithmetic;
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    DROP COLUMN quota;
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    DROP COLUMN percent_female;
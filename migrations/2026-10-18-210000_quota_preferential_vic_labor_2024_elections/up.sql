@@ -0,0 +1,6 @@
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    ADD COLUMN percent_female DOUBLE PRECISION NOT NULL DEFAULT 0.5 CHECK (percent_female >= 0 AND percent_female <= 1);
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    ADD COLUMN quota TEXT NOT NULL DEFAULT 'droop_rounded_up';
+ALTER TABLE quota_preferential_vic_labor_2024_elections
+    ADD COLUMN arithmetic TEXT NOT NULL DEFAULT 'fixed_point:0:down';
@@ -0,0 +1,4 @@
+ALTER TABLE stv_elections
+    DROP COLUMN quota;
+ALTER TABLE stv_elections
+    DROP COLUMN transfer;
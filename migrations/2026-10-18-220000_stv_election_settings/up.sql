@@ -0,0 +1,4 @@
+ALTER TABLE stv_elections
+    ADD COLUMN transfer TEXT NOT NULL DEFAULT 'random';
+ALTER TABLE stv_elections
+    ADD COLUMN quota TEXT NOT NULL DEFAULT 'hare';
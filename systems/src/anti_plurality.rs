@@ -0,0 +1,140 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_lowest_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AntiPluralityElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AntiPluralityVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The option being voted against
+    pub vote: usize,
+}
+
+impl_weighted_ballot!(AntiPluralityVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AntiPluralityTally {
+    pub option_index: usize,
+    pub vote_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AntiPluralityResult {
+    pub options: Vec<String>,
+    pub votes_tally: Vec<AntiPluralityTally>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub votes: Vec<AntiPluralityVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(
+    election: &AntiPluralityElection,
+    votes: &[AntiPluralityVote],
+) -> AntiPluralityResult {
+    let mut count_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        count_tally[vote.vote] += weight;
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| {
+                let option_count = election.options.len();
+                ((0..option_count).map(|i| i != vote.vote).collect(), weight)
+            }),
+        )
+    });
+    // Fewer votes against puts an option ahead
+    tie_breaker.record_round(count_tally.iter().map(|i| -(*i as f64)));
+    let fewest = count_tally.iter().min().copied().unwrap_or_default();
+    let bottom = (0..count_tally.len())
+        .filter(|i| count_tally[*i] == fewest)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &bottom, |_| bottom[0]);
+
+    let mut count_tally: Vec<AntiPluralityTally> = count_tally
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| AntiPluralityTally {
+            option_index: i,
+            vote_count: count,
+        })
+        .collect();
+    count_tally.sort_by_key(|a| a.vote_count);
+
+    AntiPluralityResult {
+        options: election.options.clone(),
+        winner,
+        votes_tally: count_tally,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct AntiPlurality;
+
+impl VotingSystem for AntiPlurality {
+    type Ballot = AntiPluralityVote;
+    type Config = AntiPluralityElection;
+    type Outcome = AntiPluralityResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            vec![report.winner],
+            rank_by_lowest_score(
+                report
+                    .votes_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.vote_count)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result_fewest_votes_against_wins() {
+        let election = AntiPluralityElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = [0, 0, 2]
+            .into_iter()
+            .map(|vote| AntiPluralityVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                vote,
+            })
+            .collect::<Vec<_>>();
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 1);
+    }
+}
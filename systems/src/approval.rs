@@ -0,0 +1,179 @@
+use std::cmp::Reverse;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApprovalElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApprovalVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Whether each option is approved
+    pub votes: Vec<bool>,
+}
+
+impl_weighted_ballot!(ApprovalVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApprovalTally {
+    pub option_index: usize,
+    pub approval_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApprovalResult {
+    pub options: Vec<String>,
+    pub winner: usize,
+    pub approve_tally: Vec<ApprovalTally>,
+    pub vote_count: usize,
+    pub votes: Vec<ApprovalVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(election: &ApprovalElection, votes: &[ApprovalVote]) -> ApprovalResult {
+    let mut vote_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, v) in vote.votes.iter().enumerate() {
+            if *v {
+                vote_tally[option_index] += weight;
+            }
+        }
+    }
+
+    let mut approve_tally: Vec<ApprovalTally> = vote_tally
+        .iter()
+        .enumerate()
+        .map(|(option_index, approval_count)| ApprovalTally {
+            option_index,
+            approval_count: *approval_count,
+        })
+        .collect();
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+    tie_breaker.record_round(vote_tally.iter().map(|i| *i as f64));
+    let most = vote_tally.iter().max().copied().unwrap_or_default();
+    let top = (0..vote_tally.len())
+        .filter(|i| vote_tally[*i] == most)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &top, |_| top[0]);
+
+    approve_tally.sort_by_key(|a| Reverse(a.approval_count));
+
+    ApprovalResult {
+        options: election.options.clone(),
+        winner,
+        approve_tally,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Approval;
+
+impl VotingSystem for Approval {
+    type Ballot = ApprovalVote;
+    type Config = ApprovalElection;
+    type Outcome = ApprovalResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            vec![report.winner],
+            rank_by_score(
+                report
+                    .approve_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.approval_count)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tie_break::TieResolution;
+
+    #[test]
+    fn test_get_result() {
+        let election = ApprovalElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            ApprovalVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![true, true, false],
+            },
+            ApprovalVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![false, true, true],
+            },
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.approve_tally[0].approval_count, 2);
+    }
+
+    #[test]
+    fn test_get_result_records_tie() {
+        let mut election = ApprovalElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+        let votes = vec![
+            ApprovalVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![true, false, false],
+            },
+            ApprovalVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![false, false, true],
+            },
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.ties.len(), 1);
+        assert_eq!(result.ties[0].tied, vec![0, 2]);
+        assert_eq!(result.ties[0].resolved_by, TieResolution::Method);
+
+        election.tie_break = TieBreak::FixedOrder { order: vec![2, 0] };
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 2);
+        assert_eq!(result.ties[0].kind, TieKind::Elect);
+        assert_eq!(result.ties[0].resolved_by, TieResolution::FixedOrder);
+    }
+}
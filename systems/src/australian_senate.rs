@@ -0,0 +1,779 @@
+use std::collections::BTreeMap;
+
+use log::info;
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_rankings, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_removal, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+/// Boxes voters are told to number above the line
+pub const ABOVE_THE_LINE_INSTRUCTED: usize = 6;
+/// Boxes voters are told to number below the line
+pub const BELOW_THE_LINE_INSTRUCTED: usize = 12;
+/// Under the savings provisions a single box above the line is still formal
+pub const ABOVE_THE_LINE_MINIMUM: usize = 1;
+/// Under the savings provisions 1 to 6 below the line is still formal
+pub const BELOW_THE_LINE_MINIMUM: usize = 6;
+
+/// Paper values are kept in fixed point with this many parts to a vote, a
+/// transfer value truncates anything smaller
+pub(crate) const VOTE_SCALE: u64 = 1_000_000_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SenateGroup {
+    pub name: String,
+    /// Candidate indexes in the order the group listed them
+    pub candidates: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SenateElection {
+    pub candidates: Vec<String>,
+    /// Candidates outside every group can only be numbered below the line
+    pub groups: Vec<SenateGroup>,
+    pub vacancies: usize,
+    /// Seeds the RNG used when candidates are still level after a count-back
+    pub seed: String,
+    /// Under `TieBreak::Method` level candidates go to a count-back through
+    /// the counts from the latest, then by lot
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SenateVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The number written in each group's box, if any
+    pub above_the_line: Vec<Option<usize>>,
+    /// The number written in each candidate's box, if any
+    pub below_the_line: Vec<Option<usize>>,
+}
+
+impl_weighted_ballot!(SenateVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BallotFormality {
+    AboveTheLine,
+    BelowTheLine,
+    Informal,
+}
+
+/// How a ballot paper was read
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReadBallot {
+    pub formality: BallotFormality,
+    /// Candidates in order of preference, empty when informal
+    pub preferences: Vec<usize>,
+    /// Formal only under the savings provisions, numbered in sequence but
+    /// fewer boxes than voters are told to
+    pub saved: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BallotSummary {
+    pub above_the_line: usize,
+    pub below_the_line: usize,
+    pub informal: usize,
+    pub saved_above_the_line: usize,
+    pub saved_below_the_line: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CountSheetColumn {
+    /// Papers gained this count, negative when they were passed on
+    pub papers_transferred: i64,
+    /// Votes gained this count, negative when they were passed on
+    pub votes_transferred: i64,
+    /// Papers held at the end of the count
+    pub papers: usize,
+    /// Progressive total at the end of the count
+    pub votes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CountAction {
+    FirstPreferences,
+    /// Every paper of the elected candidate is passed on with its value
+    /// multiplied by `transfer_value`, the surplus over their votes
+    Surplus {
+        candidate: usize,
+        transfer_value: f64,
+    },
+    /// More than one candidate is a bulk exclusion
+    Exclusion {
+        candidates: Vec<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CountSheet {
+    /// Starting from 1
+    pub count: usize,
+    pub action: CountAction,
+    /// One column per candidate
+    pub candidates: Vec<CountSheetColumn>,
+    pub exhausted: CountSheetColumn,
+    /// Progressive total of votes lost when transfers are truncated to
+    /// whole votes. Fractions regrouped into new parcels on an exclusion can
+    /// add up to whole votes again, which wins some of the loss back
+    pub loss_by_fraction: i64,
+    /// Elected at the end of the count, in order
+    pub elected: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SenateResult {
+    pub candidates: Vec<String>,
+    pub vacancies: usize,
+    pub ballots: BallotSummary,
+    pub quota: u64,
+    pub count_sheets: Vec<CountSheet>,
+    /// In the order they were elected
+    pub elected: Vec<usize>,
+    /// In the order they were excluded
+    pub excluded: Vec<usize>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Boxes in the order they were numbered from 1, stopping at the first
+/// number that is missing or repeated
+fn read_sequence(marks: &[Option<usize>]) -> Vec<usize> {
+    let mut sequence = Vec::new();
+    loop {
+        let number = Some(sequence.len() + 1);
+        let mut numbered = (0..marks.len()).filter(|i| marks[*i] == number);
+        match (numbered.next(), numbered.next()) {
+            (Some(i), None) => sequence.push(i),
+            _ => break,
+        }
+    }
+    sequence
+}
+
+/// Reads a paper below the line when enough of it is in sequence, otherwise
+/// above the line with each group expanded into its candidates
+pub fn read_ballot(election: &SenateElection, vote: &SenateVote) -> ReadBallot {
+    let candidate_count = election.candidates.len();
+    let below_the_line = read_sequence(&vote.below_the_line);
+    if below_the_line.len() >= BELOW_THE_LINE_MINIMUM.min(candidate_count.max(1)) {
+        return ReadBallot {
+            formality: BallotFormality::BelowTheLine,
+            saved: below_the_line.len() < BELOW_THE_LINE_INSTRUCTED.min(candidate_count),
+            preferences: below_the_line,
+        };
+    }
+
+    let above_the_line = read_sequence(&vote.above_the_line);
+    if above_the_line.len() >= ABOVE_THE_LINE_MINIMUM {
+        return ReadBallot {
+            formality: BallotFormality::AboveTheLine,
+            saved: above_the_line.len() < ABOVE_THE_LINE_INSTRUCTED.min(election.groups.len()),
+            preferences: above_the_line
+                .iter()
+                .flat_map(|group| election.groups[*group].candidates.iter().copied())
+                .collect(),
+        };
+    }
+
+    ReadBallot {
+        formality: BallotFormality::Informal,
+        preferences: vec![],
+        saved: false,
+    }
+}
+
+struct Paper {
+    preferences: Vec<usize>,
+    /// Index into `preferences` of the candidate holding the paper
+    position: usize,
+    value: u64,
+}
+
+/// Papers and votes held at some point, to work out a count's transfers
+struct Holdings {
+    papers: Vec<usize>,
+    votes: Vec<u64>,
+    exhausted_papers: usize,
+    exhausted_votes: u64,
+}
+
+struct SenateCount {
+    tie_breaker: TieBreaker,
+    quota: u64,
+    vacancies: usize,
+    papers: Vec<Paper>,
+    piles: Vec<Vec<usize>>,
+    votes: Vec<u64>,
+    exhausted_papers: usize,
+    exhausted_votes: u64,
+    loss_by_fraction: i64,
+    continuing: Vec<bool>,
+    elected: Vec<usize>,
+    excluded: Vec<usize>,
+    /// Elected candidates whose surplus hasn't been passed on yet
+    pending_surpluses: Vec<usize>,
+    count_sheets: Vec<CountSheet>,
+}
+
+impl SenateCount {
+    fn holdings(&self) -> Holdings {
+        Holdings {
+            papers: self.piles.iter().map(|pile| pile.len()).collect(),
+            votes: self.votes.clone(),
+            exhausted_papers: self.exhausted_papers,
+            exhausted_votes: self.exhausted_votes,
+        }
+    }
+
+    fn record(&mut self, action: CountAction, before: Holdings) {
+        let column =
+            |before_papers: usize, before_votes: u64, papers: usize, votes: u64| CountSheetColumn {
+                papers_transferred: papers as i64 - before_papers as i64,
+                votes_transferred: votes as i64 - before_votes as i64,
+                papers,
+                votes,
+            };
+        let count_sheet = CountSheet {
+            count: self.count_sheets.len() + 1,
+            action,
+            candidates: (0..self.votes.len())
+                .map(|i| {
+                    column(
+                        before.papers[i],
+                        before.votes[i],
+                        self.piles[i].len(),
+                        self.votes[i],
+                    )
+                })
+                .collect(),
+            exhausted: column(
+                before.exhausted_papers,
+                before.exhausted_votes,
+                self.exhausted_papers,
+                self.exhausted_votes,
+            ),
+            loss_by_fraction: self.loss_by_fraction,
+            elected: vec![],
+        };
+        self.count_sheets.push(count_sheet);
+        self.tie_breaker
+            .record_round(self.votes.iter().map(|i| *i as f64));
+    }
+
+    fn continuing_candidates(&self) -> Vec<usize> {
+        (0..self.continuing.len())
+            .filter(|i| self.continuing[*i])
+            .collect()
+    }
+
+    /// Passes the papers of `from` to their next continuing preference,
+    /// scaling each paper's value by `factor` for a surplus. The votes that
+    /// leave `from` and aren't credited anywhere are lost by fraction
+    fn transfer(&mut self, from: &[usize], factor: Option<(u64, u64)>, votes_leaving: u64) {
+        let mut parcels: BTreeMap<Option<usize>, Vec<usize>> = BTreeMap::new();
+        for source in from {
+            for paper_index in std::mem::take(&mut self.piles[*source]) {
+                let paper = &mut self.papers[paper_index];
+                if let Some((numerator, denominator)) = factor {
+                    paper.value =
+                        (paper.value as u128 * numerator as u128 / denominator as u128) as u64;
+                }
+                let next = (paper.position + 1..paper.preferences.len())
+                    .find(|i| self.continuing[paper.preferences[*i]]);
+                if let Some(next) = next {
+                    paper.position = next;
+                }
+                parcels
+                    .entry(next.map(|i| paper.preferences[i]))
+                    .or_default()
+                    .push(paper_index);
+            }
+        }
+
+        let mut credited = 0;
+        for (destination, parcel) in parcels {
+            let value: u64 = parcel.iter().map(|i| self.papers[*i].value).sum();
+            let votes = value / VOTE_SCALE;
+            credited += votes;
+            match destination {
+                Some(candidate) => {
+                    self.votes[candidate] += votes;
+                    self.piles[candidate].extend(parcel);
+                }
+                None => {
+                    self.exhausted_votes += votes;
+                    self.exhausted_papers += parcel.len();
+                }
+            }
+        }
+        self.loss_by_fraction += votes_leaving as i64 - credited as i64;
+    }
+
+    /// Breaks a tie between level candidates by the election's policy, by
+    /// default narrowing them by the most recent count at which they
+    /// differed, keeping the highest or lowest, then drawing by lot
+    fn count_back(&mut self, candidates: &[usize], highest: bool) -> usize {
+        let kind = if highest {
+            TieKind::Elect
+        } else {
+            TieKind::Exclude
+        };
+        let count_sheets = &self.count_sheets;
+        self.tie_breaker.break_tie(kind, candidates, |rng| {
+            let mut level = candidates.to_vec();
+            for count_sheet in count_sheets.iter().rev() {
+                if level.len() == 1 {
+                    break;
+                }
+                let votes = level.iter().map(|i| count_sheet.candidates[*i].votes);
+                let target = if highest { votes.max() } else { votes.min() };
+                level.retain(|i| Some(count_sheet.candidates[*i].votes) == target);
+            }
+            *level.choose(rng).unwrap()
+        })
+    }
+
+    /// Elects `candidates` from the most votes down
+    fn elect(&mut self, candidates: &[usize]) {
+        let mut candidates = candidates.to_vec();
+        while !candidates.is_empty() {
+            let most = candidates.iter().map(|i| self.votes[*i]).max().unwrap();
+            let level = candidates
+                .iter()
+                .copied()
+                .filter(|i| self.votes[*i] == most)
+                .collect::<Vec<usize>>();
+            let candidate = self.count_back(&level, true);
+            candidates.retain(|i| *i != candidate);
+
+            info!(
+                "Electing {} with {} votes",
+                candidate, self.votes[candidate]
+            );
+            self.continuing[candidate] = false;
+            self.elected.push(candidate);
+            if self.votes[candidate] > self.quota {
+                self.pending_surpluses.push(candidate);
+            }
+            if let Some(count_sheet) = self.count_sheets.last_mut() {
+                count_sheet.elected.push(candidate);
+            }
+        }
+    }
+
+    /// The lowest candidates whose votes together are fewer than the next
+    /// candidate's and the votes still needed to fill the vacancies, so
+    /// excluding them at once can't change who is excluded or elected
+    fn bulk_exclusion(&self) -> Option<Vec<usize>> {
+        let mut continuing = self.continuing_candidates();
+        continuing.sort_by_key(|i| self.votes[*i]);
+        let remaining = self.vacancies - self.elected.len();
+        let vacancy_shortfall: u64 = continuing
+            .iter()
+            .rev()
+            .take(remaining)
+            .map(|i| self.quota.saturating_sub(self.votes[*i]))
+            .sum();
+
+        (2..=continuing.len().saturating_sub(remaining))
+            .rev()
+            .find(|k| {
+                let notional: u64 = continuing[..*k].iter().map(|i| self.votes[*i]).sum();
+                notional < self.votes[continuing[*k]] && notional < vacancy_shortfall
+            })
+            .map(|k| continuing[..k].to_vec())
+    }
+}
+
+pub fn get_result(election: &SenateElection, votes: &[SenateVote]) -> SenateResult {
+    let candidate_count = election.candidates.len();
+
+    let mut ballots = BallotSummary::default();
+    let mut papers = Vec::new();
+    for (vote, weight) in weighted(votes) {
+        let read = read_ballot(election, vote);
+        match read.formality {
+            BallotFormality::AboveTheLine => {
+                ballots.above_the_line += 1;
+                ballots.saved_above_the_line += read.saved as usize;
+            }
+            BallotFormality::BelowTheLine => {
+                ballots.below_the_line += 1;
+                ballots.saved_below_the_line += read.saved as usize;
+            }
+            BallotFormality::Informal => {
+                ballots.informal += 1;
+                continue;
+            }
+        }
+        papers.push(Paper {
+            preferences: read.preferences,
+            position: 0,
+            value: VOTE_SCALE * weight as u64,
+        });
+    }
+
+    let formal_votes: u64 = papers.iter().map(|i| i.value / VOTE_SCALE).sum();
+    let quota = formal_votes / (election.vacancies as u64 + 1) + 1;
+    info!("Formal votes {} quota {}", formal_votes, quota);
+
+    let mut count = SenateCount {
+        tie_breaker: TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+            let read = weighted(votes)
+                .map(|(vote, weight)| (read_ballot(election, vote).preferences, weight))
+                .collect::<Vec<(Vec<usize>, usize)>>();
+            borda_from_rankings(
+                candidate_count,
+                read.iter()
+                    .map(|(preferences, weight)| (preferences.as_slice(), *weight)),
+            )
+        }),
+        quota,
+        vacancies: election.vacancies.min(candidate_count),
+        papers,
+        piles: vec![vec![]; candidate_count],
+        votes: vec![0; candidate_count],
+        exhausted_papers: 0,
+        exhausted_votes: 0,
+        loss_by_fraction: 0,
+        continuing: vec![true; candidate_count],
+        elected: vec![],
+        excluded: vec![],
+        pending_surpluses: vec![],
+        count_sheets: vec![],
+    };
+
+    let before = count.holdings();
+    for (paper_index, paper) in count.papers.iter().enumerate() {
+        count.piles[paper.preferences[0]].push(paper_index);
+        count.votes[paper.preferences[0]] += paper.value / VOTE_SCALE;
+    }
+    count.record(CountAction::FirstPreferences, before);
+
+    loop {
+        let reached = count
+            .continuing_candidates()
+            .into_iter()
+            .filter(|i| count.votes[*i] >= quota)
+            .collect::<Vec<usize>>();
+        count.elect(&reached);
+
+        if count.elected.len() == count.vacancies {
+            break;
+        }
+        let continuing = count.continuing_candidates();
+        if continuing.len() <= count.vacancies - count.elected.len() {
+            count.elect(&continuing);
+            break;
+        }
+
+        let before = count.holdings();
+        if !count.pending_surpluses.is_empty() {
+            let candidate = count.pending_surpluses.remove(0);
+            let total = count.votes[candidate];
+            let surplus = total - quota;
+            info!("Passing on the surplus of {} for {}", surplus, candidate);
+            count.transfer(&[candidate], Some((surplus, total)), surplus);
+            count.votes[candidate] = quota;
+            count.record(
+                CountAction::Surplus {
+                    candidate,
+                    transfer_value: surplus as f64 / total as f64,
+                },
+                before,
+            );
+            continue;
+        }
+
+        let excluding = match count.bulk_exclusion() {
+            Some(candidates) => candidates,
+            None => {
+                let fewest = continuing.iter().map(|i| count.votes[*i]).min().unwrap();
+                let level = continuing
+                    .iter()
+                    .copied()
+                    .filter(|i| count.votes[*i] == fewest)
+                    .collect::<Vec<usize>>();
+                vec![count.count_back(&level, false)]
+            }
+        };
+        info!("Excluding {:?}", excluding);
+
+        let votes_leaving = excluding.iter().map(|i| count.votes[*i]).sum();
+        for candidate in &excluding {
+            count.continuing[*candidate] = false;
+            count.votes[*candidate] = 0;
+        }
+        count.excluded.extend(&excluding);
+        count.transfer(&excluding, None, votes_leaving);
+        count.record(
+            CountAction::Exclusion {
+                candidates: excluding,
+            },
+            before,
+        );
+    }
+
+    SenateResult {
+        candidates: election.candidates.clone(),
+        vacancies: election.vacancies,
+        ballots,
+        quota,
+        count_sheets: count.count_sheets,
+        elected: count.elected,
+        excluded: count.excluded,
+        vote_count: votes.len(),
+        ties: count.tie_breaker.into_ties(),
+    }
+}
+
+pub struct AustralianSenate;
+
+impl VotingSystem for AustralianSenate {
+    type Ballot = SenateVote;
+    type Config = SenateElection;
+    type Outcome = SenateResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let final_votes = report
+            .count_sheets
+            .last()
+            .map(|count_sheet| {
+                count_sheet
+                    .candidates
+                    .iter()
+                    .map(|column| column.votes as usize)
+                    .collect::<Vec<usize>>()
+            })
+            .unwrap_or_else(|| vec![0; report.candidates.len()]);
+        let standing = (0..report.candidates.len())
+            .filter(|i| !report.elected.contains(i) && !report.excluded.contains(i))
+            .collect::<Vec<usize>>();
+
+        Outcome::from_places(
+            report.elected.clone(),
+            rank_by_removal(&standing, &final_votes, &report.excluded),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marks(numbered: &[usize], box_count: usize) -> Vec<Option<usize>> {
+        let mut marks = vec![None; box_count];
+        for (number, i) in numbered.iter().enumerate() {
+            marks[*i] = Some(number + 1);
+        }
+        marks
+    }
+
+    fn votes(election: &SenateElection, groups: &[(usize, &[usize], &[usize])]) -> Vec<SenateVote> {
+        let mut votes = vec![];
+        for (count, above_the_line, below_the_line) in groups {
+            for _ in 0..*count {
+                votes.push(SenateVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    above_the_line: marks(above_the_line, election.groups.len()),
+                    below_the_line: marks(below_the_line, election.candidates.len()),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(candidate_count: usize, groups: &[&[usize]], vacancies: usize) -> SenateElection {
+        SenateElection {
+            candidates: (0..candidate_count).map(|i| format!("C{}", i)).collect(),
+            groups: groups
+                .iter()
+                .enumerate()
+                .map(|(i, candidates)| SenateGroup {
+                    name: format!("G{}", i),
+                    candidates: candidates.to_vec(),
+                })
+                .collect(),
+            vacancies,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_read_ballot_savings_provisions() {
+        // Three groups of two and one ungrouped candidate
+        let election = election(7, &[&[0, 1], &[2, 3], &[4, 5]], 2);
+        let read = |above_the_line: Vec<Option<usize>>, below_the_line: Vec<Option<usize>>| {
+            read_ballot(
+                &election,
+                &SenateVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    above_the_line,
+                    below_the_line,
+                },
+            )
+        };
+
+        // 1 to 6 below the line is saved, all 7 is not
+        let ballot = read(vec![None; 3], marks(&[6, 5, 4, 3, 2, 1], 7));
+        assert_eq!(ballot.formality, BallotFormality::BelowTheLine);
+        assert_eq!(ballot.preferences, vec![6, 5, 4, 3, 2, 1]);
+        assert!(ballot.saved);
+        let ballot = read(vec![None; 3], marks(&[6, 5, 4, 3, 2, 1, 0], 7));
+        assert!(!ballot.saved);
+
+        // Only 5 below the line falls back to the boxes above it
+        let ballot = read(marks(&[1, 0], 3), marks(&[6, 5, 4, 3, 2], 7));
+        assert_eq!(ballot.formality, BallotFormality::AboveTheLine);
+        assert_eq!(ballot.preferences, vec![2, 3, 0, 1]);
+        assert!(ballot.saved);
+
+        // A repeated 3 breaks the sequence after 2
+        let mut below_the_line = marks(&[0, 1, 2, 3, 4, 5], 7);
+        below_the_line[6] = Some(3);
+        let ballot = read(vec![None; 3], below_the_line);
+        assert_eq!(ballot.formality, BallotFormality::Informal);
+        assert!(ballot.preferences.is_empty());
+    }
+
+    #[test]
+    fn test_get_result_weighted_surplus() {
+        // Groups A (0, 1) and B (2, 3) for two vacancies
+        let election = election(4, &[&[0, 1], &[2, 3]], 2);
+        let votes = votes(
+            &election,
+            &[(60, &[0], &[]), (30, &[1], &[]), (10, &[], &[3, 1, 0, 2])],
+        );
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.ballots.above_the_line, 90);
+        assert_eq!(result.ballots.saved_above_the_line, 90);
+        assert_eq!(result.ballots.below_the_line, 10);
+        assert_eq!(result.quota, 34);
+
+        // 26 of 0's 60 votes pass on, each paper truncated to just under
+        // 13/30 of a vote and the 60 papers worth 25 whole votes
+        let surplus = &result.count_sheets[1];
+        match &surplus.action {
+            CountAction::Surplus {
+                candidate,
+                transfer_value,
+            } => {
+                assert_eq!(*candidate, 0);
+                assert!((transfer_value - 26.0 / 60.0).abs() < 1e-9);
+            }
+            action => panic!("unexpected {:?}", action),
+        }
+        assert_eq!(surplus.candidates[0].votes, 34);
+        assert_eq!(surplus.candidates[0].papers_transferred, -60);
+        assert_eq!(surplus.candidates[1].votes, 25);
+        assert_eq!(surplus.loss_by_fraction, 1);
+
+        // Excluding 3 gives 1 their 10 votes and the second seat
+        assert_eq!(
+            result.count_sheets[2].action,
+            CountAction::Exclusion {
+                candidates: vec![3]
+            }
+        );
+        assert_eq!(result.count_sheets[2].elected, vec![1]);
+        assert_eq!(result.elected, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_get_result_regrouped_fractions() {
+        // Every candidate is a group of their own for two vacancies
+        let election = election(6, &[&[0], &[1], &[2], &[3], &[4], &[5]], 2);
+        let votes = votes(
+            &election,
+            &[
+                (1, &[0, 2, 4], &[]),
+                (1, &[0, 3, 4], &[]),
+                (26, &[0], &[]),
+                (5, &[1], &[]),
+                (4, &[4], &[]),
+                (3, &[5], &[]),
+            ],
+        );
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.quota, 14);
+
+        // 0's surplus passes on at half a vote, 2 and 3 get a paper each
+        // but no whole vote and a vote is lost by fraction
+        let surplus = &result.count_sheets[1];
+        assert_eq!(surplus.candidates[2].votes, 0);
+        assert_eq!(surplus.candidates[3].votes, 0);
+        assert_eq!(surplus.loss_by_fraction, 1);
+
+        // Excluded together their two half papers make a whole vote for 4,
+        // winning back the vote lost by fraction
+        let exclusion = &result.count_sheets[2];
+        assert_eq!(
+            exclusion.action,
+            CountAction::Exclusion {
+                candidates: vec![2, 3, 5]
+            }
+        );
+        assert_eq!(exclusion.candidates[4].votes, 5);
+        assert_eq!(exclusion.loss_by_fraction, 0);
+    }
+
+    #[test]
+    fn test_get_result_bulk_exclusion() {
+        let election = election(7, &[], 2);
+        let votes = votes(
+            &election,
+            &[
+                (29, &[], &[0, 1, 2, 3, 4, 5, 6]),
+                (24, &[], &[1, 0, 2, 3, 4, 5, 6]),
+                (17, &[], &[2, 1, 0, 3, 4, 5, 6]),
+                (16, &[], &[3, 0, 1, 2, 4, 5, 6]),
+                (7, &[], &[4, 2, 0, 1, 3, 5, 6]),
+                (5, &[], &[5, 2, 0, 1, 3, 4, 6]),
+                (2, &[], &[6, 2, 0, 1, 3, 4, 5]),
+            ],
+        );
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.quota, 34);
+
+        // 2 + 5 + 7 can't catch 3 on 16 or fill the 15 votes the top two
+        // still need, so they go in one count
+        assert_eq!(
+            result.count_sheets[1].action,
+            CountAction::Exclusion {
+                candidates: vec![6, 5, 4]
+            }
+        );
+        assert_eq!(result.count_sheets[1].candidates[2].votes, 31);
+        assert_eq!(result.count_sheets[1].exhausted.papers, 0);
+
+        assert_eq!(
+            result.count_sheets[2].action,
+            CountAction::Exclusion {
+                candidates: vec![3]
+            }
+        );
+        assert_eq!(result.elected[0], 0);
+        assert_eq!(result.elected.len(), 2);
+
+        let outcome = AustralianSenate::outcome(&result);
+        assert_eq!(outcome.elected, result.elected);
+        assert_eq!(outcome.ranking.last(), Some(&vec![6]));
+    }
+}
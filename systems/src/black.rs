@@ -0,0 +1,196 @@
+use std::cmp::Reverse;
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::{get_condorcet_winner, get_matchups, CondorcetMethodVote},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, rank_by_score, Outcome, VotingSystem},
+    weight::weighted,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlackElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when the Borda fallback is tied
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlackBordaScore {
+    pub option_index: usize,
+    pub points: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlackResult {
+    pub options: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    pub condorcet_winner: Option<usize>,
+    /// Borda points, the most points first. An option ranked `n` places from
+    /// the bottom of a ballot gets `n` points
+    pub borda_scores: Vec<BlackBordaScore>,
+    /// Set when there is no Condorcet winner
+    pub borda_winner: Option<usize>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_borda_scores(
+    option_count: usize,
+    votes: &[CondorcetMethodVote],
+) -> Vec<BlackBordaScore> {
+    let mut points = vec![0; option_count];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, preference) in vote.votes.iter().enumerate() {
+            points[option_index] += (option_count - 1 - preference) * weight;
+        }
+    }
+
+    let mut scores = points
+        .into_iter()
+        .enumerate()
+        .map(|(option_index, points)| BlackBordaScore {
+            option_index,
+            points,
+        })
+        .collect::<Vec<_>>();
+    scores.sort_by_key(|a| Reverse(a.points));
+    scores
+}
+
+pub fn get_result(election: &BlackElection, votes: &[CondorcetMethodVote]) -> BlackResult {
+    let matchups = get_matchups(election.options.len(), votes);
+    let condorcet_winner = get_condorcet_winner(&matchups);
+    let borda_scores = get_borda_scores(election.options.len(), votes);
+
+    let mut points = vec![0.0; election.options.len()];
+    for score in &borda_scores {
+        points[score.option_index] = score.points as f64;
+    }
+    let mut tie_breaker =
+        TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| points.clone());
+    tie_breaker.record_round(points);
+
+    let borda_winner = if condorcet_winner.is_none() {
+        let most_points = borda_scores
+            .iter()
+            .filter(|i| i.points == borda_scores[0].points)
+            .map(|i| i.option_index)
+            .collect::<Vec<usize>>();
+        Some(tie_breaker.break_tie(TieKind::Elect, &most_points, |rng| {
+            *most_points.choose(rng).unwrap()
+        }))
+    } else {
+        None
+    };
+
+    BlackResult {
+        options: election.options.clone(),
+        matchups,
+        condorcet_winner,
+        borda_scores,
+        borda_winner,
+        winner: condorcet_winner.or(borda_winner).unwrap(),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Black;
+
+impl VotingSystem for Black {
+    type Ballot = CondorcetMethodVote;
+    type Config = BlackElection;
+    type Outcome = BlackResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let places = rank_by_score(
+            report
+                .borda_scores
+                .iter()
+                .map(|i| (i.option_index, i.points)),
+        );
+        let ties = if report.borda_winner.is_some() {
+            level_places(&places[..1])
+        } else {
+            Vec::new()
+        };
+
+        Outcome::new(vec![report.winner], places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn vote(votes: Vec<usize>) -> CondorcetMethodVote {
+        CondorcetMethodVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election() -> BlackElection {
+        BlackElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_condorcet_winner() {
+        // A is the Condorcet winner even though B has more Borda points
+        let mut votes = vec![];
+        for _ in 0..3 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![2, 0, 1]));
+        }
+
+        let result = get_result(&election(), &votes);
+        assert_eq!(result.condorcet_winner, Some(0));
+        assert_eq!(result.borda_scores[0].option_index, 1);
+        assert_eq!(result.borda_winner, None);
+        assert_eq!(result.winner, 0);
+    }
+
+    #[test]
+    fn test_get_result_borda_fallback() {
+        // A > B 8-3, B > C 8-3, C > A 6-5
+        let mut votes = vec![];
+        for _ in 0..5 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![2, 0, 1]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![1, 2, 0]));
+        }
+
+        let result = get_result(&election(), &votes);
+        assert_eq!(result.condorcet_winner, None);
+        assert_eq!(
+            result.borda_scores[0],
+            BlackBordaScore {
+                option_index: 0,
+                points: 13
+            }
+        );
+        assert_eq!(result.winner, 0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::cmp::Reverse;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BordaCountElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BordaCountVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Points given to each option
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(BordaCountVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BordaCountTally {
+    pub option_index: usize,
+    pub vote_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BordaCountResult {
+    pub options: Vec<String>,
+    pub winner: usize,
+    pub vote_tally: Vec<BordaCountTally>,
+    pub votes: Vec<BordaCountVote>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(election: &BordaCountElection, votes: &[BordaCountVote]) -> BordaCountResult {
+    let mut vote_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, v) in vote.votes.iter().enumerate() {
+            vote_tally[option_index] += v * weight;
+        }
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+    tie_breaker.record_round(vote_tally.iter().map(|i| *i as f64));
+    let most = vote_tally.iter().max().copied().unwrap_or_default();
+    let top = (0..vote_tally.len())
+        .filter(|i| vote_tally[*i] == most)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &top, |_| top[0]);
+
+    let mut vote_tally: Vec<BordaCountTally> = vote_tally
+        .iter()
+        .enumerate()
+        .map(|(option_index, vote_count)| BordaCountTally {
+            option_index,
+            vote_count: *vote_count,
+        })
+        .collect();
+
+    vote_tally.sort_by_key(|a| Reverse(a.vote_count));
+
+    BordaCountResult {
+        options: election.options.clone(),
+        winner,
+        vote_tally,
+        votes: votes.to_vec(),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct BordaCount;
+
+impl VotingSystem for BordaCount {
+    type Ballot = BordaCountVote;
+    type Config = BordaCountElection;
+    type Outcome = BordaCountResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            vec![report.winner],
+            rank_by_score(
+                report
+                    .vote_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.vote_count)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result() {
+        let election = BordaCountElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            BordaCountVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![2, 1, 0],
+            },
+            BordaCountVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![0, 2, 1],
+            },
+            BordaCountVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![1, 2, 0],
+            },
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.vote_tally[0].vote_count, 5);
+    }
+}
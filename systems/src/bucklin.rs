@@ -0,0 +1,440 @@
+use log::info;
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumString};
+
+use crate::{
+    preferential_voting::{borda_scores, get_preference_tally, PreferentialVote},
+    ranked_choice::{tally_ranked_votes, RankedChoiceVoteTally},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{total_weight, weighted},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucklinMethod {
+    /// Adds one preference level per round until an option has a majority
+    Standard,
+    /// Counts the top `approval_depth` preferences of each ballot as
+    /// approvals, an option approved by a majority wins outright and
+    /// otherwise the two most approved meet in a runoff
+    ApprovalRunoff,
+}
+
+/// Who wins when more than one option reaches a majority in the same round
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, AsRefStr, EnumString,
+)]
+pub enum BucklinMajorityRule {
+    /// The most votes at that level, then at each earlier level
+    #[default]
+    MostVotes,
+    /// The most first preferences, then at each later level
+    FirstPreferences,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BucklinElection {
+    pub options: Vec<String>,
+    pub method: BucklinMethod,
+    pub majority_rule: BucklinMajorityRule,
+    /// Preferences counted as approvals, only for the approval runoff
+    pub approval_depth: usize,
+    /// Seeds the RNG used when options are level at every preference
+    pub seed: String,
+    /// Settles options the majority rule leaves level, `TieBreak::Method`
+    /// keeps walking the preference levels
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BucklinLevel {
+    /// Preferences counted, 1 being first preferences only
+    pub level: usize,
+    /// Ballots placing each option within the first `level` preferences
+    pub tally: Vec<usize>,
+    /// Options with a majority at this level
+    pub majorities: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BucklinRunoff {
+    pub finalists: Vec<usize>,
+    /// Ballots ranking each finalist above the other
+    pub vote_counts: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BucklinResult {
+    pub options: Vec<String>,
+    pub method: BucklinMethod,
+    pub majority_rule: BucklinMajorityRule,
+    /// Votes needed for a majority
+    pub majority: usize,
+    pub levels: Vec<BucklinLevel>,
+    pub runoff: Option<BucklinRunoff>,
+    pub winner: usize,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn get_cumulative_tally(preference_tally: &[Vec<usize>], level: usize) -> Vec<usize> {
+    preference_tally
+        .iter()
+        .map(|counts| counts[..level].iter().sum())
+        .collect()
+}
+
+/// Keeps the `candidates` with the most votes within `level` preferences
+fn most_at_level(preference_tally: &[Vec<usize>], candidates: &mut Vec<usize>, level: usize) {
+    let tally = get_cumulative_tally(preference_tally, level);
+    let most = candidates.iter().map(|i| tally[*i]).max().unwrap();
+    candidates.retain(|i| tally[*i] == most);
+}
+
+/// Narrows `candidates` to the most votes at the first level in `order`,
+/// under `TieBreak::Method` a tie then goes through each later level in turn
+/// before falling back to the RNG
+fn pick(
+    tie_breaker: &mut TieBreaker,
+    preference_tally: &[Vec<usize>],
+    candidates: &[usize],
+    order: impl Iterator<Item = usize>,
+) -> usize {
+    let mut order = order;
+    let mut level = candidates.to_vec();
+    if let Some(first_level) = order.next() {
+        most_at_level(preference_tally, &mut level, first_level);
+    }
+    tie_breaker.break_tie(TieKind::Elect, &level.clone(), |rng| {
+        for next_level in order {
+            if level.len() == 1 {
+                break;
+            }
+            most_at_level(preference_tally, &mut level, next_level);
+        }
+        *level.choose(rng).unwrap()
+    })
+}
+
+/// Applies the majority rule to the options `candidates` that are level at
+/// `level` preferences
+fn pick_majority(
+    tie_breaker: &mut TieBreaker,
+    rule: BucklinMajorityRule,
+    preference_tally: &[Vec<usize>],
+    candidates: &[usize],
+    level: usize,
+) -> usize {
+    match rule {
+        BucklinMajorityRule::MostVotes => {
+            pick(tie_breaker, preference_tally, candidates, (1..=level).rev())
+        }
+        BucklinMajorityRule::FirstPreferences => {
+            pick(tie_breaker, preference_tally, candidates, 1..=level)
+        }
+    }
+}
+
+pub fn get_result(election: &BucklinElection, votes: &[PreferentialVote]) -> BucklinResult {
+    let option_count = election.options.len();
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(option_count, votes));
+    let preference_tally = get_preference_tally(option_count, votes);
+    let majority = total_weight(votes) / 2 + 1;
+    let all_options = (0..option_count).collect::<Vec<usize>>();
+
+    let last_level = match election.method {
+        BucklinMethod::Standard => option_count,
+        BucklinMethod::ApprovalRunoff => election.approval_depth.clamp(1, option_count),
+    };
+
+    let mut levels = Vec::new();
+    let mut winner = None;
+    for level in 1..=last_level {
+        let tally = get_cumulative_tally(&preference_tally, level);
+        let majorities = all_options
+            .iter()
+            .copied()
+            .filter(|i| tally[*i] >= majority)
+            .collect::<Vec<usize>>();
+        info!("Level {} tally {:?}", level, tally);
+        tie_breaker.record_round(tally.iter().map(|i| *i as f64));
+
+        let decides = election.method == BucklinMethod::Standard || level == last_level;
+        if decides && !majorities.is_empty() {
+            winner = Some(pick_majority(
+                &mut tie_breaker,
+                election.majority_rule,
+                &preference_tally,
+                &majorities,
+                level,
+            ));
+        }
+        levels.push(BucklinLevel {
+            level,
+            tally,
+            majorities,
+        });
+        if winner.is_some() {
+            break;
+        }
+    }
+
+    let mut runoff = None;
+    let winner = match winner {
+        Some(winner) => winner,
+        None if election.method == BucklinMethod::ApprovalRunoff && option_count > 1 => {
+            let first = pick_majority(
+                &mut tie_breaker,
+                BucklinMajorityRule::MostVotes,
+                &preference_tally,
+                &all_options,
+                last_level,
+            );
+            let rest = all_options
+                .iter()
+                .copied()
+                .filter(|i| *i != first)
+                .collect::<Vec<usize>>();
+            let second = pick_majority(
+                &mut tie_breaker,
+                BucklinMajorityRule::MostVotes,
+                &preference_tally,
+                &rest,
+                last_level,
+            );
+
+            let mut vote_counts = vec![0, 0];
+            for (vote, weight) in weighted(votes) {
+                if vote.votes[first] < vote.votes[second] {
+                    vote_counts[0] += weight;
+                } else {
+                    vote_counts[1] += weight;
+                }
+            }
+            info!("Runoff between {} and {} {:?}", first, second, vote_counts);
+
+            let mut runoff_tally = vec![0.0; option_count];
+            runoff_tally[first] = vote_counts[0] as f64;
+            runoff_tally[second] = vote_counts[1] as f64;
+            tie_breaker.record_round(runoff_tally);
+
+            // A level runoff goes to the more approved finalist
+            let winner = match vote_counts[0].cmp(&vote_counts[1]) {
+                std::cmp::Ordering::Greater => first,
+                std::cmp::Ordering::Less => second,
+                std::cmp::Ordering::Equal => {
+                    tie_breaker.break_tie(TieKind::Elect, &[first, second], |_| first)
+                }
+            };
+            runoff = Some(BucklinRunoff {
+                finalists: vec![first, second],
+                vote_counts,
+            });
+            winner
+        }
+        // Only when there are no votes
+        None => pick_majority(
+            &mut tie_breaker,
+            election.majority_rule,
+            &preference_tally,
+            &all_options,
+            last_level,
+        ),
+    };
+
+    BucklinResult {
+        options: election.options.clone(),
+        method: election.method,
+        majority_rule: election.majority_rule,
+        majority,
+        levels,
+        runoff,
+        winner,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Bucklin;
+
+impl VotingSystem for Bucklin {
+    type Ballot = PreferentialVote;
+    type Config = BucklinElection;
+    type Outcome = BucklinResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let last = report.levels.last();
+        let mut ties = Vec::new();
+        if let Some(last) = last {
+            if last.majorities.len() > 1 {
+                let most = last.majorities.iter().map(|i| last.tally[*i]).max();
+                let level = last
+                    .majorities
+                    .iter()
+                    .copied()
+                    .filter(|i| Some(last.tally[*i]) == most)
+                    .collect::<Vec<usize>>();
+                if level.len() > 1 {
+                    ties.push(level);
+                }
+            }
+        }
+
+        let mut places = vec![vec![report.winner]];
+        if let Some(runoff) = &report.runoff {
+            places.extend(
+                runoff
+                    .finalists
+                    .iter()
+                    .filter(|i| **i != report.winner)
+                    .map(|i| vec![*i]),
+            );
+        }
+        let placed = places.concat();
+        if let Some(last) = last {
+            places.extend(rank_by_score(
+                last.tally
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !placed.contains(i))
+                    .map(|(i, count)| (i, *count)),
+            ));
+        }
+
+        Outcome::new(vec![report.winner], places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn votes(groups: &[(usize, Vec<usize>)]) -> Vec<PreferentialVote> {
+        let mut votes = vec![];
+        for (count, preferences) in groups {
+            for _ in 0..*count {
+                votes.push(PreferentialVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: preferences.clone(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(
+        method: BucklinMethod,
+        majority_rule: BucklinMajorityRule,
+        approval_depth: usize,
+    ) -> BucklinElection {
+        BucklinElection {
+            options: ["A", "B", "C", "D"].iter().map(|i| i.to_string()).collect(),
+            method,
+            majority_rule,
+            approval_depth,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_cumulative_levels() {
+        // First preferences are A 4, B 3, C 2, D 0, no majority of 5 until
+        // second preferences give B 3 + 4
+        let votes = votes(&[
+            (4, vec![0, 1, 2, 3]),
+            (3, vec![2, 0, 1, 3]),
+            (2, vec![3, 2, 0, 1]),
+        ]);
+
+        let result = get_result(
+            &election(BucklinMethod::Standard, BucklinMajorityRule::MostVotes, 0),
+            &votes,
+        );
+        assert_eq!(result.majority, 5);
+        assert_eq!(result.levels.len(), 2);
+        assert_eq!(result.levels[0].tally, vec![4, 3, 2, 0]);
+        assert_eq!(result.levels[1].tally, vec![4, 7, 5, 2]);
+        assert_eq!(result.levels[1].majorities, vec![1, 2]);
+        assert_eq!(result.winner, 1);
+    }
+
+    #[test]
+    fn test_get_result_majority_rule() {
+        // A and B both reach a majority of 6 at the second level, B with
+        // more votes there but A with more first preferences
+        let votes = votes(&[
+            (5, vec![0, 1, 2, 3]),
+            (1, vec![1, 2, 3, 0]),
+            (4, vec![2, 1, 0, 3]),
+        ]);
+
+        let result = get_result(
+            &election(BucklinMethod::Standard, BucklinMajorityRule::MostVotes, 0),
+            &votes,
+        );
+        assert_eq!(result.levels[0].tally, vec![5, 0, 4, 1]);
+        assert_eq!(result.levels[1].tally, vec![6, 9, 4, 1]);
+        assert_eq!(result.levels[1].majorities, vec![0, 1]);
+        assert_eq!(result.winner, 1);
+
+        let result = get_result(
+            &election(
+                BucklinMethod::Standard,
+                BucklinMajorityRule::FirstPreferences,
+                0,
+            ),
+            &votes,
+        );
+        assert_eq!(result.winner, 0);
+    }
+
+    #[test]
+    fn test_get_result_approval_runoff() {
+        let votes = votes(&[
+            (4, vec![0, 1, 2, 3]),
+            (3, vec![2, 0, 1, 3]),
+            (2, vec![2, 1, 0, 3]),
+        ]);
+
+        // Approving only first preferences nobody has a majority, B beats
+        // A in the runoff
+        let result = get_result(
+            &election(
+                BucklinMethod::ApprovalRunoff,
+                BucklinMajorityRule::MostVotes,
+                1,
+            ),
+            &votes,
+        );
+        let runoff = result.runoff.unwrap();
+        assert_eq!(runoff.finalists, vec![0, 1]);
+        assert_eq!(runoff.vote_counts, vec![4, 5]);
+        assert_eq!(result.winner, 1);
+
+        // Approving the top two B has 9 of 9
+        let result = get_result(
+            &election(
+                BucklinMethod::ApprovalRunoff,
+                BucklinMajorityRule::MostVotes,
+                2,
+            ),
+            &votes,
+        );
+        assert_eq!(result.levels.len(), 2);
+        assert_eq!(result.levels[1].tally, vec![4, 9, 5, 0]);
+        assert!(result.runoff.is_none());
+        assert_eq!(result.winner, 1);
+    }
+}
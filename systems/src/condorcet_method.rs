@@ -0,0 +1,444 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashSet, VecDeque},
+};
+
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    preferential_voting::{self, PreferentialElection, PreferentialVote},
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight, WeightedBallot},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CondorcetMethodElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when the Borda tie break is also tied
+    pub seed: String,
+    /// Also passed on to the instant runoff used as a last resort
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CondorcetMethodVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The preference given to each option, 0 being the most preferred
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(CondorcetMethodVote);
+
+impl RankedChoiceVote<usize> for CondorcetMethodVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatchedPair {
+    pub runner: usize,
+    pub opponent: usize,
+    pub votes_for_runner: usize,
+    pub votes_for_opponent: usize,
+    pub difference: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CondorcetMethodResult {
+    pub options: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    pub condorcet_winner: Option<usize>,
+    pub matched_pairs: Option<Vec<MatchedPair>>,
+    pub locked_in_pairwise_victories: Option<Vec<Vec<usize>>>,
+    pub matched_pair_winner: Option<usize>,
+    pub last_resort_winner: Option<usize>,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn creates_cycle(graph: &[Vec<usize>], runner: usize, opponent: usize) -> bool {
+    let mut visited_nodes: HashSet<usize> = HashSet::new();
+    let mut stack: VecDeque<usize> = VecDeque::new();
+
+    stack.push_back(opponent);
+
+    while let Some(current_node) = stack.pop_front() {
+        if current_node == runner {
+            return true;
+        }
+
+        if !visited_nodes.contains(&current_node) {
+            visited_nodes.insert(current_node);
+
+            for &neighbor in &graph[current_node] {
+                stack.push_back(neighbor);
+            }
+        }
+    }
+
+    false
+}
+
+fn lock_in_pairwise_victories(
+    pairwise_comparisons: &[MatchedPair],
+    num_candidates: usize,
+) -> Vec<Vec<usize>> {
+    let mut graph: Vec<Vec<usize>> = vec![Vec::new(); num_candidates];
+
+    for pairwise_comparison in pairwise_comparisons {
+        let runner = pairwise_comparison.runner;
+        let opponent = pairwise_comparison.opponent;
+
+        if !creates_cycle(&graph, runner, opponent) {
+            graph[runner].push(opponent);
+        }
+    }
+
+    graph
+}
+
+fn determine_winners(graph: &[Vec<usize>]) -> Vec<usize> {
+    (0..graph.len())
+        .filter(|candidate| !graph.iter().any(|opponents| opponents.contains(candidate)))
+        .collect()
+}
+
+fn break_pairwise_tie(rng: &mut Pcg64, tied: &[usize], votes: &[CondorcetMethodVote]) -> usize {
+    struct BordaCount {
+        index: usize,
+        score: usize,
+    }
+
+    if tied.len() == 1 {
+        return tied[0];
+    }
+
+    // Break tie using Borda count
+    let mut borda_scores = vec![0; votes[0].votes.len()];
+    for (vote, weight) in weighted(votes) {
+        for (index, &score) in vote.votes.iter().enumerate() {
+            // 1 worth the most
+            borda_scores[index] += (votes[0].votes.len() - score) * weight;
+        }
+    }
+
+    let borda_scores = borda_scores
+        .iter()
+        .enumerate()
+        .map(|(index, score)| BordaCount {
+            index,
+            score: *score,
+        })
+        .collect::<Vec<BordaCount>>();
+
+    let max_score: usize = borda_scores.iter().map(|i| i.score).max().unwrap();
+    let mut matching_max_score = borda_scores
+        .into_iter()
+        .filter(|i| i.score == max_score)
+        .collect::<Vec<BordaCount>>();
+    matching_max_score.shuffle(rng);
+
+    matching_max_score[0].index
+}
+
+/// Borda scores for the `TieBreak::Borda` policy from each ballot's preferences
+pub(crate) fn borda_scores(option_count: usize, votes: &[CondorcetMethodVote]) -> Vec<f64> {
+    borda_from_values(
+        option_count,
+        weighted(votes)
+            .map(|(vote, weight)| (vote.votes.iter().map(|i| Reverse(*i)).collect(), weight)),
+    )
+}
+
+/// Builds the pairwise matrix where `matchups[i][j]` is the weight of the
+/// voters who prefer option `i` over option `j`, ballots give the preference
+/// for each option with 0 being the most preferred
+pub fn get_matchups<T: RankedChoiceVote<usize> + WeightedBallot>(
+    option_count: usize,
+    votes: &[T],
+) -> Vec<Vec<usize>> {
+    let votes = weighted(votes)
+        .map(|(v, weight)| (v.ranked_votes(), weight))
+        .collect::<Vec<_>>();
+    let mut matchups = vec![vec![0; option_count]; option_count];
+
+    for (i, row) in matchups.iter_mut().enumerate() {
+        for (j, col) in row.iter_mut().enumerate() {
+            for (vote, weight) in &votes {
+                if vote[i] < vote[j] {
+                    *col += weight;
+                }
+            }
+        }
+    }
+
+    matchups
+}
+
+/// The option that beats every other option head to head, if there is one
+pub fn get_condorcet_winner(matchups: &[Vec<usize>]) -> Option<usize> {
+    (0..matchups.len()).find(|option_index| {
+        (0..matchups.len()).all(|other_index| {
+            *option_index == other_index
+                || matchups[*option_index][other_index] > matchups[other_index][*option_index]
+        })
+    })
+}
+
+/// The smallest group of `candidates` where every member beats every
+/// candidate outside it head to head, sorted by option index
+pub fn get_smith_set(matchups: &[Vec<usize>], candidates: &[usize]) -> Vec<usize> {
+    // A member reaches every candidate through matchups it wins or ties
+    let reaches_all = |start: usize| {
+        let mut reached = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for &next in candidates {
+                if matchups[current][next] >= matchups[next][current] && reached.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        reached.len() == candidates.len()
+    };
+
+    let mut smith_set = candidates
+        .iter()
+        .copied()
+        .filter(|i| reaches_all(*i))
+        .collect::<Vec<usize>>();
+    smith_set.sort();
+    smith_set
+}
+
+pub fn get_result(
+    election: &CondorcetMethodElection,
+    votes: &[CondorcetMethodVote],
+) -> CondorcetMethodResult {
+    let votes_ranked_vote = tally_ranked_votes(votes);
+
+    if votes.is_empty() {
+        return CondorcetMethodResult {
+            options: election.options.clone(),
+            matchups: vec![vec![0; election.options.len()]; election.options.len()],
+            condorcet_winner: None,
+            matched_pairs: None,
+            locked_in_pairwise_victories: None,
+            matched_pair_winner: None,
+            last_resort_winner: None,
+            votes: Vec::new(),
+            vote_count: votes.len(),
+            ties: Vec::new(),
+        };
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    // Create matchup matrix
+    let matchups = get_matchups(election.options.len(), votes);
+
+    // Look for Condorcet winner
+    let condorcet_winner = get_condorcet_winner(&matchups);
+
+    if let Some(option_index) = condorcet_winner {
+        return CondorcetMethodResult {
+            options: election.options.clone(),
+            matchups,
+            condorcet_winner: Some(option_index),
+            matched_pairs: None,
+            locked_in_pairwise_victories: None,
+            matched_pair_winner: None,
+            last_resort_winner: None,
+            votes: votes_ranked_vote,
+            vote_count: votes.len(),
+            ties: Vec::new(),
+        };
+    }
+
+    // Not Condorcet winner using Ranked Pairs
+    /*
+      a. Start by creating a list of all pairwise comparisons between
+      candidates (runner, opponent) sorted in descending
+      order by the strength of their victory
+      (the difference in votes between runner and opponent).
+    */
+    let mut ranked_pairs = Vec::new();
+    for (i, row) in matchups.iter().enumerate() {
+        for (j, &votes_for_runner) in row.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            let votes_for_opponent = matchups[j][i];
+            ranked_pairs.push(MatchedPair {
+                runner: i,
+                opponent: j,
+                votes_for_runner,
+                votes_for_opponent,
+                difference: (votes_for_runner as i64) - (votes_for_opponent as i64),
+            });
+        }
+    }
+    // Sort by strength of victory
+    ranked_pairs.sort_by_key(|a| Reverse(a.difference));
+    // Lock in pairwise victories
+    let locked_in_pairwise_victories =
+        lock_in_pairwise_victories(&ranked_pairs, election.options.len());
+
+    let winners = determine_winners(&locked_in_pairwise_victories);
+    // pairwise winner found
+    if !winners.is_empty() {
+        let winner = tie_breaker.break_tie(TieKind::Elect, &winners, |rng| {
+            break_pairwise_tie(rng, &winners, votes)
+        });
+
+        return CondorcetMethodResult {
+            options: election.options.clone(),
+            matchups,
+            condorcet_winner: None,
+            matched_pairs: Some(ranked_pairs),
+            locked_in_pairwise_victories: Some(locked_in_pairwise_victories),
+            matched_pair_winner: Some(winner),
+            last_resort_winner: None,
+            votes: votes_ranked_vote,
+            vote_count: votes.len(),
+            ties: tie_breaker.into_ties(),
+        };
+    }
+
+    // Okay this is completely fucked just use AEC style preference voting
+    let pref_election = PreferentialElection {
+        options: election.options.clone(),
+        seed: election.seed.clone(),
+        tie_break: election.tie_break.clone(),
+    };
+    let pref_votes: Vec<PreferentialVote> = votes
+        .iter()
+        .map(|v| PreferentialVote {
+            created_by: v.created_by,
+            weight: v.weight,
+            votes: v.votes.clone(),
+        })
+        .collect();
+    let pref_result = preferential_voting::get_election_winner(&pref_election, &pref_votes);
+
+    CondorcetMethodResult {
+        options: election.options.clone(),
+        matchups,
+        condorcet_winner,
+        locked_in_pairwise_victories: Some(locked_in_pairwise_victories),
+        matched_pairs: Some(ranked_pairs),
+        matched_pair_winner: None,
+        last_resort_winner: Some(pref_result.winner),
+        votes: votes_ranked_vote,
+        vote_count: votes.len(),
+        ties: pref_result.ties,
+    }
+}
+
+pub struct CondorcetMethod;
+
+impl VotingSystem for CondorcetMethod {
+    type Ballot = CondorcetMethodVote;
+    type Config = CondorcetMethodElection;
+    type Outcome = CondorcetMethodResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let winner = report
+            .condorcet_winner
+            .or(report.matched_pair_winner)
+            .or(report.last_resort_winner);
+
+        // Order the rest by how many head to head matchups they win
+        let places = rank_by_score((0..report.matchups.len()).map(|i| {
+            let wins = (0..report.matchups.len())
+                .filter(|j| report.matchups[i][*j] > report.matchups[*j][i])
+                .count();
+            (i, wins)
+        }));
+        let mut ties = level_places(&places);
+        if let (Some(_), Some(locked)) = (
+            report.matched_pair_winner,
+            &report.locked_in_pairwise_victories,
+        ) {
+            let unbeaten = determine_winners(locked);
+            if unbeaten.len() > 1 {
+                ties.push(unbeaten);
+            }
+        }
+
+        Outcome::new(winner.into_iter().collect(), places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<usize>) -> CondorcetMethodVote {
+        CondorcetMethodVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_result_condorcet_winner() {
+        let election = CondorcetMethodElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![0, 2, 1]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.matchups[0][1], 2);
+        assert_eq!(result.matchups[1][0], 1);
+        assert_eq!(result.condorcet_winner, Some(0));
+    }
+
+    #[test]
+    fn test_get_result_ranked_pairs_cycle() {
+        let election = CondorcetMethodElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // A > B 7-2, B > C 5-4, C > A 6-3, ranked pairs locks A > B then C > A
+        let mut votes = vec![];
+        for _ in 0..3 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![2, 0, 1]));
+        }
+        for _ in 0..4 {
+            votes.push(vote(vec![1, 2, 0]));
+        }
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.condorcet_winner, None);
+        assert_eq!(result.matched_pair_winner, Some(2));
+    }
+}
@@ -0,0 +1,185 @@
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::{borda_scores, get_matchups, CondorcetMethodVote},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, Outcome, VotingSystem},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CopelandElection {
+    pub options: Vec<String>,
+    /// Points for a tied matchup, 0.5 is classic Copeland while 0 and 1 give
+    /// Copeland⁰ and Copeland¹
+    pub tie_score: f64,
+    /// Seeds the RNG used when options are level on points
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CopelandScore {
+    pub option_index: usize,
+    pub wins: usize,
+    pub ties: usize,
+    pub losses: usize,
+    pub score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CopelandResult {
+    pub options: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    pub tie_score: f64,
+    /// Highest score first
+    pub scores: Vec<CopelandScore>,
+    /// Options sharing the highest score
+    pub top_scorers: Vec<usize>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// A tied matchup is worth somewhere from a loss to a win
+pub fn is_valid_tie_score(tie_score: f64) -> bool {
+    (0.0..=1.0).contains(&tie_score)
+}
+
+pub fn get_scores(matchups: &[Vec<usize>], tie_score: f64) -> Vec<CopelandScore> {
+    (0..matchups.len())
+        .map(|i| {
+            let mut score = CopelandScore {
+                option_index: i,
+                wins: 0,
+                ties: 0,
+                losses: 0,
+                score: 0.0,
+            };
+            for j in (0..matchups.len()).filter(|j| *j != i) {
+                if matchups[i][j] > matchups[j][i] {
+                    score.wins += 1;
+                } else if matchups[i][j] < matchups[j][i] {
+                    score.losses += 1;
+                } else {
+                    score.ties += 1;
+                }
+            }
+            score.score = score.wins as f64 + score.ties as f64 * tie_score;
+            score
+        })
+        .collect()
+}
+
+pub fn get_result(election: &CopelandElection, votes: &[CondorcetMethodVote]) -> CopelandResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let matchups = get_matchups(election.options.len(), votes);
+    let mut scores = get_scores(&matchups, election.tie_score);
+    tie_breaker.record_round(scores.iter().map(|i| i.score));
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let top_scorers = scores
+        .iter()
+        .filter(|i| i.score == scores[0].score)
+        .map(|i| i.option_index)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &top_scorers, |rng| {
+        *top_scorers.choose(rng).unwrap()
+    });
+
+    CopelandResult {
+        options: election.options.clone(),
+        matchups,
+        tie_score: election.tie_score,
+        scores,
+        top_scorers,
+        winner,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Copeland;
+
+impl VotingSystem for Copeland {
+    type Ballot = CondorcetMethodVote;
+    type Config = CopelandElection;
+    type Outcome = CopelandResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut places: Vec<Vec<usize>> = Vec::new();
+        for (i, score) in report.scores.iter().enumerate() {
+            if i > 0 && report.scores[i - 1].score == score.score {
+                places.last_mut().unwrap().push(score.option_index);
+            } else {
+                places.push(vec![score.option_index]);
+            }
+        }
+
+        Outcome::new(vec![report.winner], places.clone(), level_places(&places))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn vote(votes: Vec<usize>) -> CondorcetMethodVote {
+        CondorcetMethodVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election(tie_score: f64) -> CopelandElection {
+        CopelandElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            tie_score,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_tie_score() {
+        // A beats C, A ties B, B ties C
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![2, 1, 0]),
+            vote(vec![0, 2, 1]),
+        ];
+
+        let result = get_result(&election(0.5), &votes);
+        assert_eq!(result.scores[0].option_index, 0);
+        assert_eq!(result.scores[0].wins, 1);
+        assert_eq!(result.scores[0].ties, 1);
+        assert_eq!(result.scores[0].score, 1.5);
+        assert_eq!(result.winner, 0);
+
+        // Counting ties as wins puts B level with A
+        let result = get_result(&election(1.0), &votes);
+        assert_eq!(result.top_scorers, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_is_valid_tie_score() {
+        assert!(is_valid_tie_score(0.0));
+        assert!(is_valid_tie_score(0.5));
+        assert!(is_valid_tie_score(1.0));
+        assert!(!is_valid_tie_score(-0.5));
+        assert!(!is_valid_tie_score(1.5));
+        assert!(!is_valid_tie_score(f64::NAN));
+        assert!(!is_valid_tie_score(f64::INFINITY));
+        assert!(!is_valid_tie_score(f64::NEG_INFINITY));
+    }
+}
@@ -0,0 +1,144 @@
+use std::cmp::Reverse;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CumulativeElection {
+    pub options: Vec<String>,
+    pub max_votes: usize,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CumulativeVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Points given to each option, summing to at most `max_votes`
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(CumulativeVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CumulativeTally {
+    pub option_index: usize,
+    pub vote_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CumulativeResult {
+    pub options: Vec<String>,
+    pub votes_tally: Vec<CumulativeTally>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub votes: Vec<CumulativeVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(election: &CumulativeElection, votes: &[CumulativeVote]) -> CumulativeResult {
+    let mut points_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        for (i, points) in vote.votes.iter().enumerate() {
+            points_tally[i] += *points * weight;
+        }
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+    tie_breaker.record_round(points_tally.iter().map(|i| *i as f64));
+    let most = points_tally.iter().max().copied().unwrap_or_default();
+    let top = (0..points_tally.len())
+        .filter(|i| points_tally[*i] == most)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &top, |_| top[0]);
+
+    let mut points_tally: Vec<CumulativeTally> = points_tally
+        .into_iter()
+        .enumerate()
+        .map(|(i, points)| CumulativeTally {
+            option_index: i,
+            vote_count: points,
+        })
+        .collect();
+    points_tally.sort_by_key(|a| Reverse(a.vote_count));
+
+    CumulativeResult {
+        options: election.options.clone(),
+        winner,
+        votes_tally: points_tally,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Cumulative;
+
+impl VotingSystem for Cumulative {
+    type Ballot = CumulativeVote;
+    type Config = CumulativeElection;
+    type Outcome = CumulativeResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            vec![report.winner],
+            rank_by_score(
+                report
+                    .votes_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.vote_count)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result() {
+        let election = CumulativeElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            max_votes: 5,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            CumulativeVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![5, 0],
+            },
+            CumulativeVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                votes: vec![1, 4],
+            },
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.votes_tally[0].vote_count, 6);
+    }
+}
@@ -0,0 +1,317 @@
+//! Delegated voting, where a voter can hand their vote to another voter
+//! instead of casting a ballot. Delegations are followed transitively until
+//! they reach someone who voted, so the weight of each ballot is only known
+//! once the count is run.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::weight::{Weight, WeightedBallot};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Delegation {
+    pub delegator: uuid::Uuid,
+    pub delegate: uuid::Uuid,
+    /// The weight of the delegator's vote
+    #[serde(default)]
+    pub weight: Weight,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VoterWeight {
+    pub voter: uuid::Uuid,
+    /// The voter's own vote plus every vote delegated to them
+    pub weight: Weight,
+    /// Everyone whose vote ended up with this voter, directly or through
+    /// other delegates
+    pub delegators: Vec<uuid::Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DelegationGraph {
+    pub delegations: Vec<Delegation>,
+    /// Delegators who also voted, their own ballot is counted instead
+    pub overridden: Vec<uuid::Uuid>,
+    /// Weight carried by each voter whose ballot is counted
+    pub weights: Vec<VoterWeight>,
+    /// Chains of delegates that loop back on themselves, each starting from
+    /// its lowest identifier
+    pub cycles: Vec<Vec<uuid::Uuid>>,
+    /// Delegators whose vote never reached a ballot, either because the chain
+    /// ends with someone who didn't vote or runs into a cycle
+    pub unresolved: Vec<uuid::Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DelegatedResult<R> {
+    pub delegation: DelegationGraph,
+    pub result: R,
+}
+
+/// Whether adding `delegation` would let a vote go around in a circle
+pub fn creates_cycle(delegations: &[Delegation], delegation: &Delegation) -> bool {
+    let delegate_of: HashMap<uuid::Uuid, uuid::Uuid> = delegations
+        .iter()
+        .filter(|i| i.delegator != delegation.delegator)
+        .map(|i| (i.delegator, i.delegate))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut current = delegation.delegate;
+    while seen.insert(current) {
+        if current == delegation.delegator {
+            return true;
+        }
+        match delegate_of.get(&current) {
+            Some(next) => current = *next,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Follows every delegation to the voter whose ballot carries it, `voters`
+/// being everyone who cast a ballot with the weight of their own vote
+pub fn resolve_delegations(
+    voters: &[(uuid::Uuid, Weight)],
+    delegations: &[Delegation],
+) -> DelegationGraph {
+    let voted: BTreeSet<uuid::Uuid> = voters.iter().map(|(voter, _)| *voter).collect();
+    let own_weight: HashMap<uuid::Uuid, Weight> = voters.iter().copied().collect();
+
+    let mut delegations = delegations.to_vec();
+    delegations.sort_by_key(|i| (i.delegator, i.delegate));
+
+    let overridden = delegations
+        .iter()
+        .filter(|i| voted.contains(&i.delegator))
+        .map(|i| i.delegator)
+        .collect();
+    let delegate_of: BTreeMap<uuid::Uuid, uuid::Uuid> = delegations
+        .iter()
+        .filter(|i| !voted.contains(&i.delegator))
+        .map(|i| (i.delegator, i.delegate))
+        .collect();
+    let delegated_weight: HashMap<uuid::Uuid, Weight> = delegations
+        .iter()
+        .map(|i| (i.delegator, i.weight))
+        .collect();
+
+    let mut delegators_of: BTreeMap<uuid::Uuid, Vec<uuid::Uuid>> =
+        voted.iter().map(|i| (*i, vec![])).collect();
+    let mut cycles = BTreeSet::new();
+    let mut unresolved = vec![];
+    for (delegator, delegate) in &delegate_of {
+        let mut chain = vec![*delegator];
+        let mut current = *delegate;
+        loop {
+            if let Some(delegators) = delegators_of.get_mut(&current) {
+                delegators.push(*delegator);
+                break;
+            }
+            if let Some(start) = chain.iter().position(|i| *i == current) {
+                let mut cycle = chain[start..].to_vec();
+                let lowest = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+                cycle.rotate_left(lowest);
+                cycles.insert(cycle);
+                unresolved.push(*delegator);
+                break;
+            }
+            match delegate_of.get(&current) {
+                Some(next) => {
+                    chain.push(current);
+                    current = *next;
+                }
+                None => {
+                    unresolved.push(*delegator);
+                    break;
+                }
+            }
+        }
+    }
+
+    DelegationGraph {
+        delegations,
+        overridden,
+        weights: delegators_of
+            .into_iter()
+            .map(|(voter, delegators)| VoterWeight {
+                voter,
+                weight: delegators
+                    .iter()
+                    .fold(own_weight[&voter], |weight, i| weight + delegated_weight[i]),
+                delegators,
+            })
+            .collect(),
+        cycles: cycles.into_iter().collect(),
+        unresolved,
+    }
+}
+
+/// Gives each ballot the weight of every vote its voter carries
+pub fn weighted_ballots<B: WeightedBallot + Clone>(
+    ballots: &[B],
+    graph: &DelegationGraph,
+) -> Vec<B> {
+    let weights: HashMap<uuid::Uuid, Weight> =
+        graph.weights.iter().map(|i| (i.voter, i.weight)).collect();
+
+    ballots
+        .iter()
+        .map(|ballot| {
+            let mut ballot = ballot.clone();
+            if let Some(weight) = weights.get(&ballot.voter()) {
+                ballot.set_weight(*weight);
+            }
+            ballot
+        })
+        .collect()
+}
+
+/// Resolves the delegations against the ballots cast and counts the weighted
+/// ballots with `count`
+pub fn get_delegated_result<B: WeightedBallot + Clone, R>(
+    ballots: &[B],
+    delegations: &[Delegation],
+    count: impl FnOnce(&[B]) -> R,
+) -> DelegatedResult<R> {
+    let voters: Vec<(uuid::Uuid, Weight)> = ballots
+        .iter()
+        .map(|ballot| (ballot.voter(), ballot.weight()))
+        .collect();
+    let delegation = resolve_delegations(&voters, delegations);
+    let ballots = weighted_ballots(ballots, &delegation);
+
+    DelegatedResult {
+        result: count(&ballots),
+        delegation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(count: u128) -> Vec<uuid::Uuid> {
+        (1..=count).map(uuid::Uuid::from_u128).collect()
+    }
+
+    fn delegation(delegator: uuid::Uuid, delegate: uuid::Uuid) -> Delegation {
+        Delegation {
+            delegator,
+            delegate,
+            weight: Weight::ONE,
+        }
+    }
+
+    #[test]
+    fn test_resolve_delegations() {
+        let id = ids(8);
+        let delegations = vec![
+            // 2 -> 3 -> 1 is transitive
+            delegation(id[1], id[2]),
+            delegation(id[2], id[0]),
+            // 4 voted so keeps their own ballot
+            delegation(id[3], id[0]),
+            // 5 -> 6 -> 7 -> 5 goes round in a circle
+            delegation(id[4], id[5]),
+            delegation(id[5], id[6]),
+            delegation(id[6], id[4]),
+            // 8 hands their vote to someone who never voted
+            delegation(id[7], uuid::Uuid::from_u128(100)),
+        ];
+
+        let voters = [(id[0], Weight::ONE), (id[3], Weight::whole(2))];
+        let graph = resolve_delegations(&voters, &delegations);
+        assert_eq!(graph.overridden, vec![id[3]]);
+        assert_eq!(
+            graph.weights,
+            vec![
+                VoterWeight {
+                    voter: id[0],
+                    weight: Weight::whole(3),
+                    delegators: vec![id[1], id[2]],
+                },
+                VoterWeight {
+                    voter: id[3],
+                    weight: Weight::whole(2),
+                    delegators: vec![],
+                },
+            ]
+        );
+        assert_eq!(graph.cycles, vec![vec![id[4], id[5], id[6]]]);
+        assert_eq!(graph.unresolved, vec![id[4], id[5], id[6], id[7]]);
+    }
+
+    #[test]
+    fn test_creates_cycle() {
+        let id = ids(3);
+        let delegations = vec![delegation(id[0], id[1]), delegation(id[1], id[2])];
+
+        assert!(creates_cycle(&delegations, &delegation(id[2], id[0])));
+        assert!(creates_cycle(&delegations, &delegation(id[2], id[2])));
+        assert!(creates_cycle(&delegations, &delegation(id[1], id[0])));
+        // Moving a delegation further down the chain replaces the old one
+        assert!(!creates_cycle(&delegations, &delegation(id[0], id[2])));
+        assert!(!creates_cycle(
+            &delegations,
+            &delegation(uuid::Uuid::from_u128(100), id[0])
+        ));
+    }
+
+    #[cfg(feature = "cumulative")]
+    #[test]
+    fn test_get_delegated_result() {
+        use crate::{
+            cumulative::{get_result, CumulativeElection, CumulativeVote},
+            tie_break::TieBreak,
+        };
+
+        let id = ids(5);
+        let election = CumulativeElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            max_votes: 1,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+        let votes = vec![
+            CumulativeVote {
+                created_by: id[0],
+                weight: Weight::ONE,
+                votes: vec![1, 0],
+            },
+            CumulativeVote {
+                created_by: id[1],
+                weight: Weight::ONE,
+                votes: vec![0, 1],
+            },
+            CumulativeVote {
+                created_by: id[2],
+                weight: Weight::ONE,
+                votes: vec![0, 1],
+            },
+        ];
+
+        // Without delegation B wins two to one
+        assert_eq!(get_result(&election, &votes).winner, 1);
+
+        let delegations = vec![
+            delegation(id[3], id[0]),
+            Delegation {
+                weight: Weight::new(1, 2).unwrap(),
+                ..delegation(id[4], id[3])
+            },
+            delegation(id[2], id[0]),
+        ];
+        let delegated =
+            get_delegated_result(&votes, &delegations, |votes| get_result(&election, votes));
+        assert_eq!(delegated.delegation.overridden, vec![id[2]]);
+        assert_eq!(
+            delegated.delegation.weights[0].weight,
+            Weight::new(5, 2).unwrap()
+        );
+        assert_eq!(delegated.result.winner, 0);
+        assert_eq!(delegated.result.vote_count, 3);
+    }
+}
@@ -0,0 +1,783 @@
+use std::{cmp::Reverse, collections::BTreeMap, fmt};
+
+use log::info;
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    australian_senate::{CountAction, CountSheet, CountSheetColumn, VOTE_SCALE},
+    tie_break::{borda_from_rankings, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_removal, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HareClarkElection {
+    pub options: Vec<String>,
+    pub vacancies: usize,
+    /// Seeds the RNG used when candidates are still level after a count-back
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HareClarkVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Options in order of preference
+    pub votes: Vec<usize>,
+    /// The Robson rotation the voter's ballot paper was printed in
+    pub rotation: usize,
+}
+
+impl_weighted_ballot!(HareClarkVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HareClarkResult {
+    pub options: Vec<String>,
+    pub vacancies: usize,
+    pub quota: u64,
+    pub count_sheets: Vec<CountSheet>,
+    /// In the order they were elected
+    pub elected: Vec<usize>,
+    /// In the order they were excluded
+    pub excluded: Vec<usize>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CountbackRound {
+    /// Value of the papers with each contesting candidate still standing
+    pub votes: Vec<Option<f64>>,
+    pub exhausted: f64,
+    pub excluded: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CountbackResult {
+    pub options: Vec<String>,
+    pub vacating: usize,
+    pub contesting: Vec<usize>,
+    /// Papers that made up the vacating candidate's quota
+    pub paper_count: usize,
+    /// What those papers were worth to the vacating candidate
+    pub quota_value: f64,
+    pub rounds: Vec<CountbackRound>,
+    pub winner: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CountbackError {
+    VacatingNotElected { vacating: usize },
+    InvalidContesting { candidate: usize },
+    NoContestingCandidates,
+}
+
+impl fmt::Display for CountbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountbackError::VacatingNotElected { vacating } => {
+                write!(f, "Candidate {} was not elected", vacating)
+            }
+            CountbackError::InvalidContesting { candidate } => write!(
+                f,
+                "Candidate {} can't contest, they either don't exist or hold a seat",
+                candidate
+            ),
+            CountbackError::NoContestingCandidates => {
+                write!(f, "At least one candidate must contest the vacancy")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CountbackError {}
+
+/// Ballot papers printed for each order of the options, twice the options
+/// when there is an odd number of them
+pub fn rotation_count(option_count: usize) -> usize {
+    if option_count % 2 == 1 {
+        option_count * 2
+    } else {
+        option_count.max(1)
+    }
+}
+
+/// The order options are printed in on ballot papers of `rotation`. Across
+/// every rotation each option is printed in each position equally often and
+/// directly after each other option equally often
+pub fn robson_rotation(option_count: usize, rotation: usize) -> Vec<usize> {
+    if option_count == 0 {
+        return vec![];
+    }
+    let rotation = rotation % rotation_count(option_count);
+
+    // 0, 1, n - 1, 2, n - 2, ... shifted along by the rotation
+    let mut order = (0..option_count)
+        .map(|i| {
+            let base = if i % 2 == 1 {
+                i.div_ceil(2)
+            } else {
+                (option_count - i / 2) % option_count
+            };
+            (base + rotation) % option_count
+        })
+        .collect::<Vec<usize>>();
+    if rotation >= option_count {
+        order.reverse();
+    }
+    order
+}
+
+struct Paper {
+    preferences: Vec<usize>,
+    /// Index into `preferences` of the candidate holding the paper
+    position: usize,
+    /// Ballot weight in `1 / weight_scale` units
+    weight: u64,
+}
+
+/// Papers received by a candidate at one count
+#[derive(Clone)]
+struct Parcel {
+    papers: Vec<usize>,
+    /// Value of each unit of paper weight in the parcel
+    value: u64,
+    /// Votes credited for the parcel
+    votes: u64,
+}
+
+struct HareClarkCount {
+    tie_breaker: TieBreaker,
+    quota: u64,
+    vacancies: usize,
+    papers: Vec<Paper>,
+    piles: Vec<Vec<Parcel>>,
+    votes: Vec<u64>,
+    exhausted_papers: usize,
+    exhausted_votes: u64,
+    loss_by_fraction: u64,
+    continuing: Vec<bool>,
+    elected: Vec<usize>,
+    excluded: Vec<usize>,
+    /// Elected candidates whose surplus hasn't been passed on yet
+    pending_surpluses: Vec<usize>,
+    /// The papers and their values that made up each elected candidate's
+    /// quota, for a countback
+    quota_papers: Vec<Vec<(usize, u64)>>,
+    count_sheets: Vec<CountSheet>,
+}
+
+impl HareClarkCount {
+    fn papers_held(&self, candidate: usize) -> usize {
+        self.piles[candidate]
+            .iter()
+            .map(|parcel| parcel.papers.len())
+            .sum()
+    }
+
+    fn papers_weight(&self, papers: &[usize]) -> u64 {
+        papers.iter().map(|i| self.papers[*i].weight).sum()
+    }
+
+    fn record(&mut self, action: CountAction) {
+        let previous = self.count_sheets.last();
+        let column = |previous: Option<&CountSheetColumn>, papers: usize, votes: u64| {
+            let (previous_papers, previous_votes) = previous
+                .map(|column| (column.papers, column.votes))
+                .unwrap_or((0, 0));
+            CountSheetColumn {
+                papers_transferred: papers as i64 - previous_papers as i64,
+                votes_transferred: votes as i64 - previous_votes as i64,
+                papers,
+                votes,
+            }
+        };
+        let count_sheet = CountSheet {
+            count: self.count_sheets.len() + 1,
+            action,
+            candidates: (0..self.votes.len())
+                .map(|i| {
+                    column(
+                        previous.map(|count_sheet| &count_sheet.candidates[i]),
+                        self.papers_held(i),
+                        self.votes[i],
+                    )
+                })
+                .collect(),
+            exhausted: column(
+                previous.map(|count_sheet| &count_sheet.exhausted),
+                self.exhausted_papers,
+                self.exhausted_votes,
+            ),
+            loss_by_fraction: self.loss_by_fraction as i64,
+            elected: vec![],
+        };
+        self.count_sheets.push(count_sheet);
+        self.tie_breaker
+            .record_round(self.votes.iter().map(|i| *i as f64));
+    }
+
+    fn continuing_candidates(&self) -> Vec<usize> {
+        (0..self.continuing.len())
+            .filter(|i| self.continuing[*i])
+            .collect()
+    }
+
+    fn next_preference(&self, paper_index: usize) -> Option<usize> {
+        let paper = &self.papers[paper_index];
+        (paper.position + 1..paper.preferences.len())
+            .find(|i| self.continuing[paper.preferences[*i]])
+    }
+
+    /// Passes `papers` on at `value` each to their next continuing
+    /// preference, returning the whole votes credited
+    fn transfer(&mut self, papers: &[usize], value: u64) -> u64 {
+        let mut parcels: BTreeMap<Option<usize>, Vec<usize>> = BTreeMap::new();
+        for paper_index in papers {
+            let next = self.next_preference(*paper_index);
+            let paper = &mut self.papers[*paper_index];
+            if let Some(next) = next {
+                paper.position = next;
+            }
+            parcels
+                .entry(next.map(|i| paper.preferences[i]))
+                .or_default()
+                .push(*paper_index);
+        }
+
+        let mut credited = 0;
+        for (destination, papers) in parcels {
+            let votes = self.papers_weight(&papers) * value / VOTE_SCALE;
+            credited += votes;
+            match destination {
+                Some(candidate) => {
+                    self.votes[candidate] += votes;
+                    self.piles[candidate].push(Parcel {
+                        papers,
+                        value,
+                        votes,
+                    });
+                }
+                None => {
+                    self.exhausted_papers += papers.len();
+                    self.exhausted_votes += votes;
+                }
+            }
+        }
+        credited
+    }
+
+    /// Breaks a tie between level candidates by the election's policy, by
+    /// default narrowing them by the most recent count at which they
+    /// differed, keeping the highest or lowest, then drawing by lot
+    fn count_back(&mut self, candidates: &[usize], highest: bool) -> usize {
+        let kind = if highest {
+            TieKind::Elect
+        } else {
+            TieKind::Exclude
+        };
+        let count_sheets = &self.count_sheets;
+        self.tie_breaker.break_tie(kind, candidates, |rng| {
+            let mut level = candidates.to_vec();
+            for count_sheet in count_sheets.iter().rev() {
+                if level.len() == 1 {
+                    break;
+                }
+                let votes = level.iter().map(|i| count_sheet.candidates[*i].votes);
+                let target = if highest { votes.max() } else { votes.min() };
+                level.retain(|i| Some(count_sheet.candidates[*i].votes) == target);
+            }
+            *level.choose(rng).unwrap()
+        })
+    }
+
+    /// Every paper the candidate holds, the last bundle only at what is left
+    /// of it once the surplus is taken out
+    fn papers_making_quota(&self, candidate: usize) -> Vec<(usize, u64)> {
+        let surplus = self.votes[candidate].saturating_sub(self.quota);
+        let parcels = &self.piles[candidate];
+        let mut quota_papers = Vec::new();
+        for (i, parcel) in parcels.iter().enumerate() {
+            let value = if surplus > 0 && i == parcels.len() - 1 {
+                (parcel.votes.saturating_sub(surplus) * VOTE_SCALE)
+                    / self.papers_weight(&parcel.papers).max(1)
+            } else {
+                parcel.value
+            };
+            quota_papers.extend(
+                parcel
+                    .papers
+                    .iter()
+                    .map(|paper| (*paper, value * self.papers[*paper].weight)),
+            );
+        }
+        quota_papers
+    }
+
+    /// Elects `candidates` from the most votes down
+    fn elect(&mut self, candidates: &[usize]) {
+        let mut candidates = candidates.to_vec();
+        while !candidates.is_empty() {
+            let most = candidates.iter().map(|i| self.votes[*i]).max().unwrap();
+            let level = candidates
+                .iter()
+                .copied()
+                .filter(|i| self.votes[*i] == most)
+                .collect::<Vec<usize>>();
+            let candidate = self.count_back(&level, true);
+            candidates.retain(|i| *i != candidate);
+
+            info!(
+                "Electing {} with {} votes",
+                candidate, self.votes[candidate]
+            );
+            self.continuing[candidate] = false;
+            self.elected.push(candidate);
+            self.quota_papers[candidate] = self.papers_making_quota(candidate);
+            if self.votes[candidate] > self.quota {
+                self.pending_surpluses.push(candidate);
+            }
+            if let Some(count_sheet) = self.count_sheets.last_mut() {
+                count_sheet.elected.push(candidate);
+            }
+        }
+    }
+
+    /// Passes on the surplus from only the parcel that took the candidate
+    /// over quota, shared between its papers that have a further preference
+    fn distribute_surplus(&mut self, candidate: usize) {
+        let surplus = self.votes[candidate] - self.quota;
+        let last_bundle = self.piles[candidate].pop().unwrap();
+        let (transferring, set_aside): (Vec<usize>, Vec<usize>) = last_bundle
+            .papers
+            .iter()
+            .partition(|paper| self.next_preference(**paper).is_some());
+        info!(
+            "Passing on the surplus of {} for {} from {} papers",
+            surplus,
+            candidate,
+            transferring.len()
+        );
+
+        // Papers can't be worth more than when they arrived
+        let value = if transferring.is_empty() {
+            0
+        } else {
+            (surplus * VOTE_SCALE / self.papers_weight(&transferring).max(1)).min(last_bundle.value)
+        };
+        let credited = self.transfer(&transferring, value);
+        self.loss_by_fraction += surplus - credited;
+        self.votes[candidate] = self.quota;
+        self.piles[candidate].push(Parcel {
+            papers: set_aside,
+            ..last_bundle
+        });
+
+        self.record(CountAction::Surplus {
+            candidate,
+            transfer_value: value as f64 / VOTE_SCALE as f64,
+        });
+    }
+
+    /// Passes on each of the candidate's parcels at the value it arrived at
+    fn exclude(&mut self, candidate: usize) {
+        info!("Excluding {}", candidate);
+        self.continuing[candidate] = false;
+        self.excluded.push(candidate);
+
+        let votes_leaving = self.votes[candidate];
+        self.votes[candidate] = 0;
+        let mut parcels = std::mem::take(&mut self.piles[candidate]);
+        parcels.sort_by_key(|parcel| Reverse(parcel.value));
+        let mut credited = 0;
+        for parcel in parcels {
+            credited += self.transfer(&parcel.papers, parcel.value);
+        }
+        self.loss_by_fraction += votes_leaving - credited;
+
+        self.record(CountAction::Exclusion {
+            candidates: vec![candidate],
+        });
+    }
+}
+
+fn count(election: &HareClarkElection, votes: &[HareClarkVote]) -> HareClarkCount {
+    let option_count = election.options.len();
+    let papers = weighted(votes)
+        .filter(|(vote, _)| !vote.votes.is_empty())
+        .map(|(vote, weight)| Paper {
+            preferences: vote.votes.clone(),
+            position: 0,
+            weight: weight as u64,
+        })
+        .collect::<Vec<Paper>>();
+    let formal_votes: u64 = papers.iter().map(|i| i.weight).sum();
+    let quota = formal_votes / (election.vacancies as u64 + 1) + 1;
+    info!("Formal votes {} quota {}", formal_votes, quota);
+
+    let mut count = HareClarkCount {
+        tie_breaker: TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+            borda_from_rankings(
+                option_count,
+                weighted(votes).map(|(vote, weight)| (vote.votes.as_slice(), weight)),
+            )
+        }),
+        quota,
+        vacancies: election.vacancies.min(option_count),
+        papers,
+        piles: vec![vec![]; option_count],
+        votes: vec![0; option_count],
+        exhausted_papers: 0,
+        exhausted_votes: 0,
+        loss_by_fraction: 0,
+        continuing: vec![true; option_count],
+        elected: vec![],
+        excluded: vec![],
+        pending_surpluses: vec![],
+        quota_papers: vec![vec![]; option_count],
+        count_sheets: vec![],
+    };
+
+    let mut first_preferences: Vec<Vec<usize>> = vec![vec![]; option_count];
+    for (paper_index, paper) in count.papers.iter().enumerate() {
+        first_preferences[paper.preferences[0]].push(paper_index);
+    }
+    for (candidate, papers) in first_preferences.into_iter().enumerate() {
+        let votes = count.papers_weight(&papers);
+        count.votes[candidate] = votes;
+        count.piles[candidate].push(Parcel {
+            votes,
+            papers,
+            value: VOTE_SCALE,
+        });
+    }
+    count.record(CountAction::FirstPreferences);
+
+    loop {
+        let reached = count
+            .continuing_candidates()
+            .into_iter()
+            .filter(|i| count.votes[*i] >= quota)
+            .collect::<Vec<usize>>();
+        count.elect(&reached);
+
+        if count.elected.len() == count.vacancies {
+            break;
+        }
+        let continuing = count.continuing_candidates();
+        if continuing.len() <= count.vacancies - count.elected.len() {
+            count.elect(&continuing);
+            break;
+        }
+
+        if !count.pending_surpluses.is_empty() {
+            let candidate = count.pending_surpluses.remove(0);
+            count.distribute_surplus(candidate);
+            continue;
+        }
+
+        let fewest = continuing.iter().map(|i| count.votes[*i]).min().unwrap();
+        let level = continuing
+            .iter()
+            .copied()
+            .filter(|i| count.votes[*i] == fewest)
+            .collect::<Vec<usize>>();
+        let candidate = count.count_back(&level, false);
+        count.exclude(candidate);
+    }
+
+    count
+}
+
+pub fn get_result(election: &HareClarkElection, votes: &[HareClarkVote]) -> HareClarkResult {
+    let count = count(election, votes);
+
+    HareClarkResult {
+        options: election.options.clone(),
+        vacancies: election.vacancies,
+        quota: count.quota,
+        count_sheets: count.count_sheets,
+        elected: count.elected,
+        excluded: count.excluded,
+        vote_count: votes.len(),
+        ties: count.tie_breaker.into_ties(),
+    }
+}
+
+/// Fills the seat `vacating` leaves by recounting the papers that made up
+/// their quota, each going to its most preferred `contesting` candidate,
+/// and excluding the lowest until one has a majority of what is left
+pub fn get_countback(
+    election: &HareClarkElection,
+    votes: &[HareClarkVote],
+    vacating: usize,
+    contesting: &[usize],
+) -> Result<CountbackResult, CountbackError> {
+    let count = count(election, votes);
+
+    if !count.elected.contains(&vacating) {
+        return Err(CountbackError::VacatingNotElected { vacating });
+    }
+    if contesting.is_empty() {
+        return Err(CountbackError::NoContestingCandidates);
+    }
+    if let Some(candidate) = contesting
+        .iter()
+        .find(|i| **i >= election.options.len() || count.elected.contains(i))
+    {
+        return Err(CountbackError::InvalidContesting {
+            candidate: *candidate,
+        });
+    }
+
+    let papers = count.quota_papers[vacating]
+        .iter()
+        .map(|(paper_index, value)| {
+            let preferences = count.papers[*paper_index]
+                .preferences
+                .iter()
+                .copied()
+                .filter(|i| contesting.contains(i))
+                .collect::<Vec<usize>>();
+            (preferences, *value)
+        })
+        .collect::<Vec<(Vec<usize>, u64)>>();
+    let quota_value: u64 = papers.iter().map(|(_, value)| value).sum();
+
+    let mut tie_breaker = count.tie_breaker.restart();
+    let mut standing = contesting.to_vec();
+    let mut rounds: Vec<(Vec<u64>, u64)> = Vec::new();
+    let mut excluded = Vec::new();
+    let winner = loop {
+        let mut tally = vec![0; election.options.len()];
+        let mut exhausted = 0;
+        for (preferences, value) in &papers {
+            match preferences.iter().find(|i| standing.contains(i)) {
+                Some(candidate) => tally[*candidate] += value,
+                None => exhausted += value,
+            }
+        }
+        rounds.push((tally.clone(), exhausted));
+        tie_breaker.record_round(tally.iter().map(|i| *i as f64));
+
+        let most = standing.iter().map(|i| tally[*i]).max().unwrap();
+        let remaining: u64 = standing.iter().map(|i| tally[*i]).sum();
+        if standing.len() == 1 || most * 2 > remaining {
+            let level = standing
+                .iter()
+                .copied()
+                .filter(|i| tally[*i] == most)
+                .collect::<Vec<usize>>();
+            break tie_breaker.break_tie(TieKind::Elect, &level, |rng| *level.choose(rng).unwrap());
+        }
+
+        let fewest = standing.iter().map(|i| tally[*i]).min().unwrap();
+        let level = standing
+            .iter()
+            .copied()
+            .filter(|i| tally[*i] == fewest)
+            .collect::<Vec<usize>>();
+        // Ties for the fewest go back through the earlier rounds then by lot
+        let lowest = tie_breaker.break_tie(TieKind::Exclude, &level, |rng| {
+            let mut level = level.clone();
+            for (tally, _) in rounds.iter().rev() {
+                let fewest = level.iter().map(|i| tally[*i]).min().unwrap();
+                level.retain(|i| tally[*i] == fewest);
+            }
+            *level.choose(rng).unwrap()
+        });
+        standing.retain(|i| *i != lowest);
+        excluded.push(lowest);
+    };
+
+    let as_votes = |value: u64| value as f64 / VOTE_SCALE as f64;
+    let rounds = rounds
+        .into_iter()
+        .enumerate()
+        .map(|(round, (tally, exhausted))| CountbackRound {
+            votes: (0..election.options.len())
+                .map(|i| {
+                    (contesting.contains(&i) && !excluded[..round].contains(&i))
+                        .then(|| as_votes(tally[i]))
+                })
+                .collect(),
+            exhausted: as_votes(exhausted),
+            excluded: excluded.get(round).copied(),
+        })
+        .collect();
+
+    Ok(CountbackResult {
+        options: election.options.clone(),
+        vacating,
+        contesting: contesting.to_vec(),
+        paper_count: papers.len(),
+        quota_value: as_votes(quota_value),
+        rounds,
+        winner,
+        ties: tie_breaker.into_ties(),
+    })
+}
+
+pub struct HareClark;
+
+impl VotingSystem for HareClark {
+    type Ballot = HareClarkVote;
+    type Config = HareClarkElection;
+    type Outcome = HareClarkResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let final_votes = report
+            .count_sheets
+            .last()
+            .map(|count_sheet| {
+                count_sheet
+                    .candidates
+                    .iter()
+                    .map(|column| column.votes as usize)
+                    .collect::<Vec<usize>>()
+            })
+            .unwrap_or_else(|| vec![0; report.options.len()]);
+        let standing = (0..report.options.len())
+            .filter(|i| !report.elected.contains(i) && !report.excluded.contains(i))
+            .collect::<Vec<usize>>();
+
+        Outcome::from_places(
+            report.elected.clone(),
+            rank_by_removal(&standing, &final_votes, &report.excluded),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn votes(groups: &[(usize, &[usize])]) -> Vec<HareClarkVote> {
+        let mut votes = vec![];
+        for (count, preferences) in groups {
+            for _ in 0..*count {
+                votes.push(HareClarkVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: preferences.to_vec(),
+                    rotation: votes.len(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election() -> HareClarkElection {
+        HareClarkElection {
+            options: ["A", "B", "C", "D", "E"]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            vacancies: 3,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    fn example_votes() -> Vec<HareClarkVote> {
+        votes(&[
+            (30, &[0, 2, 1, 3]),
+            (10, &[0]),
+            (20, &[1, 3]),
+            (15, &[2, 1, 4]),
+            (15, &[3, 2]),
+            (10, &[4, 2]),
+        ])
+    }
+
+    #[test]
+    fn test_robson_rotation_is_balanced() {
+        for option_count in [4, 5] {
+            let rotations = (0..rotation_count(option_count))
+                .map(|rotation| robson_rotation(option_count, rotation))
+                .collect::<Vec<Vec<usize>>>();
+            let each = rotations.len() / option_count;
+
+            for position in 0..option_count {
+                for option in 0..option_count {
+                    let printed = rotations.iter().filter(|i| i[position] == option).count();
+                    assert_eq!(printed, each);
+                }
+            }
+            for first in 0..option_count {
+                for second in (0..option_count).filter(|i| *i != first) {
+                    let adjacent = rotations
+                        .iter()
+                        .filter(|i| i.windows(2).any(|pair| pair == [first, second]))
+                        .count();
+                    assert_eq!(adjacent, each);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_result_last_bundle_surplus() {
+        let result = get_result(&election(), &example_votes());
+        assert_eq!(result.quota, 26);
+
+        // A's 14 surplus is shared by the 30 papers with a next preference,
+        // taking C over quota with 13 whole votes
+        let count_sheet = &result.count_sheets[1];
+        assert_eq!(count_sheet.candidates[0].papers, 10);
+        assert_eq!(count_sheet.candidates[2].votes, 28);
+        assert_eq!(count_sheet.loss_by_fraction, 1);
+        assert_eq!(count_sheet.elected, vec![2]);
+
+        // Only the 30 papers C got from A carry C's surplus on, not C's own
+        let count_sheet = &result.count_sheets[2];
+        assert_eq!(
+            count_sheet.action,
+            CountAction::Surplus {
+                candidate: 2,
+                transfer_value: 0.066666666
+            }
+        );
+        assert_eq!(count_sheet.candidates[2].papers_transferred, -30);
+        assert_eq!(count_sheet.candidates[2].papers, 15);
+        assert_eq!(count_sheet.candidates[1].votes, 21);
+
+        assert_eq!(result.elected, vec![0, 2, 1]);
+        assert_eq!(result.excluded, vec![4, 3]);
+    }
+
+    #[test]
+    fn test_get_countback() {
+        let election = election();
+        let votes = example_votes();
+
+        // C's quota is their own 15 papers and what is left of the bundle
+        // from A, which goes to D
+        let result = get_countback(&election, &votes, 2, &[3, 4]).unwrap();
+        assert_eq!(result.paper_count, 45);
+        assert!((result.quota_value - 26.0).abs() < 1e-6);
+        assert_eq!(result.rounds.len(), 1);
+        assert_eq!(result.rounds[0].votes[4], Some(15.0));
+        assert!((result.rounds[0].votes[3].unwrap() - 11.0).abs() < 1e-6);
+        assert_eq!(result.rounds[0].votes[0], None);
+        assert_eq!(result.winner, 4);
+
+        assert_eq!(
+            get_countback(&election, &votes, 3, &[4]).unwrap_err(),
+            CountbackError::VacatingNotElected { vacating: 3 }
+        );
+        assert_eq!(
+            get_countback(&election, &votes, 2, &[0]).unwrap_err(),
+            CountbackError::InvalidContesting { candidate: 0 }
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use std::{cmp::Reverse, fmt};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::get_matchups,
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+/// Up to this many options every ranking is scored
+pub const BRUTE_FORCE_OPTION_LIMIT: usize = 8;
+/// Largest election the exact search will take on. Branch and bound is still
+/// factorial in the worst case so this keeps a result request to seconds
+pub const MAX_OPTIONS: usize = 12;
+/// How many of the next best rankings are reported after the consensus ranking
+pub const RUNNER_UP_COUNT: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KemenyYoungElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KemenyYoungVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The preference given to each option, 0 being the most preferred
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(KemenyYoungVote);
+
+impl RankedChoiceVote<usize> for KemenyYoungVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum KemenyYoungSearch {
+    BruteForce,
+    BranchAndBound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KemenyYoungRanking {
+    /// Options from first to last
+    pub ranking: Vec<usize>,
+    /// Number of voter preferences over pairs of options the ranking agrees with
+    pub score: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KemenyYoungResult {
+    pub options: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    /// The highest scoring ranking, under `TieBreak::Method` ties go to the
+    /// ranking that comes first when compared option by option
+    pub ranking: KemenyYoungRanking,
+    pub runner_up_rankings: Vec<KemenyYoungRanking>,
+    pub search: KemenyYoungSearch,
+    /// Complete rankings scored by the search
+    pub rankings_checked: usize,
+    pub winner: usize,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum KemenyYoungError {
+    NoOptions,
+    TooManyOptions { option_count: usize, limit: usize },
+}
+
+impl fmt::Display for KemenyYoungError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KemenyYoungError::NoOptions => write!(f, "Kemeny-Young needs at least one option"),
+            KemenyYoungError::TooManyOptions {
+                option_count,
+                limit,
+            } => write!(
+                f,
+                "Kemeny-Young can rank at most {} options but the election has {}",
+                limit, option_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KemenyYoungError {}
+
+/// Keeps the best rankings seen so far, best first
+struct BestRankings {
+    rankings: Vec<KemenyYoungRanking>,
+    capacity: usize,
+    checked: usize,
+    /// The first ranking seen on the best score for each option that leads
+    /// one, however many rankings are level
+    leaders: Vec<KemenyYoungRanking>,
+}
+
+impl BestRankings {
+    fn new(capacity: usize) -> BestRankings {
+        BestRankings {
+            rankings: Vec::new(),
+            capacity,
+            checked: 0,
+            leaders: Vec::new(),
+        }
+    }
+
+    fn best_score(&self) -> Option<usize> {
+        self.leaders.first().map(|i| i.score)
+    }
+
+    /// Whether a ranking starting with `first` could still reach `score` and
+    /// put a new option among the leaders
+    fn could_lead(&self, first: Option<&usize>, score: usize) -> bool {
+        if self.best_score() < Some(score) {
+            return true;
+        }
+        match first {
+            _ if self.best_score() != Some(score) => false,
+            Some(first) => !self.leaders.iter().any(|i| i.ranking[0] == *first),
+            None => true,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.rankings.len() >= self.capacity
+    }
+
+    /// The score a new ranking must beat to be kept
+    fn worst_score(&self) -> usize {
+        self.rankings.last().map_or(0, |i| i.score)
+    }
+
+    fn offer(&mut self, ranking: &[usize], score: usize) {
+        self.checked += 1;
+        if self.could_lead(ranking.first(), score) {
+            let leader = KemenyYoungRanking {
+                ranking: ranking.to_vec(),
+                score,
+            };
+            if self.best_score() < Some(score) {
+                self.leaders = vec![leader];
+            } else {
+                self.leaders.push(leader);
+            }
+        }
+
+        if self.is_full() && score <= self.worst_score() {
+            return;
+        }
+
+        self.rankings.push(KemenyYoungRanking {
+            ranking: ranking.to_vec(),
+            score,
+        });
+        // Stable so earlier rankings win ties
+        self.rankings.sort_by_key(|i| Reverse(i.score));
+        self.rankings.truncate(self.capacity);
+    }
+}
+
+/// Score of a ranking, the sum of `matchups[a][b]` for every `a` ranked above `b`
+pub fn get_ranking_score(matchups: &[Vec<usize>], ranking: &[usize]) -> usize {
+    let mut score = 0;
+    for (i, a) in ranking.iter().enumerate() {
+        for b in &ranking[i + 1..] {
+            score += matchups[*a][*b];
+        }
+    }
+    score
+}
+
+/// Visits every ranking in lexicographic order
+fn brute_force(
+    matchups: &[Vec<usize>],
+    ranking: &mut Vec<usize>,
+    remaining: &mut Vec<usize>,
+    best: &mut BestRankings,
+) {
+    if remaining.is_empty() {
+        best.offer(ranking, get_ranking_score(matchups, ranking));
+        return;
+    }
+
+    for i in 0..remaining.len() {
+        let option_index = remaining.remove(i);
+        ranking.push(option_index);
+        brute_force(matchups, ranking, remaining, best);
+        ranking.pop();
+        remaining.insert(i, option_index);
+    }
+}
+
+/// Best score the remaining options could add amongst themselves, every pair
+/// going the more popular way
+fn upper_bound(matchups: &[Vec<usize>], remaining: &[usize]) -> usize {
+    let mut bound = 0;
+    for (i, a) in remaining.iter().enumerate() {
+        for b in &remaining[i + 1..] {
+            bound += matchups[*a][*b].max(matchups[*b][*a]);
+        }
+    }
+    bound
+}
+
+/// Builds rankings one place at a time in lexicographic order. A branch is
+/// cut once it cannot beat the worst kept ranking, on an equal score it
+/// would lose the tie to the earlier ranking anyway. Branches that could
+/// still reach the best score are kept while they lead with an option no
+/// best ranking starts with yet
+fn branch_and_bound(
+    matchups: &[Vec<usize>],
+    ranking: &mut Vec<usize>,
+    score: usize,
+    remaining: &mut Vec<usize>,
+    best: &mut BestRankings,
+) {
+    if remaining.is_empty() {
+        best.offer(ranking, score);
+        return;
+    }
+
+    let bound = score + upper_bound(matchups, remaining);
+    if best.is_full() && bound <= best.worst_score() && !best.could_lead(ranking.first(), bound) {
+        return;
+    }
+
+    for i in 0..remaining.len() {
+        let option_index = remaining.remove(i);
+        let gained: usize = remaining.iter().map(|j| matchups[option_index][*j]).sum();
+        ranking.push(option_index);
+        branch_and_bound(matchups, ranking, score + gained, remaining, best);
+        ranking.pop();
+        remaining.insert(i, option_index);
+    }
+}
+
+pub fn get_result(
+    election: &KemenyYoungElection,
+    votes: &[KemenyYoungVote],
+) -> Result<KemenyYoungResult, KemenyYoungError> {
+    let option_count = election.options.len();
+    if option_count == 0 {
+        return Err(KemenyYoungError::NoOptions);
+    }
+    if option_count > MAX_OPTIONS {
+        return Err(KemenyYoungError::TooManyOptions {
+            option_count,
+            limit: MAX_OPTIONS,
+        });
+    }
+
+    let matchups = get_matchups(option_count, votes);
+
+    let mut best = BestRankings::new(RUNNER_UP_COUNT + 1);
+    let mut remaining = (0..option_count).collect::<Vec<usize>>();
+    let search = if option_count <= BRUTE_FORCE_OPTION_LIMIT {
+        brute_force(&matchups, &mut Vec::new(), &mut remaining, &mut best);
+        KemenyYoungSearch::BruteForce
+    } else {
+        branch_and_bound(&matchups, &mut Vec::new(), 0, &mut remaining, &mut best);
+        KemenyYoungSearch::BranchAndBound
+    };
+
+    // Rankings level on the best score are settled by the option they put
+    // first, every option leading one of them is in the tie
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            option_count,
+            weighted(votes)
+                .map(|(vote, weight)| (vote.votes.iter().map(|i| Reverse(*i)).collect(), weight)),
+        )
+    });
+    let mut rankings = best.rankings;
+    let mut leaders = best
+        .leaders
+        .iter()
+        .map(|i| i.ranking[0])
+        .collect::<Vec<usize>>();
+    let first = leaders[0];
+    leaders.sort();
+    let leader = tie_breaker.break_tie(TieKind::Elect, &leaders, |_| first);
+    let ranking = match rankings.iter().position(|i| i.ranking[0] == leader) {
+        Some(chosen) => rankings.remove(chosen),
+        None => {
+            rankings.truncate(RUNNER_UP_COUNT);
+            best.leaders
+                .into_iter()
+                .find(|i| i.ranking[0] == leader)
+                .unwrap()
+        }
+    };
+
+    Ok(KemenyYoungResult {
+        options: election.options.clone(),
+        matchups,
+        winner: ranking.ranking[0],
+        ranking,
+        runner_up_rankings: rankings,
+        search,
+        rankings_checked: best.checked,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    })
+}
+
+pub struct KemenyYoung;
+
+impl VotingSystem for KemenyYoung {
+    type Ballot = KemenyYoungVote;
+    type Config = KemenyYoungElection;
+    type Outcome = Result<KemenyYoungResult, KemenyYoungError>;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let report = match report {
+            Ok(report) => report,
+            Err(_) => return Outcome::new(Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        let ties = report.ties.iter().map(|i| i.tied.clone()).collect();
+
+        Outcome::new(
+            vec![report.winner],
+            report.ranking.ranking.iter().map(|i| vec![*i]).collect(),
+            ties,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<usize>) -> KemenyYoungVote {
+        KemenyYoungVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election(option_count: usize) -> KemenyYoungElection {
+        KemenyYoungElection {
+            options: (0..option_count).map(|i| i.to_string()).collect(),
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_tennessee() {
+        // Memphis, Nashville, Chattanooga, Knoxville
+        let (m, n, c, k) = (0, 1, 2, 3);
+        let mut votes = vec![];
+        for _ in 0..42 {
+            votes.push(vote(vec![0, 1, 2, 3]));
+        }
+        for _ in 0..26 {
+            votes.push(vote(vec![3, 0, 1, 2]));
+        }
+        for _ in 0..15 {
+            votes.push(vote(vec![3, 2, 0, 1]));
+        }
+        for _ in 0..17 {
+            votes.push(vote(vec![3, 2, 1, 0]));
+        }
+
+        let result = get_result(&election(4), &votes).unwrap();
+        assert_eq!(result.search, KemenyYoungSearch::BruteForce);
+        assert_eq!(result.rankings_checked, 24);
+        assert_eq!(result.ranking.ranking, vec![n, c, k, m]);
+        assert_eq!(result.ranking.score, 393);
+        assert_eq!(result.winner, n);
+        assert_eq!(result.runner_up_rankings.len(), RUNNER_UP_COUNT);
+        assert!(result
+            .runner_up_rankings
+            .iter()
+            .all(|i| i.score <= result.ranking.score));
+    }
+
+    #[test]
+    fn test_branch_and_bound_matches_brute_force() {
+        let option_count = BRUTE_FORCE_OPTION_LIMIT + 1;
+        let mut votes = vec![];
+        for i in 0..option_count {
+            // Each voter shifts the ranking along so every pair is contested
+            votes.push(vote(
+                (0..option_count)
+                    .map(|j| (j + i * 2) % option_count)
+                    .collect(),
+            ));
+        }
+        votes.push(vote((0..option_count).collect()));
+
+        let result = get_result(&election(option_count), &votes).unwrap();
+        assert_eq!(result.search, KemenyYoungSearch::BranchAndBound);
+
+        let matchups = get_matchups(option_count, &votes);
+        let mut best = BestRankings::new(RUNNER_UP_COUNT + 1);
+        brute_force(
+            &matchups,
+            &mut Vec::new(),
+            &mut (0..option_count).collect(),
+            &mut best,
+        );
+        assert_eq!(result.ranking, best.rankings[0]);
+        assert_eq!(result.runner_up_rankings, best.rankings[1..].to_vec());
+        assert!(result.rankings_checked < best.checked);
+    }
+
+    #[test]
+    fn test_get_result_level_leaders() {
+        // With no preferences every ranking is level, far more than are kept.
+        // Branch and bound keeps searching branches led by a new option
+        let option_count = BRUTE_FORCE_OPTION_LIMIT + 1;
+        let result = get_result(&election(option_count), &[]).unwrap();
+        assert_eq!(result.search, KemenyYoungSearch::BranchAndBound);
+        assert_eq!(
+            result.ties[0].tied,
+            (0..option_count).collect::<Vec<usize>>()
+        );
+
+        let mut election = election(4);
+        let result = get_result(&election, &[]).unwrap();
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.ties.len(), 1);
+        assert_eq!(result.ties[0].tied, vec![0, 1, 2, 3]);
+
+        election.tie_break = TieBreak::Lot;
+        let result = get_result(&election, &[]).unwrap();
+        assert_eq!(result.ties[0].tied, vec![0, 1, 2, 3]);
+        assert_eq!(result.ties[0].chosen, result.winner);
+        assert_eq!(result.ranking.ranking[0], result.winner);
+        assert_eq!(result.runner_up_rankings.len(), RUNNER_UP_COUNT);
+    }
+
+    #[test]
+    fn test_get_result_too_many_options() {
+        let result = get_result(&election(MAX_OPTIONS + 1), &[]);
+        assert_eq!(
+            result.unwrap_err(),
+            KemenyYoungError::TooManyOptions {
+                option_count: MAX_OPTIONS + 1,
+                limit: MAX_OPTIONS,
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_result_few_options() {
+        let result = get_result(&election(0), &[]);
+        assert_eq!(result.unwrap_err(), KemenyYoungError::NoOptions);
+        assert_eq!(
+            KemenyYoung::outcome(&KemenyYoung::tabulate(&election(0), &[])),
+            Outcome::new(Vec::new(), Vec::new(), Vec::new())
+        );
+
+        let result = get_result(&election(1), &[vote(vec![0]), vote(vec![0])]).unwrap();
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.ranking.ranking, vec![0]);
+        assert_eq!(result.ranking.score, 0);
+        assert!(result.runner_up_rankings.is_empty());
+        assert!(result.ties.is_empty());
+    }
+}
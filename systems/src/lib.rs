@@ -1,4 +1,71 @@
+#[cfg(feature = "anti_plurality")]
+pub mod anti_plurality;
+#[cfg(feature = "approval")]
+pub mod approval;
+#[cfg(feature = "australian_senate")]
+pub mod australian_senate;
+#[cfg(feature = "black")]
+pub mod black;
+#[cfg(feature = "borda_count")]
+pub mod borda_count;
+#[cfg(feature = "bucklin")]
+pub mod bucklin;
+#[cfg(feature = "condorcet_method")]
+pub mod condorcet_method;
+#[cfg(feature = "copeland")]
+pub mod copeland;
+#[cfg(feature = "cumulative")]
+pub mod cumulative;
+pub mod delegation;
+#[cfg(feature = "hare_clark")]
+pub mod hare_clark;
+#[cfg(feature = "kemeny_young")]
+pub mod kemeny_young;
+#[cfg(feature = "majority_judgment")]
+pub mod majority_judgment;
+#[cfg(feature = "meek_stv")]
+pub mod meek_stv;
+#[cfg(feature = "minimax")]
+pub mod minimax;
+#[cfg(feature = "multi_winner_star")]
+pub mod multi_winner_star;
+pub mod numeric;
+#[cfg(feature = "participatory_budgeting")]
+pub mod participatory_budgeting;
+#[cfg(feature = "party_list")]
+pub mod party_list;
+#[cfg(feature = "preferential_voting")]
+pub mod preferential_voting;
+#[cfg(feature = "proportional_approval")]
+pub mod proportional_approval;
+#[cfg(feature = "proportional_score")]
+pub mod proportional_score;
+#[cfg(feature = "quadratic")]
+pub mod quadratic;
 #[cfg(feature = "quota_preferential_vic_labor_2024")]
 pub mod quota_preferential_vic_labor_2024;
+pub mod ranked_choice;
+#[cfg(feature = "schulze")]
+pub mod schulze;
+#[cfg(feature = "score")]
+pub mod score;
+pub mod seed;
+#[cfg(feature = "sequential_elimination")]
+pub mod sequential_elimination;
 #[cfg(feature = "single_non_transferable_vote")]
 pub mod single_non_transferable_vote;
+#[cfg(feature = "single_party")]
+pub mod single_party;
+#[cfg(feature = "single_transferable_vote")]
+pub mod single_transferable_vote;
+#[cfg(feature = "star")]
+pub mod star;
+#[cfg(feature = "three_two_one")]
+pub mod three_two_one;
+pub mod tie_break;
+#[cfg(feature = "two_round")]
+pub mod two_round;
+#[cfg(feature = "usual_judgment")]
+pub mod usual_judgment;
+pub mod voting_system;
+pub mod weight;
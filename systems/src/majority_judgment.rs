@@ -0,0 +1,392 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    score::{self, ScoreElection, ScoreResult, ScoreVote},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MajorityJudgmentElection {
+    pub options: Vec<String>,
+    /// Seeds the score voting fallback used when the runoff is tied
+    pub seed: String,
+    /// Under `TieBreak::Method` a tied runoff goes to score voting, any other
+    /// policy settles it directly
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq, Ord, PartialOrd)]
+pub enum Rating {
+    Terrible,
+    Poor,
+    Acceptable,
+    Good,
+    VeryGood,
+}
+
+impl From<Rating> for i32 {
+    fn from(val: Rating) -> Self {
+        match val {
+            Rating::VeryGood => 4,
+            Rating::Good => 3,
+            Rating::Acceptable => 2,
+            Rating::Poor => 1,
+            Rating::Terrible => 0,
+        }
+    }
+}
+
+impl From<Rating> for usize {
+    fn from(val: Rating) -> Self {
+        match val {
+            Rating::VeryGood => 4,
+            Rating::Good => 3,
+            Rating::Acceptable => 2,
+            Rating::Poor => 1,
+            Rating::Terrible => 0,
+        }
+    }
+}
+
+impl From<i32> for Rating {
+    fn from(val: i32) -> Self {
+        match val {
+            4 => Rating::VeryGood,
+            3 => Rating::Good,
+            2 => Rating::Acceptable,
+            1 => Rating::Poor,
+            0 => Rating::Terrible,
+            _ => panic!("Invalid rating"),
+        }
+    }
+}
+
+impl From<usize> for Rating {
+    fn from(val: usize) -> Self {
+        match val {
+            4 => Rating::VeryGood,
+            3 => Rating::Good,
+            2 => Rating::Acceptable,
+            1 => Rating::Poor,
+            0 => Rating::Terrible,
+            _ => panic!("Invalid rating"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MajorityJudgmentVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    pub votes: Vec<Rating>,
+}
+
+impl_weighted_ballot!(MajorityJudgmentVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MajorityJudgmentTally {
+    pub option_index: usize,
+    pub ratings: [i64; 5],
+}
+
+impl MajorityJudgmentTally {
+    pub fn median(&self) -> Rating {
+        let count: i64 = self.ratings.iter().sum();
+        let target = count / 2;
+
+        let mut seen = 0;
+        for (k, v) in self.ratings.iter().enumerate() {
+            seen += v;
+            if seen > target {
+                return k.into();
+            }
+        }
+
+        Rating::Terrible
+    }
+}
+
+fn highest_median(tally: &[MajorityJudgmentTally]) -> Rating {
+    tally
+        .iter()
+        .map(|i| i.median())
+        .max()
+        .expect("No options in round")
+}
+
+fn options_matching_median(tally: &[MajorityJudgmentTally], median: Rating) -> Vec<usize> {
+    tally
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.median() == median)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MajorityJudgmentRunoff {
+    pub modified_tally: Vec<MajorityJudgmentTally>,
+    pub best_median: Rating,
+    pub participants: Vec<usize>,
+    pub winners: Vec<usize>,
+}
+
+fn find_winners(starting_tally: &[MajorityJudgmentTally]) -> MajorityJudgmentRunoff {
+    let best_median = highest_median(starting_tally);
+    let best_median_idx: usize = best_median.into();
+    let mut modified_tally = starting_tally.to_vec();
+    let mut old_tally = modified_tally.clone();
+    let mut options_matching;
+    loop {
+        options_matching = options_matching_median(&modified_tally, best_median);
+
+        if options_matching.is_empty() {
+            return MajorityJudgmentRunoff {
+                modified_tally,
+                best_median,
+                participants: options_matching_median(starting_tally, best_median),
+                winners: options_matching_median(&old_tally, best_median),
+            };
+        }
+
+        if options_matching.len() == 1 {
+            return MajorityJudgmentRunoff {
+                modified_tally,
+                best_median,
+                participants: options_matching_median(starting_tally, best_median),
+                winners: vec![options_matching[0]],
+            };
+        }
+
+        // Subtract one from the selected median for all options
+        old_tally = modified_tally.clone();
+        for option in &options_matching {
+            let tally = modified_tally.get_mut(*option).unwrap();
+            let rating = tally.ratings.get_mut(best_median_idx).unwrap();
+            if *rating - 1 < 1 {
+                continue;
+            }
+            *rating -= 1;
+        }
+
+        // Nothing left to remove so the remaining options are tied
+        if modified_tally == old_tally {
+            return MajorityJudgmentRunoff {
+                modified_tally,
+                best_median,
+                participants: options_matching_median(starting_tally, best_median),
+                winners: options_matching,
+            };
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MajorityJudgmentResult {
+    pub options: Vec<String>,
+    pub starting_tally: Vec<MajorityJudgmentTally>,
+    pub best_median: Rating,
+    pub runoff: MajorityJudgmentRunoff,
+    pub score_result: Option<ScoreResult>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub votes: Vec<MajorityJudgmentVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(
+    election: &MajorityJudgmentElection,
+    votes: &[MajorityJudgmentVote],
+) -> MajorityJudgmentResult {
+    let mut tally = Vec::new();
+    for i in 0..election.options.len() {
+        tally.push(MajorityJudgmentTally {
+            option_index: i,
+            ratings: [0, 0, 0, 0, 0],
+        })
+    }
+    for (vote, weight) in weighted(votes) {
+        for (i, rating) in vote.votes.iter().enumerate() {
+            let rating_index: usize = (*rating).into();
+            tally[i].ratings[rating_index] += weight as i64;
+        }
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let runoff = find_winners(&tally);
+    let mut score_result = None;
+    if runoff.winners.len() > 1 && election.tie_break == TieBreak::Method {
+        let score_election = ScoreElection {
+            options: election.options.clone(),
+            max_score: Rating::VeryGood.into(),
+            seed: election.seed.clone(),
+            tie_break: TieBreak::Method,
+        };
+        let score_votes = votes
+            .iter()
+            .map(|i| ScoreVote {
+                created_by: i.created_by,
+                weight: i.weight,
+                votes: i
+                    .votes
+                    .iter()
+                    .enumerate()
+                    .map(|(option_index, j)| {
+                        if runoff.winners.contains(&option_index) {
+                            (*j).into()
+                        } else {
+                            0
+                        }
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        score_result = Some(score::get_result(&score_election, &score_votes));
+    };
+
+    let winner = tie_breaker.break_tie(TieKind::Elect, &runoff.winners, |_| {
+        score_result
+            .as_ref()
+            .map_or(runoff.winners[0], |score_result| score_result.winner)
+    });
+
+    MajorityJudgmentResult {
+        options: election.options.clone(),
+        winner,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        best_median: highest_median(&tally),
+        starting_tally: tally,
+        runoff,
+        score_result,
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct MajorityJudgment;
+
+impl VotingSystem for MajorityJudgment {
+    type Ballot = MajorityJudgmentVote;
+    type Config = MajorityJudgmentElection;
+    type Outcome = MajorityJudgmentResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut ties = Vec::new();
+        if report.runoff.winners.len() > 1 {
+            ties.push(report.runoff.winners.clone());
+        }
+
+        let mut places = vec![report.runoff.winners.clone()];
+        places.extend(rank_by_score(
+            report
+                .starting_tally
+                .iter()
+                .filter(|i| !report.runoff.winners.contains(&i.option_index))
+                .map(|i| (i.option_index, i.median())),
+        ));
+
+        Outcome::new(vec![report.winner], places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<Rating>) -> MajorityJudgmentVote {
+        MajorityJudgmentVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        let tally = MajorityJudgmentTally {
+            option_index: 0,
+            ratings: [1, 0, 1, 2, 1],
+        };
+
+        assert_eq!(tally.median(), Rating::Good);
+    }
+
+    #[test]
+    fn test_get_result_highest_median() {
+        use Rating::*;
+
+        let election = MajorityJudgmentElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![Good, Poor]),
+            vote(vec![Acceptable, VeryGood]),
+            vote(vec![Good, Poor]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.best_median, Good);
+        assert_eq!(result.winner, 0);
+        assert!(result.score_result.is_none());
+    }
+
+    #[test]
+    fn test_get_result_weighted_median() {
+        use Rating::*;
+
+        let election = MajorityJudgmentElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // The one ballot preferring B outweighs the other two
+        let votes = vec![
+            vote(vec![Good, Poor]),
+            MajorityJudgmentVote {
+                weight: Weight::new(5, 2).unwrap(),
+                ..vote(vec![Acceptable, VeryGood])
+            },
+            vote(vec![Good, Poor]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.best_median, VeryGood);
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.starting_tally[1].ratings, [0, 4, 0, 0, 5]);
+    }
+
+    #[test]
+    fn test_get_result_identical_ballots_fall_back_to_score() {
+        use Rating::*;
+
+        let election = MajorityJudgmentElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![vote(vec![Good, Good])];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.runoff.winners, vec![0, 1]);
+        assert!(result.score_result.is_some());
+    }
+}
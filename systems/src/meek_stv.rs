@@ -0,0 +1,429 @@
+use log::info;
+use num_bigint::BigInt;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    numeric::Number,
+    ranked_choice::{tally_ranked_votes, RankedChoiceVoteTally},
+    single_transferable_vote::{borda_scores, StvVote},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{Outcome, VotingSystem},
+    weight::{total_weight, weighted},
+};
+
+/// Keep values stop being adjusted after this many passes even if the
+/// surplus is still above the tolerance
+pub const MAX_ITERATIONS: usize = 10_000;
+
+/// The count is kept in whole billionths of a vote, as in Hill, Wichmann
+/// and Woodall's algorithm. Keep values are rounded up and the votes an
+/// option keeps rounded down, so rounding never passes on more than a ballot
+/// holds and the result doesn't depend on the order of the ballots
+const SCALE: i128 = 1_000_000_000;
+
+fn to_number(value: i128) -> Number {
+    &Number::from(BigInt::from(value)) / &Number::from(BigInt::from(SCALE))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MeekStvElection {
+    pub options: Vec<String>,
+    pub elected_count: usize,
+    /// Keep values are adjusted until the surplus of every elected option
+    /// adds up to no more than this, must be more than 0
+    pub tolerance: f64,
+    /// Seeds the RNG used when options are level in every round
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct MeekStvRound {
+    /// Droop quota over the votes that have not exhausted
+    pub quota: Number,
+    /// Share of each vote reaching an option that the option keeps, 1 while
+    /// hopeful and 0 once excluded
+    pub keep_values: Vec<Number>,
+    pub vote_counts: Vec<Number>,
+    /// Votes that passed every preference on their ballot
+    pub excess: Number,
+    /// Passes taken to settle the keep values
+    pub iterations: usize,
+    /// False when the surplus was still above the tolerance after
+    /// `MAX_ITERATIONS` passes
+    pub converged: bool,
+    pub elected_candidates: Vec<usize>,
+    pub eliminated_candidates: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct MeekStvResult {
+    pub candidates: Vec<String>,
+    /// In the order they were elected
+    pub elected_candidates: Vec<usize>,
+    pub starting_eliminated_candidates: Vec<usize>,
+    pub tolerance: f64,
+    pub rounds: Vec<MeekStvRound>,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Passes every ballot down its preferences, each option keeping its keep
+/// value of what reaches it. Returns the votes for each option and the excess
+fn distribute(option_count: usize, votes: &[StvVote], keep_values: &[i128]) -> (Vec<i128>, i128) {
+    let mut vote_counts = vec![0; option_count];
+    let mut excess = 0;
+    for (vote, weight) in weighted(votes) {
+        let mut remaining = weight as i128 * SCALE;
+        for option_index in &vote.votes {
+            let kept = remaining * keep_values[*option_index] / SCALE;
+            vote_counts[*option_index] += kept;
+            remaining -= kept;
+            if remaining == 0 {
+                break;
+            }
+        }
+        excess += remaining;
+    }
+    (vote_counts, excess)
+}
+
+fn get_quota(vote_count: usize, excess: i128, elected_count: usize) -> i128 {
+    (vote_count as i128 * SCALE - excess) / (elected_count as i128 + 1)
+}
+
+/// Picks the option to exclude from `candidates` by the fewest votes in the
+/// latest earlier round that separates them, falling back to the RNG
+fn break_elimination_tie(rng: &mut Pcg64, rounds: &[MeekStvRound], candidates: &[usize]) -> usize {
+    let mut candidates = candidates.to_vec();
+    for round in rounds.iter().rev() {
+        let lowest = candidates
+            .iter()
+            .map(|i| &round.vote_counts[*i])
+            .min()
+            .unwrap()
+            .clone();
+        candidates.retain(|i| round.vote_counts[*i] == lowest);
+        if candidates.len() == 1 {
+            return candidates[0];
+        }
+    }
+    *candidates.choose(rng).unwrap()
+}
+
+pub fn get_result(
+    election: &MeekStvElection,
+    votes: &[StvVote],
+    starting_eliminated_candidates: &[usize],
+) -> MeekStvResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let option_count = election.options.len();
+    let mut keep_values = vec![SCALE; option_count];
+    for option_index in starting_eliminated_candidates {
+        keep_values[*option_index] = 0;
+    }
+    let mut elected_candidates: Vec<usize> = Vec::new();
+    let mut eliminated_candidates: Vec<usize> = starting_eliminated_candidates.to_vec();
+    let mut rounds: Vec<MeekStvRound> = Vec::new();
+
+    while elected_candidates.len() < election.elected_count {
+        let hopeful = (0..option_count)
+            .filter(|i| !elected_candidates.contains(i) && !eliminated_candidates.contains(i))
+            .collect::<Vec<usize>>();
+        if hopeful.is_empty() {
+            break;
+        }
+
+        // Lower the keep value of each elected option until it holds about a quota
+        let mut iterations = 0;
+        let (vote_counts, excess, quota, converged) = loop {
+            iterations += 1;
+            let (vote_counts, excess) = distribute(option_count, votes, &keep_values);
+            let quota = get_quota(total_weight(votes), excess, election.elected_count);
+            let surplus: i128 = elected_candidates
+                .iter()
+                .map(|i| (vote_counts[*i] - quota).max(0))
+                .sum();
+            let converged = surplus as f64 / SCALE as f64 <= election.tolerance;
+            if converged || iterations >= MAX_ITERATIONS {
+                break (vote_counts, excess, quota, converged);
+            }
+            for option_index in elected_candidates.iter().filter(|i| vote_counts[**i] > 0) {
+                let count = vote_counts[*option_index];
+                // Rounded up
+                keep_values[*option_index] =
+                    (keep_values[*option_index] * quota + count - 1) / count;
+            }
+        };
+        info!(
+            "Round {} quota {} after {} iterations vote counts {:?}",
+            rounds.len(),
+            quota,
+            iterations,
+            vote_counts
+        );
+        tie_breaker.record_round(vote_counts.iter().map(|i| *i as f64 / SCALE as f64));
+
+        let mut reaching_quota = hopeful
+            .iter()
+            .copied()
+            .filter(|i| vote_counts[*i] >= quota)
+            .collect::<Vec<usize>>();
+        if hopeful.len() + elected_candidates.len() <= election.elected_count {
+            // Everyone left fills a seat
+            reaching_quota = hopeful.clone();
+        }
+
+        if !reaching_quota.is_empty() {
+            reaching_quota.sort_by(|a, b| vote_counts[*b].cmp(&vote_counts[*a]));
+            let mut places: Vec<Vec<usize>> = Vec::new();
+            for (i, option_index) in reaching_quota.iter().enumerate() {
+                if i > 0 && vote_counts[reaching_quota[i - 1]] == vote_counts[*option_index] {
+                    places.last_mut().unwrap().push(*option_index);
+                } else {
+                    places.push(vec![*option_index]);
+                }
+            }
+            let reaching_quota = tie_breaker.take_top(
+                &places,
+                election.elected_count - elected_candidates.len(),
+                |_, place| place.to_vec(),
+            );
+            info!("Electing {:?}", reaching_quota);
+            elected_candidates.extend(reaching_quota);
+        } else {
+            let lowest_count = hopeful.iter().map(|i| vote_counts[*i]).min().unwrap();
+            let lowest = hopeful
+                .iter()
+                .copied()
+                .filter(|i| vote_counts[*i] == lowest_count)
+                .collect::<Vec<usize>>();
+            let to_eliminate = tie_breaker.break_tie(TieKind::Exclude, &lowest, |rng| {
+                if lowest.len() > 1 {
+                    break_elimination_tie(rng, &rounds, &lowest)
+                } else {
+                    lowest[0]
+                }
+            });
+            info!("Eliminating {} with {}", to_eliminate, lowest_count);
+            keep_values[to_eliminate] = 0;
+            eliminated_candidates.push(to_eliminate);
+        }
+
+        rounds.push(MeekStvRound {
+            quota: to_number(quota),
+            keep_values: keep_values.iter().copied().map(to_number).collect(),
+            vote_counts: vote_counts.into_iter().map(to_number).collect(),
+            excess: to_number(excess),
+            iterations,
+            converged,
+            elected_candidates: elected_candidates.clone(),
+            eliminated_candidates: eliminated_candidates.clone(),
+        });
+    }
+
+    MeekStvResult {
+        candidates: election.options.clone(),
+        elected_candidates,
+        starting_eliminated_candidates: starting_eliminated_candidates.to_vec(),
+        tolerance: election.tolerance,
+        rounds,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct MeekStv;
+
+impl VotingSystem for MeekStv {
+    type Ballot = StvVote;
+    type Config = MeekStvElection;
+    type Outcome = MeekStvResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots, &[])
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut ties = Vec::new();
+        let mut decided = report.starting_eliminated_candidates.clone();
+        for round in &report.rounds {
+            for option_index in round
+                .eliminated_candidates
+                .iter()
+                .filter(|i| !decided.contains(i))
+            {
+                let level = (0..report.candidates.len())
+                    .filter(|i| {
+                        !decided.contains(i)
+                            && !round.elected_candidates.contains(i)
+                            && round.vote_counts[*i] == round.vote_counts[*option_index]
+                    })
+                    .collect::<Vec<usize>>();
+                if level.len() > 1 {
+                    ties.push(level);
+                }
+            }
+            decided = round.eliminated_candidates.clone();
+        }
+
+        let last_counts = report.rounds.last().map(|i| &i.vote_counts);
+        let mut standing = (0..report.candidates.len())
+            .filter(|i| {
+                !report.elected_candidates.contains(i)
+                    && !report
+                        .rounds
+                        .iter()
+                        .any(|r| r.eliminated_candidates.contains(i))
+                    && !report.starting_eliminated_candidates.contains(i)
+            })
+            .collect::<Vec<usize>>();
+        if let Some(last_counts) = last_counts {
+            standing.sort_by(|a, b| last_counts[*b].cmp(&last_counts[*a]));
+        }
+
+        let eliminated = report
+            .rounds
+            .last()
+            .map_or_else(Vec::new, |i| i.eliminated_candidates.clone());
+        let mut places: Vec<Vec<usize>> = standing.into_iter().map(|i| vec![i]).collect();
+        places.extend(
+            eliminated
+                .iter()
+                .filter(|i| !report.starting_eliminated_candidates.contains(i))
+                .rev()
+                .map(|i| vec![*i]),
+        );
+        if !report.starting_eliminated_candidates.is_empty() {
+            places.push(report.starting_eliminated_candidates.clone());
+        }
+
+        Outcome::new(report.elected_candidates.clone(), places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::weight::Weight;
+
+    fn vote(votes: Vec<usize>) -> StvVote {
+        StvVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election(option_count: usize, elected_count: usize) -> MeekStvElection {
+        MeekStvElection {
+            options: ["A", "B", "C", "D"][..option_count]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            elected_count,
+            tolerance: 1e-6,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_surplus_transfer() {
+        let mut votes = vec![];
+        for _ in 0..6 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![2, 0, 1]));
+        }
+        votes.push(vote(vec![1, 2, 0]));
+
+        let result = get_result(&election(3, 2), &votes, &[]);
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0].quota, Number::from(3));
+        // Half of each of A's votes moves on to B
+        assert_eq!(result.rounds[1].keep_values[0], Number::new(1, 2));
+        assert_eq!(result.rounds[1].vote_counts[1], Number::from(4));
+    }
+
+    #[test]
+    fn test_get_result_quota_falls_as_votes_exhaust() {
+        let mut votes = vec![];
+        for _ in 0..5 {
+            votes.push(vote(vec![0]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![1]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![2]));
+        }
+        votes.push(vote(vec![3, 1]));
+
+        let result = get_result(&election(4, 2), &votes, &[]);
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        assert_eq!(result.rounds[1].eliminated_candidates, vec![3]);
+        // A keeps half of each vote and the other half exhausts, so the
+        // quota falls from 10 / 3 to 2.5
+        let round = &result.rounds[1];
+        assert!((round.quota.to_f64() - 2.5).abs() < 1e-6);
+        assert!((round.keep_values[0].to_f64() - 0.5).abs() < 1e-6);
+        assert!((round.excess.to_f64() - 2.5).abs() < 1e-6);
+        assert!(round.iterations > 1);
+        assert!(result.rounds.iter().all(|i| i.converged));
+    }
+
+    #[test]
+    fn test_get_result_stops_at_max_iterations() {
+        let mut votes = vec![vote(vec![0, 1, 2]), vote(vec![1, 0, 3]), vote(vec![3])];
+        for _ in 0..3 {
+            votes.push(vote(vec![2, 1]));
+        }
+
+        // B's keep value would settle on an irrational number, rounded up it
+        // always leaves a little over the quota so a tolerance of 0 is never
+        // met
+        let mut election = election(4, 3);
+        election.tolerance = 0.0;
+        let result = get_result(&election, &votes, &[]);
+        assert_eq!(result.elected_candidates, vec![2, 1, 0]);
+        assert!(result.rounds[1].converged);
+        let round = &result.rounds[2];
+        assert!(!round.converged);
+        assert_eq!(round.iterations, MAX_ITERATIONS);
+    }
+
+    #[test]
+    fn test_get_result_ignores_ballot_order() {
+        let mut votes = vec![];
+        for i in 0..12 {
+            votes.push(vote(vec![i % 4, (i + 1) % 4, (i + 3) % 4]));
+        }
+        for _ in 0..5 {
+            votes.push(vote(vec![0, 2]));
+        }
+        votes.push(vote(vec![3, 1]));
+
+        let result = get_result(&election(4, 2), &votes, &[]);
+        assert!(result.rounds.iter().any(|i| i.iterations > 1));
+        let mut rng = Pcg64::seed_from_u64(1);
+        for _ in 0..10 {
+            votes.shuffle(&mut rng);
+            let shuffled = get_result(&election(4, 2), &votes, &[]);
+            assert_eq!(result.elected_candidates, shuffled.elected_candidates);
+            assert_eq!(result.rounds, shuffled.rounds);
+            assert_eq!(result.ties, shuffled.ties);
+        }
+    }
+}
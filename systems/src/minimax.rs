@@ -0,0 +1,193 @@
+use std::cmp::Reverse;
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::{borda_scores, get_matchups, CondorcetMethodVote},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, rank_by_lowest_score, Outcome, VotingSystem},
+};
+
+/// How the strength of a matchup against an option is measured
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimaxVariant {
+    /// Votes for the opponent, only when the opponent wins the matchup
+    WinningVotes,
+    /// Votes for the opponent less votes for the option
+    Margins,
+    /// Votes for the opponent whether or not they win the matchup
+    PairwiseOpposition,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinimaxElection {
+    pub options: Vec<String>,
+    pub variant: MinimaxVariant,
+    /// Seeds the RNG used when options share the smallest worst defeat
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MinimaxScore {
+    pub option_index: usize,
+    /// Strength of the strongest matchup against the option
+    pub worst_defeat: i64,
+    /// Opponent in that matchup, none if there is only one option
+    pub worst_opponent: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinimaxResult {
+    pub options: Vec<String>,
+    pub variant: MinimaxVariant,
+    pub matchups: Vec<Vec<usize>>,
+    /// Smallest worst defeat first
+    pub scores: Vec<MinimaxScore>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn defeat_strength(
+    matchups: &[Vec<usize>],
+    variant: MinimaxVariant,
+    opponent: usize,
+    option_index: usize,
+) -> i64 {
+    let against = matchups[opponent][option_index] as i64;
+    let for_option = matchups[option_index][opponent] as i64;
+    match variant {
+        MinimaxVariant::WinningVotes => {
+            if against > for_option {
+                against
+            } else {
+                0
+            }
+        }
+        MinimaxVariant::Margins => against - for_option,
+        MinimaxVariant::PairwiseOpposition => against,
+    }
+}
+
+pub fn get_scores(matchups: &[Vec<usize>], variant: MinimaxVariant) -> Vec<MinimaxScore> {
+    (0..matchups.len())
+        .map(|i| {
+            let worst = (0..matchups.len())
+                .filter(|j| *j != i)
+                .map(|j| (j, defeat_strength(matchups, variant, j, i)))
+                .max_by_key(|(j, strength)| (*strength, Reverse(*j)));
+            MinimaxScore {
+                option_index: i,
+                worst_defeat: worst.map_or(0, |(_, strength)| strength),
+                worst_opponent: worst.map(|(j, _)| j),
+            }
+        })
+        .collect()
+}
+
+pub fn get_result(election: &MinimaxElection, votes: &[CondorcetMethodVote]) -> MinimaxResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let matchups = get_matchups(election.options.len(), votes);
+    let mut scores = get_scores(&matchups, election.variant);
+    // A smaller worst defeat puts an option ahead
+    tie_breaker.record_round(scores.iter().map(|i| -(i.worst_defeat as f64)));
+    scores.sort_by_key(|a| a.worst_defeat);
+
+    let lowest = scores
+        .iter()
+        .filter(|i| i.worst_defeat == scores[0].worst_defeat)
+        .map(|i| i.option_index)
+        .collect::<Vec<usize>>();
+    let winner = tie_breaker.break_tie(TieKind::Elect, &lowest, |rng| *lowest.choose(rng).unwrap());
+
+    MinimaxResult {
+        options: election.options.clone(),
+        variant: election.variant,
+        matchups,
+        scores,
+        winner,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Minimax;
+
+impl VotingSystem for Minimax {
+    type Ballot = CondorcetMethodVote;
+    type Config = MinimaxElection;
+    type Outcome = MinimaxResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let places = rank_by_lowest_score(
+            report
+                .scores
+                .iter()
+                .map(|i| (i.option_index, i.worst_defeat)),
+        );
+
+        Outcome::new(vec![report.winner], places.clone(), level_places(&places))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn vote(votes: Vec<usize>) -> CondorcetMethodVote {
+        CondorcetMethodVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election(variant: MinimaxVariant) -> MinimaxElection {
+        MinimaxElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            variant,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_cycle() {
+        // A > B 6-3, B > C 7-2, C > A 5-4
+        let mut votes = vec![];
+        for _ in 0..4 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![2, 0, 1]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![1, 2, 0]));
+        }
+
+        let winning_votes = get_result(&election(MinimaxVariant::WinningVotes), &votes);
+        assert_eq!(winning_votes.winner, 0);
+        assert_eq!(winning_votes.scores[0].worst_defeat, 5);
+        assert_eq!(winning_votes.scores[0].worst_opponent, Some(2));
+
+        let margins = get_result(&election(MinimaxVariant::Margins), &votes);
+        assert_eq!(margins.winner, 0);
+        assert_eq!(margins.scores[0].worst_defeat, 1);
+
+        let opposition = get_result(&election(MinimaxVariant::PairwiseOpposition), &votes);
+        assert_eq!(opposition.winner, 0);
+        assert_eq!(opposition.scores[0].worst_defeat, 5);
+        assert_eq!(opposition.scores[2].option_index, 2);
+        assert_eq!(opposition.scores[2].worst_defeat, 7);
+    }
+}
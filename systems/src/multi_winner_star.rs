@@ -0,0 +1,359 @@
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    star::StarVote,
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{Outcome, VotingSystem},
+    weight::{total_weight, weighted},
+};
+
+/// Most stars a ballot can give an option
+pub const MAX_STARS: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiStarMethod {
+    /// Runs STAR once per seat over the options not yet elected
+    Bloc,
+    /// Elects the highest weighted score each seat, then spends a Hare quota
+    /// of the ballots that scored the winner highest
+    AllocatedScore,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiStarElection {
+    pub options: Vec<String>,
+    pub seat_count: usize,
+    pub method: MultiStarMethod,
+    /// Seeds the RNG used when options are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+/// Same shape as `StarTally` with ballot weights applied
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StarSeatTally {
+    pub option_index: usize,
+    pub points_count: f64,
+}
+
+/// Same shape as `StarRunoffScore`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StarSeatRunoffScore {
+    pub option_index: usize,
+    pub vote_count: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StarSeatRound {
+    /// Options still standing, highest first
+    pub points_tally: Vec<StarSeatTally>,
+    /// The top two head to head, only for Bloc STAR
+    pub runoff: Option<Vec<StarSeatRunoffScore>>,
+    pub winner: usize,
+    /// Ballot weight used up electing the winner, only for Allocated Score
+    pub spent_weight: Option<f64>,
+    /// Weight each ballot carries into the next seat
+    pub ballot_weights: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiStarResult {
+    pub options: Vec<String>,
+    pub method: MultiStarMethod,
+    pub seat_count: usize,
+    /// Hare quota, only for Allocated Score
+    pub quota: Option<f64>,
+    pub rounds: Vec<StarSeatRound>,
+    /// In the order they were elected
+    pub winners: Vec<usize>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Tallies the standing options highest first, the options level at the
+/// cut for the `finalists` put in order by the tie break
+fn get_points_tally(
+    tie_breaker: &mut TieBreaker,
+    option_count: usize,
+    votes: &[StarVote],
+    weights: &[f64],
+    standing: &[usize],
+    finalists: usize,
+) -> Vec<StarSeatTally> {
+    let mut points_tally = standing
+        .iter()
+        .map(|option_index| StarSeatTally {
+            option_index: *option_index,
+            points_count: votes
+                .iter()
+                .zip(weights)
+                .map(|(vote, weight)| vote.votes[*option_index] as f64 * weight)
+                .sum(),
+        })
+        .collect::<Vec<StarSeatTally>>();
+    // Level options keep a random order
+    points_tally.shuffle(tie_breaker.rng());
+    points_tally.sort_by(|a, b| b.points_count.total_cmp(&a.points_count));
+
+    let mut round = vec![0.0; option_count];
+    for tally in &points_tally {
+        round[tally.option_index] = tally.points_count;
+    }
+    tie_breaker.record_round(round);
+
+    let mut places: Vec<Vec<usize>> = Vec::new();
+    for (i, tally) in points_tally.iter().enumerate() {
+        if i > 0 && points_tally[i - 1].points_count == tally.points_count {
+            places.last_mut().unwrap().push(tally.option_index);
+        } else {
+            places.push(vec![tally.option_index]);
+        }
+    }
+    let top = tie_breaker.take_top(&places, finalists, |_, place| place.to_vec());
+    let (mut ordered, rest): (Vec<StarSeatTally>, Vec<StarSeatTally>) = points_tally
+        .into_iter()
+        .partition(|i| top.contains(&i.option_index));
+    ordered.sort_by_key(|i| top.iter().position(|j| *j == i.option_index));
+    ordered.extend(rest);
+    ordered
+}
+
+/// Head to head between the first two in `points_tally`, a level runoff goes
+/// to the higher score
+fn runoff(votes: &[StarVote], points_tally: &[StarSeatTally]) -> Vec<StarSeatRunoffScore> {
+    let mut runoff = points_tally
+        .iter()
+        .take(2)
+        .map(|i| StarSeatRunoffScore {
+            option_index: i.option_index,
+            vote_count: 0.0,
+        })
+        .collect::<Vec<StarSeatRunoffScore>>();
+    if runoff.len() == 2 {
+        for (vote, weight) in weighted(votes) {
+            let a_votes = vote.votes[runoff[0].option_index];
+            let b_votes = vote.votes[runoff[1].option_index];
+            if a_votes > b_votes {
+                runoff[0].vote_count += weight as f64;
+            } else if b_votes > a_votes {
+                runoff[1].vote_count += weight as f64;
+            }
+        }
+    }
+    runoff.sort_by(|a, b| b.vote_count.total_cmp(&a.vote_count));
+    runoff
+}
+
+/// Spends a quota of weight from the ballots that gave `winner` the most
+/// stars, working down until a level only needs part of its weight
+fn allocate(votes: &[StarVote], weights: &mut [f64], winner: usize, quota: f64) -> f64 {
+    let mut spent = 0.0;
+    for stars in (1..=MAX_STARS).rev() {
+        let level_weight: f64 = votes
+            .iter()
+            .zip(weights.iter())
+            .filter(|(vote, _)| vote.votes[winner] == stars)
+            .map(|(_, weight)| weight)
+            .sum();
+        if level_weight == 0.0 {
+            continue;
+        }
+
+        let remaining_fraction = if spent + level_weight <= quota {
+            0.0
+        } else {
+            1.0 - (quota - spent) / level_weight
+        };
+        for (vote, weight) in votes.iter().zip(weights.iter_mut()) {
+            if vote.votes[winner] == stars {
+                *weight *= remaining_fraction;
+            }
+        }
+        spent += level_weight * (1.0 - remaining_fraction);
+        if remaining_fraction > 0.0 {
+            break;
+        }
+    }
+    spent
+}
+
+pub fn get_result(election: &MultiStarElection, votes: &[StarVote]) -> MultiStarResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let quota = match election.method {
+        MultiStarMethod::Bloc => None,
+        MultiStarMethod::AllocatedScore => {
+            Some(total_weight(votes) as f64 / election.seat_count.max(1) as f64)
+        }
+    };
+
+    let mut weights = weighted(votes)
+        .map(|(_, weight)| weight as f64)
+        .collect::<Vec<f64>>();
+    let mut winners: Vec<usize> = Vec::new();
+    let mut rounds = Vec::new();
+    while winners.len() < election.seat_count.min(election.options.len()) {
+        let standing = (0..election.options.len())
+            .filter(|i| !winners.contains(i))
+            .collect::<Vec<usize>>();
+        let finalists = match election.method {
+            MultiStarMethod::Bloc => 2,
+            MultiStarMethod::AllocatedScore => 1,
+        };
+        let points_tally = get_points_tally(
+            &mut tie_breaker,
+            election.options.len(),
+            votes,
+            &weights,
+            &standing,
+            finalists,
+        );
+
+        let (winner, runoff, spent_weight) = match election.method {
+            MultiStarMethod::Bloc => {
+                let runoff = runoff(votes, &points_tally);
+                let mut round = vec![0.0; election.options.len()];
+                for score in &runoff {
+                    round[score.option_index] = score.vote_count;
+                }
+                tie_breaker.record_round(round);
+                let level = runoff
+                    .iter()
+                    .filter(|i| i.vote_count == runoff[0].vote_count)
+                    .map(|i| i.option_index)
+                    .collect::<Vec<usize>>();
+                let winner = tie_breaker.break_tie(TieKind::Elect, &level, |_| level[0]);
+                (winner, Some(runoff), None)
+            }
+            MultiStarMethod::AllocatedScore => {
+                let winner = points_tally[0].option_index;
+                let spent = allocate(votes, &mut weights, winner, quota.unwrap());
+                (winner, None, Some(spent))
+            }
+        };
+
+        winners.push(winner);
+        rounds.push(StarSeatRound {
+            points_tally,
+            runoff,
+            winner,
+            spent_weight,
+            ballot_weights: weights.clone(),
+        });
+    }
+
+    MultiStarResult {
+        options: election.options.clone(),
+        method: election.method,
+        seat_count: election.seat_count,
+        quota,
+        rounds,
+        winners,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct MultiWinnerStar;
+
+impl VotingSystem for MultiWinnerStar {
+    type Ballot = StarVote;
+    type Config = MultiStarElection;
+    type Outcome = MultiStarResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut places = report
+            .winners
+            .iter()
+            .map(|i| vec![*i])
+            .collect::<Vec<Vec<usize>>>();
+        if let Some(round) = report.rounds.last() {
+            // Whoever was still standing at the last seat, by the points they had then
+            let mut last_points = None;
+            for tally in round
+                .points_tally
+                .iter()
+                .filter(|i| i.option_index != round.winner)
+            {
+                if last_points == Some(tally.points_count) {
+                    places.last_mut().unwrap().push(tally.option_index);
+                } else {
+                    places.push(vec![tally.option_index]);
+                }
+                last_points = Some(tally.points_count);
+            }
+        }
+
+        Outcome::from_places(report.winners.clone(), places)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn votes(groups: &[(usize, Vec<usize>)]) -> Vec<StarVote> {
+        let mut votes = vec![];
+        for (count, stars) in groups {
+            for _ in 0..*count {
+                votes.push(StarVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: stars.clone(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(method: MultiStarMethod, seat_count: usize) -> MultiStarElection {
+        MultiStarElection {
+            options: ["A", "B", "C", "D"].iter().map(|i| i.to_string()).collect(),
+            seat_count,
+            method,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_bloc_star() {
+        // The majority takes both seats
+        let votes = votes(&[(6, vec![5, 4, 0, 0]), (4, vec![0, 0, 5, 4])]);
+
+        let result = get_result(&election(MultiStarMethod::Bloc, 2), &votes);
+        assert_eq!(result.winners, vec![0, 1]);
+        let runoff = result.rounds[1].runoff.as_ref().unwrap();
+        assert_eq!(runoff[0].option_index, 1);
+        assert_eq!(runoff[0].vote_count, 6.0);
+        assert_eq!(result.rounds[1].points_tally.len(), 3);
+    }
+
+    #[test]
+    fn test_get_result_allocated_score() {
+        // The majority spends its quota of 5 on A, 1 of them is left over at
+        // full weight and can't outweigh the minority's C
+        let votes = votes(&[(6, vec![5, 4, 0, 0]), (4, vec![0, 0, 5, 4])]);
+
+        let result = get_result(&election(MultiStarMethod::AllocatedScore, 2), &votes);
+        assert_eq!(result.quota, Some(5.0));
+        assert_eq!(result.winners, vec![0, 2]);
+        assert_eq!(result.rounds[0].spent_weight, Some(5.0));
+        let majority_weight: f64 = result.rounds[0].ballot_weights[..6].iter().sum();
+        assert!((majority_weight - 1.0).abs() < 1e-9);
+        assert_eq!(result.rounds[1].points_tally[0].points_count, 20.0);
+        assert!(result.rounds[1].runoff.is_none());
+    }
+}
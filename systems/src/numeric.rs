@@ -0,0 +1,414 @@
+//! Numbers for counts whose rules specify fractional transfer values. Every
+//! value is held as an exact fraction of big integers, an `Arithmetic` decides
+//! whether quotients keep their exact value or are rounded to a fixed number
+//! of decimal places, and rounding reports what it lost so count sheets can
+//! show it.
+
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Sub},
+    str::FromStr,
+};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use serde::{de, Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
+
+/// An exact fraction, written as `numerator/denominator` in lowest terms or
+/// as a whole number
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Number(BigRational);
+
+impl Number {
+    pub fn new(numerator: i64, denominator: i64) -> Number {
+        Number(BigRational::new(numerator.into(), denominator.into()))
+    }
+
+    pub fn zero() -> Number {
+        Number(BigRational::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.0.is_positive()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+impl Default for Number {
+    fn default() -> Self {
+        Number::zero()
+    }
+}
+
+impl From<i64> for Number {
+    fn from(value: i64) -> Self {
+        Number(BigRational::from_integer(value.into()))
+    }
+}
+
+impl From<BigInt> for Number {
+    fn from(value: BigInt) -> Self {
+        Number(BigRational::from_integer(value))
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNumberError;
+
+impl fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Number must be a whole number or a fraction like 5/2")
+    }
+}
+
+impl std::error::Error for ParseNumberError {}
+
+impl FromStr for Number {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = match s.split_once('/') {
+            Some((numerator, denominator)) => (numerator, denominator),
+            None => (s, "1"),
+        };
+        let numerator: BigInt = numerator.trim().parse().map_err(|_| ParseNumberError)?;
+        let denominator: BigInt = denominator.trim().parse().map_err(|_| ParseNumberError)?;
+        if denominator.is_zero() {
+            return Err(ParseNumberError);
+        }
+        Ok(Number(BigRational::new(numerator, denominator)))
+    }
+}
+
+impl serde::Serialize for Number {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Whole(i64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Whole(value) => Ok(Number::from(value)),
+            Raw::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+macro_rules! impl_number_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Number {
+            type Output = Number;
+
+            fn $method(self, other: Number) -> Number {
+                Number(self.0.$method(other.0))
+            }
+        }
+
+        impl<'a> $trait<&'a Number> for &'a Number {
+            type Output = Number;
+
+            fn $method(self, other: &Number) -> Number {
+                Number((&self.0).$method(&other.0))
+            }
+        }
+    };
+}
+
+impl_number_op!(Add, add);
+impl_number_op!(Sub, sub);
+impl_number_op!(Mul, mul);
+impl_number_op!(Div, div);
+
+impl AddAssign for Number {
+    fn add_assign(&mut self, other: Number) {
+        self.0 += other.0;
+    }
+}
+
+impl Sum for Number {
+    fn sum<I: Iterator<Item = Number>>(iter: I) -> Self {
+        iter.fold(Number::zero(), |a, b| a + b)
+    }
+}
+
+/// Which way a value between two representable values goes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Towards negative infinity, dropping the remainder
+    Down,
+    /// Towards positive infinity
+    Up,
+    /// To the nearest, halves go up
+    HalfUp,
+    /// To the nearest, halves go to the even neighbour
+    HalfEven,
+}
+
+/// Most decimal places a count will be asked to keep
+pub const MAX_PLACES: u32 = 12;
+
+/// How quotients are kept
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(
+    tag = "mode",
+    rename_all = "snake_case",
+    try_from = "UncheckedArithmetic"
+)]
+pub enum Arithmetic {
+    /// Exact fractions, nothing is lost
+    #[default]
+    Exact,
+    /// Rounded to `places` decimal places
+    FixedPoint { places: u32, rounding: Rounding },
+}
+
+/// `Arithmetic` as it is written, before the places are checked
+#[derive(Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum UncheckedArithmetic {
+    Exact,
+    FixedPoint { places: u32, rounding: Rounding },
+}
+
+impl TryFrom<UncheckedArithmetic> for Arithmetic {
+    type Error = ParseArithmeticError;
+
+    fn try_from(value: UncheckedArithmetic) -> Result<Self, Self::Error> {
+        match value {
+            UncheckedArithmetic::Exact => Ok(Arithmetic::Exact),
+            UncheckedArithmetic::FixedPoint { places, .. } if places > MAX_PLACES => {
+                Err(ParseArithmeticError)
+            }
+            UncheckedArithmetic::FixedPoint { places, rounding } => {
+                Ok(Arithmetic::FixedPoint { places, rounding })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rounding::Down => write!(f, "down"),
+            Rounding::Up => write!(f, "up"),
+            Rounding::HalfUp => write!(f, "half_up"),
+            Rounding::HalfEven => write!(f, "half_even"),
+        }
+    }
+}
+
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arithmetic::Exact => write!(f, "exact"),
+            Arithmetic::FixedPoint { places, rounding } => {
+                write!(f, "fixed_point:{}:{}", places, rounding)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseArithmeticError;
+
+impl fmt::Display for ParseArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Arithmetic must be exact or fixed_point:places:rounding, places being at most {} and rounding being down, up, half_up or half_even",
+            MAX_PLACES
+        )
+    }
+}
+
+impl std::error::Error for ParseArithmeticError {}
+
+/// Written the way `Display` puts it, so it can be given in a query string
+impl FromStr for Arithmetic {
+    type Err = ParseArithmeticError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "exact" {
+            return Ok(Arithmetic::Exact);
+        }
+        let fixed_point = s
+            .trim()
+            .strip_prefix("fixed_point:")
+            .ok_or(ParseArithmeticError)?;
+        let (places, rounding) = fixed_point.split_once(':').ok_or(ParseArithmeticError)?;
+        UncheckedArithmetic::FixedPoint {
+            places: places.trim().parse().map_err(|_| ParseArithmeticError)?,
+            rounding: match rounding.trim() {
+                "down" => Rounding::Down,
+                "up" => Rounding::Up,
+                "half_up" => Rounding::HalfUp,
+                "half_even" => Rounding::HalfEven,
+                _ => return Err(ParseArithmeticError),
+            },
+        }
+        .try_into()
+    }
+}
+
+/// A rounded quotient and the exact value minus the rounded one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Rounded {
+    pub value: Number,
+    pub loss: Number,
+}
+
+impl Arithmetic {
+    pub fn round(&self, value: &Number) -> Number {
+        let (places, rounding) = match self {
+            Arithmetic::Exact => return value.clone(),
+            Arithmetic::FixedPoint { places, rounding } => (*places, *rounding),
+        };
+
+        let scale = BigRational::from_integer(num_traits::pow(BigInt::from(10), places as usize));
+        let scaled = &value.0 * &scale;
+        let floor = scaled.floor();
+        let fraction = &scaled - &floor;
+        let half = BigRational::new(BigInt::one(), BigInt::from(2));
+        let rounded = match rounding {
+            Rounding::Down => floor,
+            Rounding::Up => scaled.ceil(),
+            Rounding::HalfUp if fraction >= half => floor + BigRational::one(),
+            Rounding::HalfUp => floor,
+            Rounding::HalfEven if fraction > half => floor + BigRational::one(),
+            Rounding::HalfEven if fraction < half => floor,
+            Rounding::HalfEven if floor.to_integer().is_even() => floor,
+            Rounding::HalfEven => floor + BigRational::one(),
+        };
+
+        Number(rounded / scale)
+    }
+
+    /// `numerator / denominator` at this arithmetic's precision
+    pub fn divide(&self, numerator: &Number, denominator: &Number) -> Rounded {
+        let exact = numerator / denominator;
+        let value = self.round(&exact);
+        Rounded {
+            loss: &exact - &value,
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_strings() {
+        assert_eq!(Number::from_str("6/4"), Ok(Number::new(3, 2)));
+        assert_eq!(Number::new(150000, 7).to_string(), "150000/7");
+        assert_eq!(Number::new(21429, 1).to_string(), "21429");
+        assert_eq!(Number::from_str("1/0"), Err(ParseNumberError));
+    }
+
+    #[test]
+    fn test_arithmetic_strings() {
+        let fixed = Arithmetic::FixedPoint {
+            places: 6,
+            rounding: Rounding::HalfEven,
+        };
+        assert_eq!(fixed.to_string(), "fixed_point:6:half_even");
+        assert_eq!(Arithmetic::from_str("fixed_point:6:half_even"), Ok(fixed));
+        assert_eq!(Arithmetic::from_str("exact"), Ok(Arithmetic::Exact));
+        assert_eq!(
+            Arithmetic::from_str("fixed_point:6"),
+            Err(ParseArithmeticError)
+        );
+        assert_eq!(
+            Arithmetic::from_str("fixed_point:-1:down"),
+            Err(ParseArithmeticError)
+        );
+        assert_eq!(
+            Arithmetic::from_str("fixed_point:12:down").map(|a| a.to_string()),
+            Ok("fixed_point:12:down".to_string())
+        );
+        assert_eq!(
+            Arithmetic::from_str("fixed_point:13:down"),
+            Err(ParseArithmeticError)
+        );
+        assert_eq!(
+            Arithmetic::try_from(UncheckedArithmetic::FixedPoint {
+                places: MAX_PLACES + 1,
+                rounding: Rounding::Down,
+            }),
+            Err(ParseArithmeticError)
+        );
+    }
+
+    #[test]
+    fn test_rounding() {
+        let fixed = |places, rounding| Arithmetic::FixedPoint { places, rounding };
+        let value = Number::new(150000, 7);
+
+        assert_eq!(Arithmetic::Exact.round(&value), value);
+        assert_eq!(fixed(0, Rounding::Down).round(&value), Number::from(21428));
+        assert_eq!(fixed(0, Rounding::Up).round(&value), Number::from(21429));
+        assert_eq!(
+            fixed(0, Rounding::HalfUp).round(&value),
+            Number::from(21429)
+        );
+        assert_eq!(
+            fixed(2, Rounding::Down).round(&value),
+            Number::new(2142857, 100)
+        );
+
+        assert_eq!(
+            fixed(0, Rounding::HalfEven).round(&Number::new(5, 2)),
+            Number::from(2)
+        );
+        assert_eq!(
+            fixed(0, Rounding::HalfEven).round(&Number::new(7, 2)),
+            Number::from(4)
+        );
+        assert_eq!(
+            fixed(0, Rounding::HalfUp).round(&Number::new(5, 2)),
+            Number::from(3)
+        );
+    }
+
+    #[test]
+    fn test_divide_loss() {
+        let rounded = Arithmetic::FixedPoint {
+            places: 0,
+            rounding: Rounding::Down,
+        }
+        .divide(&Number::from(1000), &Number::from(3));
+        assert_eq!(rounded.value, Number::from(333));
+        assert_eq!(rounded.loss, Number::new(1, 3));
+
+        let exact = Arithmetic::Exact.divide(&Number::from(1000), &Number::from(3));
+        assert_eq!(exact.value, Number::new(1000, 3));
+        assert!(exact.loss.is_zero());
+    }
+}
@@ -0,0 +1,424 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, total_weight, weighted, Weight},
+};
+
+/// Budget shares are compared with this much slack so rounding in the
+/// payments doesn't make an option unaffordable
+const EPSILON: f64 = 1e-9;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetingMethod {
+    /// Funds options by most total score, skipping any that no longer fit
+    Greedy,
+    /// Method of Equal Shares, each voter gets an equal share of the budget
+    /// and options are funded by their supporters at the lowest price per
+    /// point of score. Whatever is left is spent greedily
+    EqualShares,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BudgetingElection {
+    pub options: Vec<String>,
+    /// Cost of each option
+    pub costs: Vec<u64>,
+    pub budget: u64,
+    /// 1 for approval ballots
+    pub max_score: usize,
+    pub method: BudgetingMethod,
+    /// Seeds the RNG used when options are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BudgetingVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Score between 0 and `max_score` given to each option
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(BudgetingVote);
+
+/// Voters who gave exactly the same scores
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BudgetingVoterGroup {
+    pub scores: Vec<usize>,
+    pub count: usize,
+    /// Each voter's equal share of the budget
+    pub share: f64,
+    /// Spent by each voter by the end of the count
+    pub spent: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BudgetingStep {
+    pub option_index: usize,
+    pub cost: u64,
+    /// Price per point of score paid by the supporters under equal shares,
+    /// None when funded greedily
+    pub price: Option<f64>,
+    /// Paid by each voter in each group, greedy picks split the cost by score
+    pub payments: Vec<f64>,
+    /// Funded while spending what equal shares left over
+    pub completion: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BudgetingResult {
+    pub options: Vec<String>,
+    pub costs: Vec<u64>,
+    pub budget: u64,
+    pub method: BudgetingMethod,
+    pub scores: Vec<usize>,
+    /// Largest group first
+    pub groups: Vec<BudgetingVoterGroup>,
+    pub steps: Vec<BudgetingStep>,
+    /// In the order they were funded
+    pub funded: Vec<usize>,
+    pub spent: u64,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn get_voter_groups(votes: &[BudgetingVote], share: f64) -> Vec<BudgetingVoterGroup> {
+    let mut map: HashMap<&[usize], usize> = HashMap::new();
+    for (vote, weight) in weighted(votes) {
+        *map.entry(&vote.votes).or_insert(0) += weight;
+    }
+
+    let mut groups = map
+        .into_iter()
+        .map(|(scores, count)| BudgetingVoterGroup {
+            scores: scores.to_vec(),
+            count,
+            share,
+            spent: 0.0,
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then(a.scores.cmp(&b.scores)));
+    groups
+}
+
+/// Lowest price per point of score at which the supporters of `option_index`
+/// can cover `cost` out of what they have left, None if they can't afford it
+fn get_price(
+    groups: &[BudgetingVoterGroup],
+    remaining: &[f64],
+    option_index: usize,
+    cost: f64,
+) -> Option<f64> {
+    let mut supporters = groups
+        .iter()
+        .zip(remaining)
+        .filter(|(group, _)| group.scores[option_index] > 0)
+        .map(|(group, remaining)| {
+            (
+                group.count as f64,
+                group.scores[option_index] as f64,
+                *remaining,
+            )
+        })
+        .collect::<Vec<(f64, f64, f64)>>();
+    let available: f64 = supporters.iter().map(|(count, _, left)| count * left).sum();
+    if supporters.is_empty() || available < cost - EPSILON {
+        return None;
+    }
+
+    // Voters with the least left per point run out first and pay all they have
+    supporters.sort_by(|a, b| (a.2 / a.1).total_cmp(&(b.2 / b.1)));
+    let mut cost_left = cost;
+    let mut score_left: f64 = supporters
+        .iter()
+        .map(|(count, score, _)| count * score)
+        .sum();
+    for (count, score, left) in &supporters {
+        let price = cost_left / score_left;
+        if left / score >= price {
+            return Some(price);
+        }
+        cost_left -= count * left;
+        score_left -= count * score;
+    }
+    // Only within the slack, everyone pays all they have left
+    supporters.last().map(|(_, score, left)| left / score)
+}
+
+/// Options with some support from most to least total score, level options
+/// put in order by the tie break
+fn greedy_order(tie_breaker: &mut TieBreaker, scores: &[usize]) -> Vec<usize> {
+    let mut order = (0..scores.len())
+        .filter(|i| scores[*i] > 0)
+        .collect::<Vec<usize>>();
+    order.shuffle(tie_breaker.rng());
+    order.sort_by_key(|i| Reverse(scores[*i]));
+
+    let mut places: Vec<Vec<usize>> = Vec::new();
+    for (i, option_index) in order.iter().enumerate() {
+        if i > 0 && scores[order[i - 1]] == scores[*option_index] {
+            places.last_mut().unwrap().push(*option_index);
+        } else {
+            places.push(vec![*option_index]);
+        }
+    }
+    places
+        .into_iter()
+        .flat_map(|place| tie_breaker.order(TieKind::Elect, &place, |_| place.clone()))
+        .collect()
+}
+
+fn greedy_step(
+    groups: &[BudgetingVoterGroup],
+    scores: &[usize],
+    option_index: usize,
+    cost: u64,
+    completion: bool,
+) -> BudgetingStep {
+    BudgetingStep {
+        option_index,
+        cost,
+        price: None,
+        payments: groups
+            .iter()
+            .map(|group| {
+                cost as f64 * group.scores[option_index] as f64 / scores[option_index] as f64
+            })
+            .collect(),
+        completion,
+    }
+}
+
+pub fn get_result(election: &BudgetingElection, votes: &[BudgetingVote]) -> BudgetingResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let option_count = election.options.len();
+    let mut scores = vec![0; option_count];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, score) in vote.votes.iter().enumerate() {
+            scores[option_index] += score * weight;
+        }
+    }
+    tie_breaker.record_round(scores.iter().map(|i| *i as f64));
+
+    let share = if votes.is_empty() {
+        0.0
+    } else {
+        election.budget as f64 / total_weight(votes) as f64
+    };
+    let mut groups = get_voter_groups(votes, share);
+    let mut steps = Vec::new();
+    let mut funded: Vec<usize> = Vec::new();
+
+    if election.method == BudgetingMethod::EqualShares {
+        let mut remaining = vec![share; groups.len()];
+        loop {
+            let prices = (0..option_count)
+                .filter(|i| !funded.contains(i))
+                .filter_map(|i| {
+                    get_price(&groups, &remaining, i, election.costs[i] as f64).map(|p| (i, p))
+                })
+                .collect::<Vec<(usize, f64)>>();
+            let lowest = match prices.iter().map(|(_, p)| *p).min_by(f64::total_cmp) {
+                Some(lowest) => lowest,
+                None => break,
+            };
+            let cheapest = prices
+                .iter()
+                .filter(|(_, p)| *p <= lowest + EPSILON)
+                .map(|(i, _)| *i)
+                .collect::<Vec<usize>>();
+            let most_score = cheapest.iter().map(|i| scores[*i]).max().unwrap();
+            let cheapest = cheapest
+                .into_iter()
+                .filter(|i| scores[*i] == most_score)
+                .collect::<Vec<usize>>();
+            let option_index = tie_breaker.break_tie(TieKind::Elect, &cheapest, |rng| {
+                *cheapest.choose(rng).unwrap()
+            });
+            let price = prices.iter().find(|(i, _)| *i == option_index).unwrap().1;
+
+            let payments = groups
+                .iter()
+                .zip(&remaining)
+                .map(|(group, left)| left.min(price * group.scores[option_index] as f64))
+                .collect::<Vec<f64>>();
+            for (left, payment) in remaining.iter_mut().zip(&payments) {
+                *left -= payment;
+            }
+            funded.push(option_index);
+            steps.push(BudgetingStep {
+                option_index,
+                cost: election.costs[option_index],
+                price: Some(price),
+                payments,
+                completion: false,
+            });
+        }
+    }
+
+    let mut spent: u64 = funded.iter().map(|i| election.costs[*i]).sum();
+    let completion = election.method == BudgetingMethod::EqualShares;
+    for option_index in greedy_order(&mut tie_breaker, &scores) {
+        let cost = election.costs[option_index];
+        if funded.contains(&option_index) || spent + cost > election.budget {
+            continue;
+        }
+        spent += cost;
+        funded.push(option_index);
+        steps.push(greedy_step(
+            &groups,
+            &scores,
+            option_index,
+            cost,
+            completion,
+        ));
+    }
+
+    for step in &steps {
+        for (group, payment) in groups.iter_mut().zip(&step.payments) {
+            group.spent += payment;
+        }
+    }
+
+    BudgetingResult {
+        options: election.options.clone(),
+        costs: election.costs.clone(),
+        budget: election.budget,
+        method: election.method,
+        scores,
+        groups,
+        steps,
+        funded,
+        spent,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct ParticipatoryBudgeting;
+
+impl VotingSystem for ParticipatoryBudgeting {
+    type Ballot = BudgetingVote;
+    type Config = BudgetingElection;
+    type Outcome = BudgetingResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let places = rank_by_score(
+            report
+                .scores
+                .iter()
+                .enumerate()
+                .map(|(i, score)| (i, (report.funded.contains(&i), *score))),
+        );
+        Outcome::new(report.funded.clone(), places, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn votes(groups: &[(usize, Vec<usize>)]) -> Vec<BudgetingVote> {
+        let mut votes = vec![];
+        for (count, scores) in groups {
+            for _ in 0..*count {
+                votes.push(BudgetingVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: scores.clone(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(method: BudgetingMethod, costs: Vec<u64>, budget: u64) -> BudgetingElection {
+        BudgetingElection {
+            options: ["A", "B", "C", "D"][..costs.len()]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            costs,
+            budget,
+            max_score: 1,
+            method,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_greedy() {
+        let votes = votes(&[(6, vec![1, 1, 0, 0]), (4, vec![0, 0, 1, 1])]);
+
+        // C no longer fits once A and B are funded, D still does
+        let result = get_result(
+            &election(BudgetingMethod::Greedy, vec![50, 40, 30, 10], 100),
+            &votes,
+        );
+        assert_eq!(result.funded[2..], [3]);
+        assert_eq!(result.spent, 100);
+        assert!((result.groups[0].spent - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_result_equal_shares() {
+        let votes = votes(&[(6, vec![1, 1, 0, 0]), (4, vec![0, 0, 1, 1])]);
+
+        // The minority's 40 pays for D and then C, the majority's 60 pays
+        // for B but can't then afford A, nor can greedy completion
+        let result = get_result(
+            &election(BudgetingMethod::EqualShares, vec![50, 40, 30, 10], 100),
+            &votes,
+        );
+        assert_eq!(result.groups[0].share, 10.0);
+        assert_eq!(result.funded, vec![3, 1, 2]);
+        assert_eq!(result.steps[0].price, Some(2.5));
+        assert_eq!(result.steps[2].price, Some(7.5));
+        assert!((result.groups[0].spent - 40.0 / 6.0).abs() < 1e-9);
+        assert!((result.groups[1].spent - 10.0).abs() < 1e-9);
+        assert!(result.steps.iter().all(|i| !i.completion));
+        assert_eq!(result.spent, 80);
+    }
+
+    #[test]
+    fn test_get_result_equal_shares_completion() {
+        let votes = votes(&[(3, vec![2, 0, 1]), (1, vec![0, 2, 1])]);
+
+        // B's supporter alone only has 10 of its 20
+        let result = get_result(
+            &election(BudgetingMethod::EqualShares, vec![30, 20, 20], 40),
+            &votes,
+        );
+        assert_eq!(result.funded, vec![0]);
+        assert_eq!(result.spent, 30);
+
+        let result = get_result(
+            &election(BudgetingMethod::EqualShares, vec![20, 20, 20], 60),
+            &votes,
+        );
+        // B's supporter is left with 10 after paying towards C, the
+        // remaining 20 of the budget then funds it greedily
+        assert_eq!(result.funded, vec![0, 2, 1]);
+        assert!(result.steps[2].completion);
+        assert_eq!(result.spent, 60);
+    }
+}
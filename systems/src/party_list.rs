@@ -0,0 +1,426 @@
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumString};
+
+use crate::{
+    single_non_transferable_vote::SNTVVote,
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::weighted,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, AsRefStr, EnumString)]
+pub enum PartyListMethod {
+    /// Divisors 1, 2, 3, ...
+    DHondt,
+    /// Divisors 1, 3, 5, ...
+    SainteLague,
+    /// Divisors 1.4, 3, 5, ...
+    ModifiedSainteLague,
+    /// Whole Hare quotas first, then the largest remainders
+    HareLargestRemainder,
+    /// Whole Droop quotas first, then the largest remainders
+    DroopLargestRemainder,
+}
+
+impl PartyListMethod {
+    /// Divisor for a party that has already won `seats_won` seats, none for
+    /// the largest remainder methods
+    pub fn divisor(&self, seats_won: usize) -> Option<f64> {
+        match self {
+            PartyListMethod::DHondt => Some((seats_won + 1) as f64),
+            PartyListMethod::SainteLague => Some((2 * seats_won + 1) as f64),
+            PartyListMethod::ModifiedSainteLague => {
+                if seats_won == 0 {
+                    Some(1.4)
+                } else {
+                    Some((2 * seats_won + 1) as f64)
+                }
+            }
+            PartyListMethod::HareLargestRemainder | PartyListMethod::DroopLargestRemainder => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartyListElection {
+    /// The parties or slates
+    pub options: Vec<String>,
+    pub seat_count: usize,
+    pub method: PartyListMethod,
+    /// Percentage of the vote a party needs to win any seats
+    pub threshold: Option<f64>,
+    /// Seeds the RNG used when parties are level for the last seat
+    pub seed: String,
+    /// Under `TieBreak::Method` the party with the most votes takes a level
+    /// seat, then by lot
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PartyListTally {
+    pub option_index: usize,
+    pub vote_count: usize,
+    pub percent: f64,
+    /// Whether the party met the threshold
+    pub eligible: bool,
+    pub seats: usize,
+    /// Votes left over after whole quotas, only for largest remainder
+    pub remainder: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PartyListSeat {
+    pub option_index: usize,
+    /// Divisor of the winning quotient, none for largest remainder
+    pub divisor: Option<f64>,
+    /// The winning quotient, for largest remainder the whole quotas or the
+    /// remainder that took the seat
+    pub quotient: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartyListResult {
+    pub options: Vec<String>,
+    pub method: PartyListMethod,
+    pub seat_count: usize,
+    pub threshold: Option<f64>,
+    /// Only for largest remainder
+    pub quota: Option<f64>,
+    /// Most seats first
+    pub tallies: Vec<PartyListTally>,
+    /// `quotients[option_index][n]` is the party's votes over its divisor
+    /// after `n` seats, empty for largest remainder and parties under the
+    /// threshold
+    pub quotients: Vec<Vec<PartyListQuotient>>,
+    /// Seats in the order they were won
+    pub seats: Vec<PartyListSeat>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PartyListQuotient {
+    pub divisor: f64,
+    pub quotient: f64,
+    /// Seat number the quotient won, counting from 0
+    pub seat: Option<usize>,
+}
+
+/// Picks the party with the highest value, level parties going to the tie
+/// break
+fn pick_highest(
+    tie_breaker: &mut TieBreaker,
+    candidates: &[usize],
+    value: impl Fn(usize) -> f64,
+    vote_counts: &[usize],
+) -> usize {
+    let mut round = vec![0.0; vote_counts.len()];
+    for option_index in candidates {
+        round[*option_index] = value(*option_index);
+    }
+    tie_breaker.record_round(round);
+
+    let highest_value = candidates
+        .iter()
+        .map(|i| value(*i))
+        .fold(f64::MIN, f64::max);
+    let highest = candidates
+        .iter()
+        .copied()
+        .filter(|i| value(*i) == highest_value)
+        .collect::<Vec<usize>>();
+    tie_breaker.break_tie(TieKind::Elect, &highest, |rng| {
+        let most_votes = highest.iter().map(|i| vote_counts[*i]).max().unwrap();
+        let most = highest
+            .iter()
+            .copied()
+            .filter(|i| vote_counts[*i] == most_votes)
+            .collect::<Vec<usize>>();
+        *most.choose(rng).unwrap()
+    })
+}
+
+pub fn get_result(election: &PartyListElection, votes: &[SNTVVote]) -> PartyListResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let option_count = election.options.len();
+    let mut vote_counts = vec![0; option_count];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, v) in vote.votes.iter().enumerate() {
+            if *v {
+                vote_counts[option_index] += weight;
+            }
+        }
+    }
+    let total_votes: usize = vote_counts.iter().sum();
+    let percent = |i: usize| {
+        if total_votes == 0 {
+            0.0
+        } else {
+            vote_counts[i] as f64 * 100.0 / total_votes as f64
+        }
+    };
+    let eligible = (0..option_count)
+        .filter(|i| {
+            vote_counts[*i] > 0
+                && match election.threshold {
+                    Some(threshold) => percent(*i) >= threshold,
+                    None => true,
+                }
+        })
+        .collect::<Vec<usize>>();
+
+    let mut seats_won = vec![0; option_count];
+    let mut seats = Vec::new();
+    let mut remainders = vec![None; option_count];
+    let mut quota = None;
+    let mut quotients = vec![Vec::new(); option_count];
+
+    if !eligible.is_empty() {
+        match election.method {
+            PartyListMethod::HareLargestRemainder | PartyListMethod::DroopLargestRemainder => {
+                let eligible_votes: usize = eligible.iter().map(|i| vote_counts[*i]).sum();
+                let eligible_votes = eligible_votes as f64;
+                let seat_count = election.seat_count as f64;
+                let q = if election.method == PartyListMethod::HareLargestRemainder {
+                    eligible_votes / seat_count
+                } else {
+                    (eligible_votes / (seat_count + 1.0)).floor() + 1.0
+                };
+                quota = Some(q);
+
+                for option_index in &eligible {
+                    let quotas = vote_counts[*option_index] as f64 / q;
+                    let whole = (quotas.floor() as usize).min(election.seat_count - seats.len());
+                    seats_won[*option_index] = whole;
+                    remainders[*option_index] =
+                        Some(vote_counts[*option_index] as f64 - whole as f64 * q);
+                    seats.extend((0..whole).map(|_| PartyListSeat {
+                        option_index: *option_index,
+                        divisor: None,
+                        quotient: quotas,
+                    }));
+                }
+
+                let mut remaining = eligible.clone();
+                while seats.len() < election.seat_count {
+                    if remaining.is_empty() {
+                        remaining = eligible.clone();
+                    }
+                    let option_index = pick_highest(
+                        &mut tie_breaker,
+                        &remaining,
+                        |i| remainders[i].unwrap(),
+                        &vote_counts,
+                    );
+                    remaining.retain(|i| *i != option_index);
+                    seats_won[option_index] += 1;
+                    seats.push(PartyListSeat {
+                        option_index,
+                        divisor: None,
+                        quotient: remainders[option_index].unwrap(),
+                    });
+                }
+            }
+            _ => {
+                for option_index in &eligible {
+                    quotients[*option_index] = (0..election.seat_count)
+                        .map(|n| {
+                            let divisor = election.method.divisor(n).unwrap();
+                            PartyListQuotient {
+                                divisor,
+                                quotient: vote_counts[*option_index] as f64 / divisor,
+                                seat: None,
+                            }
+                        })
+                        .collect();
+                }
+
+                for seat in 0..election.seat_count {
+                    let option_index = pick_highest(
+                        &mut tie_breaker,
+                        &eligible,
+                        |i| quotients[i][seats_won[i]].quotient,
+                        &vote_counts,
+                    );
+                    let quotient = &mut quotients[option_index][seats_won[option_index]];
+                    quotient.seat = Some(seat);
+                    seats.push(PartyListSeat {
+                        option_index,
+                        divisor: Some(quotient.divisor),
+                        quotient: quotient.quotient,
+                    });
+                    seats_won[option_index] += 1;
+                }
+            }
+        }
+    }
+
+    let mut tallies = (0..option_count)
+        .map(|i| PartyListTally {
+            option_index: i,
+            vote_count: vote_counts[i],
+            percent: percent(i),
+            eligible: eligible.contains(&i),
+            seats: seats_won[i],
+            remainder: remainders[i],
+        })
+        .collect::<Vec<PartyListTally>>();
+    tallies.sort_by(|a, b| b.seats.cmp(&a.seats).then(b.vote_count.cmp(&a.vote_count)));
+
+    PartyListResult {
+        options: election.options.clone(),
+        method: election.method,
+        seat_count: election.seat_count,
+        threshold: election.threshold,
+        quota,
+        tallies,
+        quotients,
+        seats,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct PartyList;
+
+impl VotingSystem for PartyList {
+    type Ballot = SNTVVote;
+    type Config = PartyListElection;
+    type Outcome = PartyListResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        // Parties in the order they won their first seat
+        let mut elected: Vec<usize> = Vec::new();
+        for seat in &report.seats {
+            if !elected.contains(&seat.option_index) {
+                elected.push(seat.option_index);
+            }
+        }
+
+        Outcome::from_places(
+            elected,
+            rank_by_score(
+                report
+                    .tallies
+                    .iter()
+                    .map(|i| (i.option_index, (i.seats, i.vote_count))),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn votes(vote_counts: &[usize]) -> Vec<SNTVVote> {
+        let mut votes = vec![];
+        for (option_index, count) in vote_counts.iter().enumerate() {
+            for _ in 0..*count {
+                let mut vote = vec![false; vote_counts.len()];
+                vote[option_index] = true;
+                votes.push(SNTVVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: vote,
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(
+        option_count: usize,
+        method: PartyListMethod,
+        seat_count: usize,
+    ) -> PartyListElection {
+        PartyListElection {
+            options: ["A", "B", "C", "D", "E", "F"][..option_count]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            seat_count,
+            method,
+            threshold: None,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    fn seats(result: &PartyListResult) -> Vec<usize> {
+        let mut seats = vec![0; result.options.len()];
+        for tally in &result.tallies {
+            seats[tally.option_index] = tally.seats;
+        }
+        seats
+    }
+
+    #[test]
+    fn test_get_result_divisor_methods() {
+        let votes = votes(&[100_000, 80_000, 30_000, 20_000]);
+
+        let result = get_result(&election(4, PartyListMethod::DHondt, 8), &votes);
+        assert_eq!(seats(&result), vec![4, 3, 1, 0]);
+        // The last seat goes to A's fourth quotient ahead of B and D on 20,000
+        assert_eq!(result.seats[7].option_index, 0);
+        assert_eq!(result.quotients[0][3].seat, Some(7));
+        assert_eq!(result.quotients[0][3].quotient, 25_000.0);
+
+        let result = get_result(&election(4, PartyListMethod::SainteLague, 7), &votes);
+        assert_eq!(seats(&result), vec![3, 2, 1, 1]);
+
+        // The larger first divisor keeps D out
+        let result = get_result(
+            &election(4, PartyListMethod::ModifiedSainteLague, 7),
+            &votes,
+        );
+        assert_eq!(result.quotients[3][0].divisor, 1.4);
+        assert_eq!(seats(&result), vec![3, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_get_result_largest_remainder() {
+        let votes = votes(&[47_000, 16_000, 15_800, 12_000, 6_100, 3_100]);
+
+        let result = get_result(
+            &election(6, PartyListMethod::HareLargestRemainder, 10),
+            &votes,
+        );
+        assert_eq!(result.quota, Some(10_000.0));
+        assert_eq!(seats(&result), vec![5, 2, 1, 1, 1, 0]);
+
+        let result = get_result(
+            &election(6, PartyListMethod::DroopLargestRemainder, 10),
+            &votes,
+        );
+        assert_eq!(result.quota, Some(9_091.0));
+        assert_eq!(seats(&result), vec![5, 2, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_get_result_threshold() {
+        let votes = votes(&[60, 30, 6, 4]);
+        let mut election = election(4, PartyListMethod::DHondt, 10);
+        election.threshold = Some(5.0);
+
+        let result = get_result(&election, &votes);
+        assert_eq!(seats(&result), vec![7, 3, 0, 0]);
+        let tally = |i: usize| result.tallies.iter().find(|t| t.option_index == i).unwrap();
+        assert!(tally(2).eligible);
+        assert!(!tally(3).eligible);
+        assert!(result.quotients[3].is_empty());
+    }
+}
@@ -0,0 +1,623 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use log::info;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, rank_by_removal, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, total_weight, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreferentialElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie survives every preference level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreferentialVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The preference given to each option, 0 being the most preferred
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(PreferentialVote);
+
+impl RankedChoiceVote<usize> for PreferentialVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct CandidateVoteRoundResult {
+    pub name: String,
+    pub votes: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct LogEntry {
+    pub eliminated: Vec<usize>,
+    pub votes: Vec<CandidateVoteRoundResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ElectionWinner {
+    pub candidates: Vec<String>,
+    pub log: Vec<LogEntry>,
+    pub winner: usize,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Picks the option to eliminate from `candidates`, the one with the fewest
+/// votes at `pref_number` and then each later preference, falling back to the RNG
+pub(crate) fn break_elimination_tie(
+    rng: &mut Pcg64,
+    preference_tally: &[Vec<usize>],
+    candidates: &[usize],
+    pref_number: usize,
+) -> usize {
+    let lowest_value = candidates
+        .iter()
+        .map(|c| preference_tally[*c][pref_number])
+        .min()
+        .unwrap();
+
+    let matching_lowest = candidates
+        .iter()
+        .copied()
+        .filter(|c| preference_tally[*c][pref_number] == lowest_value)
+        .collect::<Vec<usize>>();
+
+    info!(
+        "Trying to break tie checking pref number {} between {:?} lowest value is {}",
+        pref_number,
+        candidates
+            .iter()
+            .map(|c| (*c, preference_tally[*c][pref_number]))
+            .collect::<Vec<(usize, usize)>>(),
+        lowest_value
+    );
+
+    if matching_lowest.len() == 1 {
+        return *matching_lowest.first().unwrap();
+    }
+
+    if pref_number + 1 >= preference_tally[0].len() {
+        return *matching_lowest.choose(rng).unwrap();
+    }
+
+    break_elimination_tie(rng, preference_tally, &matching_lowest, pref_number + 1)
+}
+
+pub(crate) fn get_preference_tally(
+    candidate_count: usize,
+    votes: &[PreferentialVote],
+) -> Vec<Vec<usize>> {
+    let mut preference_tally = vec![vec![0; candidate_count]; candidate_count];
+
+    for (vote, weight) in weighted(votes) {
+        for (vote, pref_number) in vote.votes.iter().enumerate() {
+            preference_tally[vote][*pref_number] += weight;
+        }
+    }
+
+    preference_tally
+}
+
+/// Borda scores for the `TieBreak::Borda` policy from each ballot's preferences
+pub(crate) fn borda_scores(candidate_count: usize, votes: &[PreferentialVote]) -> Vec<f64> {
+    borda_from_values(
+        candidate_count,
+        weighted(votes)
+            .map(|(vote, weight)| (vote.votes.iter().map(|i| Reverse(*i)).collect(), weight)),
+    )
+}
+
+/// Under `TieBreak::Method` a final tie is settled by eliminating options
+/// one at a time on their preferences
+fn last_standing(rng: &mut Pcg64, preference_tally: &[Vec<usize>], tied: &[usize]) -> usize {
+    let mut tied_candidates = tied.to_vec();
+    while tied_candidates.len() > 1 {
+        let remove_index = break_elimination_tie(rng, preference_tally, &tied_candidates, 0);
+        tied_candidates.retain(|i| *i != remove_index);
+    }
+    tied_candidates[0]
+}
+
+/// Votes for each option from the highest preference on each ballot that
+/// isn't eliminated
+fn count_first_preferences(
+    candidate_count: usize,
+    votes: &[PreferentialVote],
+    eliminated_candidates: &HashSet<usize>,
+) -> Vec<usize> {
+    let mut vote_count = vec![0; candidate_count];
+    for (vote, weight) in weighted(votes) {
+        let mut votes: Vec<_> = vote
+            .votes
+            .iter()
+            .enumerate()
+            .map(|(x, y)| (x, *y))
+            .collect();
+
+        votes.sort_by_key(|(_, pref)| *pref);
+
+        for option in votes {
+            if !eliminated_candidates.contains(&option.0) {
+                vote_count[option.0] += weight;
+                break;
+            }
+        }
+    }
+    vote_count
+}
+
+fn log_entry(
+    candidates: &[String],
+    vote_count: &[usize],
+    eliminated_candidates: &HashSet<usize>,
+) -> LogEntry {
+    let entry: Vec<CandidateVoteRoundResult> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, name)| CandidateVoteRoundResult {
+            name: name.clone(),
+            votes: vote_count[i],
+        })
+        .collect();
+    info!("Candidates this round {:?}", entry);
+    LogEntry {
+        eliminated: eliminated_candidates.iter().copied().collect(),
+        votes: entry,
+    }
+}
+
+pub fn get_election_winner(
+    election: &PreferentialElection,
+    votes: &[PreferentialVote],
+) -> ElectionWinner {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let preference_tally = get_preference_tally(election.options.len(), votes);
+
+    let candidates = election.options.clone();
+    let target_count = total_weight(votes) / 2;
+
+    let mut log = Vec::new();
+
+    let mut eliminated_candidates = HashSet::new();
+    let mut vote_counts: Vec<Vec<usize>> = Vec::new();
+    // Loop through each preference level
+    loop {
+        vote_counts.push(count_first_preferences(
+            candidates.len(),
+            votes,
+            &eliminated_candidates,
+        ));
+        let vote_count = vote_counts.last().unwrap();
+        log.push(log_entry(&candidates, vote_count, &eliminated_candidates));
+        tie_breaker.record_round(vote_count.iter().map(|i| *i as f64));
+        if vote_count.iter().any(|&v| v > target_count)
+            || (candidates.len() - eliminated_candidates.len()) <= 2
+        {
+            break;
+        }
+
+        // Eliminate the lowest candidate
+        let lowest_index = vote_count
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, &v)| {
+                if eliminated_candidates.contains(i) {
+                    usize::MAX
+                } else {
+                    v
+                }
+            })
+            .unwrap()
+            .0;
+        let lowest_not_eliminated_vote_count = vote_count[lowest_index];
+        let remove_list = vote_count
+            .iter()
+            .enumerate()
+            .filter(|(i, &v)| {
+                !eliminated_candidates.contains(i) && v == lowest_not_eliminated_vote_count
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        let remove_index = if remove_list.len() > 1 {
+            info!("Tie between candidates {:?} with {:?} votes will remove one with lowest first round", remove_list, lowest_not_eliminated_vote_count);
+            tie_breaker.break_tie(TieKind::Exclude, &remove_list, |rng| {
+                break_elimination_tie(rng, &preference_tally, &remove_list, 0)
+            })
+        } else {
+            remove_list[0]
+        };
+        info!(
+            "Removing candidate {:?} with {:?} votes",
+            remove_index, lowest_not_eliminated_vote_count
+        );
+        eliminated_candidates.insert(remove_index);
+    }
+
+    let last_vote_count = vote_counts.last().unwrap();
+
+    let winner_index = match last_vote_count.iter().position(|v| *v > target_count) {
+        Some(i) => i,
+        None => {
+            let standing: Vec<usize> = (0..candidates.len())
+                .filter(|i| !eliminated_candidates.contains(i))
+                .collect();
+            // The method's own rule looks at every option left standing, a
+            // policy only at the ones level on the most votes
+            let tied = if election.tie_break == TieBreak::Method {
+                standing
+            } else {
+                let most = standing.iter().map(|i| last_vote_count[*i]).max().unwrap();
+                standing
+                    .into_iter()
+                    .filter(|i| last_vote_count[*i] == most)
+                    .collect()
+            };
+            tie_breaker.break_tie(TieKind::Elect, &tied, |rng| {
+                last_standing(rng, &preference_tally, &tied)
+            })
+        }
+    };
+
+    info!("Winner(s) {:?}", candidates[winner_index]);
+
+    ElectionWinner {
+        winner: winner_index,
+        log,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        candidates,
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+/// Contingent vote, if nobody has a majority of first preferences every
+/// option but the top two is eliminated at once and their ballots move to
+/// whichever of the two they prefer
+pub fn get_contingent_winner(
+    election: &PreferentialElection,
+    votes: &[PreferentialVote],
+) -> ElectionWinner {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let preference_tally = get_preference_tally(election.options.len(), votes);
+
+    let candidates = election.options.clone();
+    let target_count = total_weight(votes) / 2;
+
+    let mut eliminated_candidates = HashSet::new();
+    let first_round = count_first_preferences(candidates.len(), votes, &eliminated_candidates);
+    tie_breaker.record_round(first_round.iter().map(|i| *i as f64));
+    let mut log = vec![log_entry(&candidates, &first_round, &eliminated_candidates)];
+
+    let winner_index = match first_round.iter().position(|v| *v > target_count) {
+        Some(i) => i,
+        None => {
+            // Drop the lowest one at a time so ties for second place are
+            // broken the same way as an instant runoff would
+            while candidates.len() - eliminated_candidates.len() > 2 {
+                let lowest_count = (0..candidates.len())
+                    .filter(|i| !eliminated_candidates.contains(i))
+                    .map(|i| first_round[i])
+                    .min()
+                    .unwrap();
+                let remove_list = (0..candidates.len())
+                    .filter(|i| {
+                        !eliminated_candidates.contains(i) && first_round[*i] == lowest_count
+                    })
+                    .collect::<Vec<usize>>();
+                let remove_index = tie_breaker.break_tie(TieKind::Exclude, &remove_list, |rng| {
+                    break_elimination_tie(rng, &preference_tally, &remove_list, 0)
+                });
+                info!(
+                    "Removing candidate {:?} with {:?} votes",
+                    remove_index, lowest_count
+                );
+                eliminated_candidates.insert(remove_index);
+            }
+
+            let second_round =
+                count_first_preferences(candidates.len(), votes, &eliminated_candidates);
+            log.push(log_entry(
+                &candidates,
+                &second_round,
+                &eliminated_candidates,
+            ));
+            tie_breaker.record_round(second_round.iter().map(|i| *i as f64));
+
+            let finalists = (0..candidates.len())
+                .filter(|i| !eliminated_candidates.contains(i))
+                .collect::<Vec<usize>>();
+            let most = finalists.iter().map(|i| second_round[*i]).max().unwrap();
+            let tied = finalists
+                .into_iter()
+                .filter(|i| second_round[*i] == most)
+                .collect::<Vec<usize>>();
+            tie_breaker.break_tie(TieKind::Elect, &tied, |rng| {
+                last_standing(rng, &preference_tally, &tied)
+            })
+        }
+    };
+
+    info!("Winner(s) {:?}", candidates[winner_index]);
+
+    ElectionWinner {
+        winner: winner_index,
+        log,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        candidates,
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct PreferentialVoting;
+
+impl VotingSystem for PreferentialVoting {
+    type Ballot = PreferentialVote;
+    type Config = PreferentialElection;
+    type Outcome = ElectionWinner;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_election_winner(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        outcome_from_log(report.candidates.len(), &report.log, report.winner)
+    }
+}
+
+/// Shared view for counts that log rounds as `LogEntry`s, options still
+/// standing are placed by their last round votes and the eliminated follow
+/// in reverse order of elimination
+pub(crate) fn outcome_from_log(candidate_count: usize, log: &[LogEntry], winner: usize) -> Outcome {
+    let mut removed = Vec::new();
+    let mut ties = Vec::new();
+    for rounds in log.windows(2) {
+        let (round, next) = (&rounds[0], &rounds[1]);
+        for option_index in next
+            .eliminated
+            .iter()
+            .filter(|i| !round.eliminated.contains(i))
+        {
+            let level = (0..round.votes.len())
+                .filter(|i| {
+                    !round.eliminated.contains(i)
+                        && round.votes[*i].votes == round.votes[*option_index].votes
+                })
+                .collect::<Vec<usize>>();
+            if level.len() > 1 {
+                ties.push(level);
+            }
+            removed.push(*option_index);
+        }
+    }
+
+    let last_round = log.last().unwrap();
+    let standing = (0..candidate_count)
+        .filter(|i| !last_round.eliminated.contains(i))
+        .collect::<Vec<usize>>();
+    let final_scores = last_round
+        .votes
+        .iter()
+        .map(|i| i.votes)
+        .collect::<Vec<usize>>();
+    let places = rank_by_removal(&standing, &final_scores, &removed);
+    ties.extend(level_places(&places[..places.len() - removed.len()]));
+
+    Outcome::new(vec![winner], places, ties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tie_break::TieResolution;
+
+    fn vote(votes: Vec<usize>) -> PreferentialVote {
+        PreferentialVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_election_winner_majority_first_round() {
+        let election = PreferentialElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 2, 1]),
+            vote(vec![1, 0, 2]),
+        ];
+
+        let result = get_election_winner(&election, &votes);
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.log.len(), 1);
+    }
+
+    #[test]
+    fn test_get_election_winner_after_elimination() {
+        let election = PreferentialElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // C is eliminated and their preferences flow to B
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![2, 1, 0]),
+        ];
+
+        let result = get_election_winner(&election, &votes);
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.log.len(), 2);
+        assert_eq!(result.log[1].eliminated, vec![2]);
+    }
+
+    #[test]
+    fn test_get_election_winner_count_back_tie() {
+        let options = ["A", "B", "C", "D"].iter().map(|i| i.to_string()).collect();
+        let election = PreferentialElection {
+            options,
+            seed: "test".to_string(),
+            tie_break: TieBreak::ForwardCountBack,
+        };
+
+        // Once D is out B and C are level, B was ahead on first preferences
+        let mut votes = vec![];
+        votes.extend((0..4).map(|_| vote(vec![0, 1, 2, 3])));
+        votes.extend((0..3).map(|_| vote(vec![1, 0, 2, 3])));
+        votes.extend((0..2).map(|_| vote(vec![2, 1, 0, 3])));
+        votes.push(vote(vec![3, 2, 1, 0]));
+
+        let result = get_election_winner(&election, &votes);
+        assert_eq!(result.winner, 1);
+        let tie = result
+            .ties
+            .iter()
+            .find(|i| i.kind == TieKind::Exclude)
+            .unwrap();
+        assert_eq!(tie.tied, vec![1, 2]);
+        assert_eq!(tie.chosen, 2);
+        assert_eq!(tie.resolved_by, TieResolution::CountBack { round: 0 });
+    }
+
+    #[test]
+    fn test_get_election_winner_weighted_ballots() {
+        use crate::weight::get_weighted_result;
+
+        let election = PreferentialElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let weighted = |votes: Vec<usize>, weight: Weight| PreferentialVote {
+            weight,
+            ..vote(votes)
+        };
+        let votes = vec![
+            weighted(vec![0, 1, 2], Weight::whole(2)),
+            weighted(vec![1, 0, 2], Weight::new(3, 2).unwrap()),
+            weighted(vec![2, 1, 0], Weight::ONE),
+        ];
+
+        let result = get_weighted_result(&votes, |votes| get_election_winner(&election, votes));
+        assert_eq!(result.weight_scale, 2);
+        // In halves of a vote A has 4, B 3 and C 2, C's ballot then goes to B
+        assert_eq!(result.weighted.log[0].votes[0].votes, 4);
+        assert_eq!(result.weighted.log[1].votes[1].votes, 5);
+        assert_eq!(result.weighted.winner, 1);
+        let raw = result.raw.unwrap();
+        assert_eq!(raw.log[0].votes[0].votes, 1);
+        assert_eq!(raw.vote_count, 3);
+    }
+
+    #[test]
+    fn test_outcome_ranks_eliminated_last() {
+        let election = PreferentialElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![2, 1, 0]),
+        ];
+
+        let outcome = PreferentialVoting::run(&election, &votes);
+        assert_eq!(outcome.elected, vec![1]);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![0], vec![2]]);
+        assert!(outcome.ties.is_empty());
+    }
+    #[test]
+    fn test_contingent_eliminates_all_but_top_two() {
+        let election = PreferentialElection {
+            options: ["A", "B", "C", "D"].iter().map(|i| i.to_string()).collect(),
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // First preferences are A 5, B 4, C 3, D 2. An instant runoff
+        // elects C with B's and D's transfers, the contingent vote only
+        // keeps A and B and B picks up everything else
+        let mut votes = vec![];
+        for (count, preferences) in [
+            (5, vec![0, 1, 2, 3]),
+            (4, vec![2, 0, 1, 3]),
+            (3, vec![2, 1, 0, 3]),
+            (2, vec![3, 2, 1, 0]),
+        ] {
+            for _ in 0..count {
+                votes.push(vote(preferences.clone()));
+            }
+        }
+
+        assert_eq!(get_election_winner(&election, &votes).winner, 2);
+
+        let result = get_contingent_winner(&election, &votes);
+        assert_eq!(result.winner, 1);
+        assert_eq!(result.log.len(), 2);
+        let mut eliminated = result.log[1].eliminated.clone();
+        eliminated.sort();
+        assert_eq!(eliminated, vec![2, 3]);
+        let second_round = result.log[1]
+            .votes
+            .iter()
+            .map(|i| i.votes)
+            .collect::<Vec<_>>();
+        assert_eq!(second_round, vec![5, 9, 0, 0]);
+    }
+
+    #[test]
+    fn test_contingent_first_round_majority() {
+        let election = PreferentialElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+        ];
+
+        let result = get_contingent_winner(&election, &votes);
+        assert_eq!(result.winner, 0);
+        assert_eq!(result.log.len(), 1);
+    }
+}
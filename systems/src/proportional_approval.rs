@@ -0,0 +1,634 @@
+use std::{collections::HashMap, fmt};
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    approval::ApprovalVote,
+    numeric::Number,
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::weighted,
+};
+
+/// Largest number of committees exact PAV will score
+pub const PAV_MAX_COMMITTEES: u128 = 200_000;
+
+/// Largest committee any of the methods will fill
+pub const MAX_COMMITTEE_SIZE: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProportionalApprovalMethod {
+    /// Scores every committee, a voter counts 1 + 1/2 + ... + 1/r for the r
+    /// members they approve of
+    Pav,
+    /// Fills seats one at a time, a voter with r approved members already
+    /// elected counts 1/(r + 1) towards each option they approve of
+    SequentialPav,
+    /// Fills seats one at a time with the option that leaves the smallest
+    /// maximum load on the voters that approve of it
+    Phragmen,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProportionalApprovalElection {
+    pub options: Vec<String>,
+    pub committee_size: usize,
+    pub method: ProportionalApprovalMethod,
+    /// Seeds the RNG used when options or committees are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+/// Voters who approved of exactly the same options
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalVoterGroup {
+    pub approvals: Vec<usize>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProportionalApprovalStep {
+    pub elected: usize,
+    /// What each option offered for the seat, the PAV score it would add or
+    /// for Phragmén the load it would leave on its voters. None once elected
+    pub scores: Vec<Option<f64>>,
+    /// Per voter group after the seat, the weight of each vote for PAV or
+    /// the load carried by each voter for Phragmén
+    pub group_values: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProportionalApprovalResult {
+    pub options: Vec<String>,
+    pub method: ProportionalApprovalMethod,
+    pub committee_size: usize,
+    pub approval_counts: Vec<usize>,
+    /// Largest group first
+    pub groups: Vec<ApprovalVoterGroup>,
+    pub steps: Vec<ProportionalApprovalStep>,
+    /// In the order the seats were filled
+    pub committee: Vec<usize>,
+    /// PAV score of the committee
+    pub pav_score: f64,
+    /// Committees scored by exact PAV
+    pub committees_checked: Option<usize>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ProportionalApprovalError {
+    InvalidCommitteeSize {
+        committee_size: usize,
+        option_count: usize,
+    },
+    TooManyCommittees {
+        committee_count: u128,
+        limit: u128,
+    },
+    CommitteeTooLarge {
+        committee_size: usize,
+        limit: usize,
+    },
+}
+
+impl fmt::Display for ProportionalApprovalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProportionalApprovalError::InvalidCommitteeSize {
+                committee_size,
+                option_count,
+            } => write!(
+                f,
+                "Committee size must be between 1 and {} but was {}",
+                option_count, committee_size
+            ),
+            ProportionalApprovalError::TooManyCommittees {
+                committee_count,
+                limit,
+            } => write!(
+                f,
+                "PAV can check at most {} committees but the election has {}",
+                limit, committee_count
+            ),
+            ProportionalApprovalError::CommitteeTooLarge {
+                committee_size,
+                limit,
+            } => write!(
+                f,
+                "Committee size can be at most {} but was {}",
+                limit, committee_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProportionalApprovalError {}
+
+pub fn get_voter_groups(votes: &[ApprovalVote]) -> Vec<ApprovalVoterGroup> {
+    let mut map: HashMap<Vec<usize>, usize> = HashMap::new();
+    for (vote, weight) in weighted(votes) {
+        let approvals = vote
+            .votes
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| **v)
+            .map(|(i, _)| i)
+            .collect();
+        *map.entry(approvals).or_insert(0) += weight;
+    }
+
+    let mut groups = map
+        .into_iter()
+        .map(|(approvals, count)| ApprovalVoterGroup { approvals, count })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then(a.approvals.cmp(&b.approvals)));
+    groups
+}
+
+fn approved_members(group: &ApprovalVoterGroup, committee: &[usize]) -> usize {
+    committee
+        .iter()
+        .filter(|i| group.approvals.contains(i))
+        .count()
+}
+
+/// PAV score kept as an exact fraction so level committees compare equal
+fn get_pav_score(groups: &[ApprovalVoterGroup], committee: &[usize]) -> Number {
+    groups
+        .iter()
+        .map(|group| {
+            let members = approved_members(group, committee);
+            (1..=members as i64)
+                .map(|j| Number::new(group.count as i64, j))
+                .sum::<Number>()
+        })
+        .sum()
+}
+
+/// PAV score `option_index` would add to `committee`
+fn get_marginal_score(
+    groups: &[ApprovalVoterGroup],
+    committee: &[usize],
+    option_index: usize,
+) -> Number {
+    groups
+        .iter()
+        .filter(|group| group.approvals.contains(&option_index))
+        .map(|group| {
+            Number::new(
+                group.count as i64,
+                approved_members(group, committee) as i64 + 1,
+            )
+        })
+        .sum()
+}
+
+fn get_pav_weights(groups: &[ApprovalVoterGroup], committee: &[usize]) -> Vec<f64> {
+    groups
+        .iter()
+        .map(|group| 1.0 / (approved_members(group, committee) + 1) as f64)
+        .collect()
+}
+
+fn binomial(n: usize, k: usize) -> u128 {
+    (0..k as u128).fold(1, |acc: u128, i| {
+        acc.saturating_mul(n as u128 - i) / (i + 1)
+    })
+}
+
+/// Visits every committee of `size` options in lexicographic order
+fn for_each_committee(
+    option_count: usize,
+    size: usize,
+    committee: &mut Vec<usize>,
+    visit: &mut impl FnMut(&[usize]),
+) {
+    if committee.len() == size {
+        visit(committee);
+        return;
+    }
+    let start = committee.last().map_or(0, |i| i + 1);
+    for option_index in start..option_count {
+        committee.push(option_index);
+        for_each_committee(option_count, size, committee, visit);
+        committee.pop();
+    }
+}
+
+/// Adds members one at a time by the most PAV score, choosing only from
+/// `candidates`
+fn sequential_pav(
+    tie_breaker: &mut TieBreaker,
+    groups: &[ApprovalVoterGroup],
+    candidates: &[usize],
+    option_count: usize,
+    committee_size: usize,
+) -> (Vec<usize>, Vec<ProportionalApprovalStep>) {
+    let mut committee: Vec<usize> = Vec::new();
+    let mut steps = Vec::new();
+    while committee.len() < committee_size {
+        let marginal = (0..option_count)
+            .map(|i| {
+                if committee.contains(&i) {
+                    None
+                } else {
+                    Some(get_marginal_score(groups, &committee, i))
+                }
+            })
+            .collect::<Vec<Option<Number>>>();
+        tie_breaker.record_round(
+            marginal
+                .iter()
+                .map(|i| i.as_ref().map_or(0.0, Number::to_f64)),
+        );
+
+        let remaining = candidates
+            .iter()
+            .copied()
+            .filter(|i| !committee.contains(i))
+            .collect::<Vec<usize>>();
+        let best = remaining
+            .iter()
+            .map(|i| &marginal[*i])
+            .max()
+            .unwrap()
+            .clone();
+        let best = remaining
+            .into_iter()
+            .filter(|i| marginal[*i] == best)
+            .collect::<Vec<usize>>();
+        let elected =
+            tie_breaker.break_tie(TieKind::Elect, &best, |rng| *best.choose(rng).unwrap());
+        committee.push(elected);
+
+        steps.push(ProportionalApprovalStep {
+            elected,
+            scores: marginal
+                .iter()
+                .map(|i| i.as_ref().map(Number::to_f64))
+                .collect(),
+            group_values: get_pav_weights(groups, &committee),
+        });
+    }
+    (committee, steps)
+}
+
+fn phragmen(
+    tie_breaker: &mut TieBreaker,
+    groups: &[ApprovalVoterGroup],
+    option_count: usize,
+    committee_size: usize,
+) -> (Vec<usize>, Vec<ProportionalApprovalStep>) {
+    let mut committee: Vec<usize> = Vec::new();
+    let mut loads = vec![0.0; groups.len()];
+    let mut steps = Vec::new();
+    while committee.len() < committee_size {
+        // Spreading one more seat over the approvers evens out their loads
+        let new_loads = (0..option_count)
+            .map(|i| {
+                if committee.contains(&i) {
+                    return None;
+                }
+                let (approvers, load) = groups
+                    .iter()
+                    .zip(&loads)
+                    .filter(|(group, _)| group.approvals.contains(&i))
+                    .fold((0, 0.0), |(approvers, load), (group, group_load)| {
+                        (
+                            approvers + group.count,
+                            load + group.count as f64 * group_load,
+                        )
+                    });
+                if approvers == 0 {
+                    Some(f64::INFINITY)
+                } else {
+                    Some((1.0 + load) / approvers as f64)
+                }
+            })
+            .collect::<Vec<Option<f64>>>();
+        // A lighter load is better
+        tie_breaker.record_round(new_loads.iter().map(|i| i.map_or(0.0, |load| -load)));
+
+        let lowest = new_loads
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f64::INFINITY, f64::min);
+        let lowest = (0..option_count)
+            .filter(|i| new_loads[*i] == Some(lowest))
+            .collect::<Vec<usize>>();
+        let elected =
+            tie_breaker.break_tie(TieKind::Elect, &lowest, |rng| *lowest.choose(rng).unwrap());
+        committee.push(elected);
+
+        let new_load = new_loads[elected].unwrap();
+        for (group, load) in groups.iter().zip(loads.iter_mut()) {
+            if group.approvals.contains(&elected) {
+                *load = new_load;
+            }
+        }
+
+        steps.push(ProportionalApprovalStep {
+            elected,
+            scores: new_loads,
+            group_values: loads.clone(),
+        });
+    }
+    (committee, steps)
+}
+
+/// Picks from committees level on PAV score by putting the options they
+/// differ on in order, the committee holding the earliest of them wins
+fn pick_committee(
+    tie_breaker: &mut TieBreaker,
+    option_count: usize,
+    best: &[Vec<usize>],
+) -> Vec<usize> {
+    if best.len() == 1 {
+        return best[0].clone();
+    }
+
+    let contested = (0..option_count)
+        .filter(|i| {
+            let holding = best.iter().filter(|c| c.contains(i)).count();
+            holding > 0 && holding < best.len()
+        })
+        .collect::<Vec<usize>>();
+    let order = tie_breaker.order(TieKind::Elect, &contested, |rng| {
+        let members = best.choose(rng).unwrap();
+        let (mut order, rest): (Vec<usize>, Vec<usize>) =
+            contested.iter().partition(|i| members.contains(i));
+        order.extend(rest);
+        order
+    });
+    best.iter()
+        .min_by_key(|committee| {
+            let mut positions = committee
+                .iter()
+                .filter_map(|i| order.iter().position(|j| j == i))
+                .collect::<Vec<usize>>();
+            positions.sort();
+            positions
+        })
+        .unwrap()
+        .clone()
+}
+
+pub fn get_result(
+    election: &ProportionalApprovalElection,
+    votes: &[ApprovalVote],
+) -> Result<ProportionalApprovalResult, ProportionalApprovalError> {
+    let option_count = election.options.len();
+    let committee_size = election.committee_size;
+    if committee_size == 0 || committee_size > option_count {
+        return Err(ProportionalApprovalError::InvalidCommitteeSize {
+            committee_size,
+            option_count,
+        });
+    }
+    if committee_size > MAX_COMMITTEE_SIZE {
+        return Err(ProportionalApprovalError::CommitteeTooLarge {
+            committee_size,
+            limit: MAX_COMMITTEE_SIZE,
+        });
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            option_count,
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let groups = get_voter_groups(votes);
+    let mut approval_counts = vec![0; option_count];
+    for group in &groups {
+        for option_index in &group.approvals {
+            approval_counts[*option_index] += group.count;
+        }
+    }
+    tie_breaker.record_round(approval_counts.iter().map(|i| *i as f64));
+    let all_options = (0..option_count).collect::<Vec<usize>>();
+
+    let mut committees_checked = None;
+    let (committee, steps) = match election.method {
+        ProportionalApprovalMethod::Pav => {
+            let committee_count = binomial(option_count, committee_size);
+            if committee_count > PAV_MAX_COMMITTEES {
+                return Err(ProportionalApprovalError::TooManyCommittees {
+                    committee_count,
+                    limit: PAV_MAX_COMMITTEES,
+                });
+            }
+
+            let mut best_score = Number::zero();
+            let mut best: Vec<Vec<usize>> = Vec::new();
+            let mut checked = 0;
+            for_each_committee(
+                option_count,
+                committee_size,
+                &mut Vec::new(),
+                &mut |committee| {
+                    checked += 1;
+                    let score = get_pav_score(&groups, committee);
+                    if best.is_empty() || score > best_score {
+                        best_score = score;
+                        best = vec![committee.to_vec()];
+                    } else if score == best_score {
+                        best.push(committee.to_vec());
+                    }
+                },
+            );
+            committees_checked = Some(checked);
+
+            // Lists the chosen members in the order they add the most score
+            let members = pick_committee(&mut tie_breaker, option_count, &best);
+            sequential_pav(
+                &mut tie_breaker,
+                &groups,
+                &members,
+                option_count,
+                committee_size,
+            )
+        }
+        ProportionalApprovalMethod::SequentialPav => sequential_pav(
+            &mut tie_breaker,
+            &groups,
+            &all_options,
+            option_count,
+            committee_size,
+        ),
+        ProportionalApprovalMethod::Phragmen => {
+            phragmen(&mut tie_breaker, &groups, option_count, committee_size)
+        }
+    };
+
+    Ok(ProportionalApprovalResult {
+        options: election.options.clone(),
+        method: election.method,
+        committee_size,
+        approval_counts,
+        pav_score: get_pav_score(&groups, &committee).to_f64(),
+        groups,
+        steps,
+        committee,
+        committees_checked,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    })
+}
+
+pub struct ProportionalApproval;
+
+impl VotingSystem for ProportionalApproval {
+    type Ballot = ApprovalVote;
+    type Config = ProportionalApprovalElection;
+    type Outcome = Result<ProportionalApprovalResult, ProportionalApprovalError>;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let report = match report {
+            Ok(report) => report,
+            Err(_) => return Outcome::new(Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        let places = rank_by_score(
+            report
+                .approval_counts
+                .iter()
+                .enumerate()
+                .map(|(i, count)| (i, (report.committee.contains(&i), *count))),
+        );
+        let mut ties = Vec::new();
+        for step in &report.steps {
+            let elected_score = step.scores[step.elected];
+            let level = (0..report.options.len())
+                .filter(|i| step.scores[*i].is_some() && step.scores[*i] == elected_score)
+                .collect::<Vec<usize>>();
+            if level.len() > 1 {
+                ties.push(level);
+            }
+        }
+
+        Outcome::new(report.committee.clone(), places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn votes(groups: &[(usize, Vec<usize>)], option_count: usize) -> Vec<ApprovalVote> {
+        let mut votes = vec![];
+        for (count, approvals) in groups {
+            for _ in 0..*count {
+                votes.push(ApprovalVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: (0..option_count).map(|i| approvals.contains(&i)).collect(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(
+        method: ProportionalApprovalMethod,
+        committee_size: usize,
+    ) -> ProportionalApprovalElection {
+        ProportionalApprovalElection {
+            options: ["A", "B", "C", "D"].iter().map(|i| i.to_string()).collect(),
+            committee_size,
+            method,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_pav_shares_seats() {
+        // Plain approval would seat A and B, PAV gives the minority C
+        let votes = votes(&[(6, vec![0, 1]), (4, vec![2])], 4);
+
+        let result = get_result(&election(ProportionalApprovalMethod::Pav, 2), &votes).unwrap();
+        assert_eq!(result.committees_checked, Some(6));
+        let mut committee = result.committee.clone();
+        committee.sort();
+        assert_eq!(committee[1], 2);
+        assert_eq!(result.pav_score, 10.0);
+
+        let result = get_result(
+            &election(ProportionalApprovalMethod::SequentialPav, 2),
+            &votes,
+        )
+        .unwrap();
+        assert_eq!(result.steps[0].scores[0], Some(6.0));
+        // The majority's votes are worth half once they have a member
+        assert_eq!(result.steps[0].group_values, vec![0.5, 1.0]);
+        let other = 1 - result.steps[0].elected;
+        assert_eq!(result.steps[1].scores[other], Some(3.0));
+        assert_eq!(result.steps[1].scores[2], Some(4.0));
+        assert_eq!(result.steps[1].elected, 2);
+    }
+
+    #[test]
+    fn test_get_result_phragmen_loads() {
+        let votes = votes(&[(7, vec![0, 1]), (3, vec![2])], 4);
+
+        let result =
+            get_result(&election(ProportionalApprovalMethod::Phragmen, 3), &votes).unwrap();
+        // The first seat spreads a load of 1/7 over the majority, the second
+        // raises it to 2/7 which is still below the 1/3 the minority would carry
+        assert!((result.steps[0].group_values[0] - 1.0 / 7.0).abs() < 1e-9);
+        assert!((result.steps[1].scores[2].unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert!((result.steps[1].group_values[0] - 2.0 / 7.0).abs() < 1e-9);
+        assert_eq!(result.steps[2].elected, 2);
+        assert!((result.steps[2].group_values[1] - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            result.steps[2].group_values[0],
+            result.steps[1].group_values[0]
+        );
+    }
+
+    #[test]
+    fn test_get_result_large_committee() {
+        // The harmonic scores of a committee this size have no common
+        // denominator that fits in a machine word
+        let votes = votes(&[(3, (0..100).collect()), (2, vec![0])], 100);
+        let mut election = election(ProportionalApprovalMethod::SequentialPav, 95);
+        election.options = (0..100).map(|i| i.to_string()).collect();
+
+        let result = get_result(&election, &votes).unwrap();
+        assert_eq!(result.committee.len(), 95);
+        assert_eq!(result.committee[0], 0);
+
+        election.options = (0..101).map(|i| i.to_string()).collect();
+        election.committee_size = 101;
+        assert_eq!(
+            get_result(&election, &votes).unwrap_err(),
+            ProportionalApprovalError::CommitteeTooLarge {
+                committee_size: 101,
+                limit: MAX_COMMITTEE_SIZE,
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_result_invalid_committee_size() {
+        let result = get_result(&election(ProportionalApprovalMethod::Pav, 5), &[]);
+        assert_eq!(
+            result.unwrap_err(),
+            ProportionalApprovalError::InvalidCommitteeSize {
+                committee_size: 5,
+                option_count: 4,
+            }
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    score::ScoreVote,
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{Outcome, VotingSystem},
+    weight::{total_weight, weighted},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProportionalScoreMethod {
+    /// Each ballot counts K / (K + SUM / MAX) where SUM is the score it gave
+    /// the options already elected
+    ReweightedRange,
+    /// Each ballot holds a budget of one vote, the winner's supporters spend
+    /// a Hare quota between them in proportion to the score they gave it
+    SequentiallySpentScore,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProportionalScoreElection {
+    pub options: Vec<String>,
+    pub max_score: usize,
+    pub seat_count: usize,
+    pub method: ProportionalScoreMethod,
+    /// The K constant for Reweighted Range Voting, 1 is Jefferson-like and
+    /// 0.5 is Webster-like
+    pub k: f64,
+    /// Seeds the RNG used when options are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+/// Ballots that gave exactly the same scores
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScoreBallotGroup {
+    pub scores: Vec<usize>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProportionalScoreRound {
+    /// Weighted score of each standing option, as a share of `max_score` for
+    /// Sequentially Spent Score. None once elected
+    pub scores: Vec<Option<f64>>,
+    pub winner: usize,
+    /// Ballot weight used up electing the winner, only for Sequentially
+    /// Spent Score
+    pub spent: Option<f64>,
+    /// Weight left on each ballot of each group after the seat
+    pub group_weights: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProportionalScoreResult {
+    pub options: Vec<String>,
+    pub method: ProportionalScoreMethod,
+    pub max_score: usize,
+    pub seat_count: usize,
+    /// Only for Reweighted Range Voting
+    pub k: Option<f64>,
+    /// Hare quota, only for Sequentially Spent Score
+    pub quota: Option<f64>,
+    /// Largest group first
+    pub groups: Vec<ScoreBallotGroup>,
+    pub rounds: Vec<ProportionalScoreRound>,
+    /// In the order they were elected
+    pub winners: Vec<usize>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_ballot_groups(votes: &[ScoreVote]) -> Vec<ScoreBallotGroup> {
+    let mut map: HashMap<&[usize], usize> = HashMap::new();
+    for (vote, weight) in weighted(votes) {
+        *map.entry(&vote.votes).or_insert(0) += weight;
+    }
+
+    let mut groups = map
+        .into_iter()
+        .map(|(scores, count)| ScoreBallotGroup {
+            scores: scores.to_vec(),
+            count,
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then(a.scores.cmp(&b.scores)));
+    groups
+}
+
+fn rrv_weights(groups: &[ScoreBallotGroup], winners: &[usize], max_score: f64, k: f64) -> Vec<f64> {
+    groups
+        .iter()
+        .map(|group| {
+            let sum: usize = winners.iter().map(|i| group.scores[*i]).sum();
+            k / (k + sum as f64 / max_score)
+        })
+        .collect()
+}
+
+pub fn get_result(
+    election: &ProportionalScoreElection,
+    votes: &[ScoreVote],
+) -> ProportionalScoreResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let option_count = election.options.len();
+    let max_score = election.max_score.max(1) as f64;
+    let groups = get_ballot_groups(votes);
+    let quota = match election.method {
+        ProportionalScoreMethod::ReweightedRange => None,
+        ProportionalScoreMethod::SequentiallySpentScore => {
+            Some(total_weight(votes) as f64 / election.seat_count.max(1) as f64)
+        }
+    };
+    // Sequentially Spent Score compares shares of a full score so the
+    // quota is in the same units as the ballot weights
+    let scale = match election.method {
+        ProportionalScoreMethod::ReweightedRange => 1.0,
+        ProportionalScoreMethod::SequentiallySpentScore => max_score,
+    };
+
+    let mut weights = vec![1.0; groups.len()];
+    let mut winners: Vec<usize> = Vec::new();
+    let mut rounds = Vec::new();
+    while winners.len() < election.seat_count.min(option_count) {
+        let scores = (0..option_count)
+            .map(|i| {
+                if winners.contains(&i) {
+                    return None;
+                }
+                Some(
+                    groups
+                        .iter()
+                        .zip(&weights)
+                        .map(|(group, weight)| {
+                            group.count as f64 * group.scores[i] as f64 * weight / scale
+                        })
+                        .sum::<f64>(),
+                )
+            })
+            .collect::<Vec<Option<f64>>>();
+        tie_breaker.record_round(scores.iter().map(|i| i.unwrap_or(0.0)));
+
+        let highest = scores.iter().flatten().copied().fold(0.0, f64::max);
+        let highest = (0..option_count)
+            .filter(|i| scores[*i] == Some(highest))
+            .collect::<Vec<usize>>();
+        let winner = tie_breaker.break_tie(TieKind::Elect, &highest, |rng| {
+            *highest.choose(rng).unwrap()
+        });
+        winners.push(winner);
+
+        let spent = match election.method {
+            ProportionalScoreMethod::ReweightedRange => {
+                weights = rrv_weights(&groups, &winners, max_score, election.k);
+                None
+            }
+            ProportionalScoreMethod::SequentiallySpentScore => {
+                let total = scores[winner].unwrap();
+                let quota = quota.unwrap();
+                // Supporters only pay in full when the winner got no more than a quota
+                let factor = if total > quota { quota / total } else { 1.0 };
+                for (group, weight) in groups.iter().zip(weights.iter_mut()) {
+                    let spent = group.scores[winner] as f64 / max_score * *weight * factor;
+                    *weight = (*weight - spent).max(0.0);
+                }
+                Some(total.min(quota))
+            }
+        };
+
+        rounds.push(ProportionalScoreRound {
+            scores,
+            winner,
+            spent,
+            group_weights: weights.clone(),
+        });
+    }
+
+    ProportionalScoreResult {
+        options: election.options.clone(),
+        method: election.method,
+        max_score: election.max_score,
+        seat_count: election.seat_count,
+        k: match election.method {
+            ProportionalScoreMethod::ReweightedRange => Some(election.k),
+            ProportionalScoreMethod::SequentiallySpentScore => None,
+        },
+        quota,
+        groups,
+        rounds,
+        winners,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct ProportionalScore;
+
+impl VotingSystem for ProportionalScore {
+    type Ballot = ScoreVote;
+    type Config = ProportionalScoreElection;
+    type Outcome = ProportionalScoreResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut places = report
+            .winners
+            .iter()
+            .map(|i| vec![*i])
+            .collect::<Vec<Vec<usize>>>();
+        let mut ties = Vec::new();
+        for round in &report.rounds {
+            let level = (0..report.options.len())
+                .filter(|i| round.scores[*i] == round.scores[round.winner])
+                .collect::<Vec<usize>>();
+            if level.len() > 1 {
+                ties.push(level);
+            }
+        }
+
+        if let Some(round) = report.rounds.last() {
+            // Whoever was still standing at the last seat, by their score then
+            let mut standing = (0..report.options.len())
+                .filter(|i| *i != round.winner && round.scores[*i].is_some())
+                .collect::<Vec<usize>>();
+            standing.sort_by(|a, b| {
+                round.scores[*b]
+                    .unwrap()
+                    .total_cmp(&round.scores[*a].unwrap())
+            });
+            let mut last_score = None;
+            for option_index in standing {
+                if last_score == round.scores[option_index] {
+                    places.last_mut().unwrap().push(option_index);
+                } else {
+                    places.push(vec![option_index]);
+                }
+                last_score = round.scores[option_index];
+            }
+        }
+
+        Outcome::new(report.winners.clone(), places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn votes(groups: &[(usize, Vec<usize>)]) -> Vec<ScoreVote> {
+        let mut votes = vec![];
+        for (count, scores) in groups {
+            for _ in 0..*count {
+                votes.push(ScoreVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    votes: scores.clone(),
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(method: ProportionalScoreMethod, k: f64) -> ProportionalScoreElection {
+        ProportionalScoreElection {
+            options: ["A", "B", "C"].iter().map(|i| i.to_string()).collect(),
+            max_score: 5,
+            seat_count: 2,
+            method,
+            k,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_reweighted_range_k() {
+        let votes = votes(&[(8, vec![5, 4, 0]), (4, vec![0, 0, 5])]);
+
+        // With K = 1 the majority counts half once A is elected, B's 16
+        // loses to C's 20
+        let result = get_result(
+            &election(ProportionalScoreMethod::ReweightedRange, 1.0),
+            &votes,
+        );
+        assert_eq!(result.winners, vec![0, 2]);
+        assert_eq!(result.rounds[0].group_weights, vec![0.5, 1.0]);
+        assert_eq!(result.rounds[1].scores[1], Some(16.0));
+        assert_eq!(result.rounds[1].group_weights, vec![0.5, 0.5]);
+
+        // A larger K keeps more of the majority's weight, B gets 24
+        let result = get_result(
+            &election(ProportionalScoreMethod::ReweightedRange, 3.0),
+            &votes,
+        );
+        assert_eq!(result.winners, vec![0, 1]);
+        assert_eq!(result.rounds[0].group_weights, vec![0.75, 1.0]);
+        assert_eq!(result.k, Some(3.0));
+    }
+
+    #[test]
+    fn test_get_result_sequentially_spent_score() {
+        let votes = votes(&[(6, vec![5, 4, 0]), (4, vec![0, 0, 5])]);
+
+        // A's 6 full scores are more than the quota of 5, so each majority
+        // ballot spends 5/6 of its weight and C then beats B
+        let result = get_result(
+            &election(ProportionalScoreMethod::SequentiallySpentScore, 1.0),
+            &votes,
+        );
+        assert_eq!(result.quota, Some(5.0));
+        assert_eq!(result.winners, vec![0, 2]);
+        assert_eq!(result.rounds[0].spent, Some(5.0));
+        assert!((result.rounds[0].group_weights[0] - 1.0 / 6.0).abs() < 1e-9);
+        assert!((result.rounds[1].scores[1].unwrap() - 0.8).abs() < 1e-9);
+        // The minority's 4 is under a quota so they spend everything
+        assert_eq!(result.rounds[1].spent, Some(4.0));
+        assert_eq!(result.rounds[1].group_weights[1], 0.0);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{cmp::Reverse, fmt};
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticElection {
+    pub options: Vec<String>,
+    /// Credits each voter can spend, casting n votes on an option costs n²
+    pub credits: usize,
+    /// Whether votes can be cast against an option
+    pub allow_negative: bool,
+    /// Seeds the RNG used when options are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Votes cast on each option, negative votes are against the option
+    pub votes: Vec<i64>,
+}
+
+impl_weighted_ballot!(QuadraticVote);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuadraticVoteError {
+    WrongOptionCount { expected: usize, found: usize },
+    NegativeVote { option: usize },
+    OverBudget { credits: usize, cost: u64 },
+}
+
+impl fmt::Display for QuadraticVoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuadraticVoteError::WrongOptionCount { expected, found } => write!(
+                f,
+                "Ballot must have votes for {} options but had {}",
+                expected, found
+            ),
+            QuadraticVoteError::NegativeVote { option } => write!(
+                f,
+                "Votes against option {} are not allowed in this election",
+                option
+            ),
+            QuadraticVoteError::OverBudget { credits, cost } => write!(
+                f,
+                "Ballot costs {} credits but only {} are available",
+                cost, credits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QuadraticVoteError {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticTally {
+    pub option_index: usize,
+    /// Votes for less votes against
+    pub net_votes: i64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    /// Credits voters spent on the option, for and against
+    pub credits_spent: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuadraticResult {
+    pub options: Vec<String>,
+    pub credits: usize,
+    pub allow_negative: bool,
+    /// Tallies ordered from the most net votes to the least
+    pub votes_tally: Vec<QuadraticTally>,
+    /// None when no ballots have been cast
+    pub winner: Option<usize>,
+    pub credits_spent: u64,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Credits needed to cast `votes` on a single option
+pub fn credit_cost(votes: i64) -> u64 {
+    votes.unsigned_abs().saturating_mul(votes.unsigned_abs())
+}
+
+/// Checks a ballot against the election's budget, giving the credits it spends
+pub fn validate_vote(
+    election: &QuadraticElection,
+    votes: &[i64],
+) -> Result<u64, QuadraticVoteError> {
+    if votes.len() != election.options.len() {
+        return Err(QuadraticVoteError::WrongOptionCount {
+            expected: election.options.len(),
+            found: votes.len(),
+        });
+    }
+
+    if !election.allow_negative {
+        if let Some(option) = votes.iter().position(|v| *v < 0) {
+            return Err(QuadraticVoteError::NegativeVote { option });
+        }
+    }
+
+    let cost = votes
+        .iter()
+        .fold(0u64, |cost, v| cost.saturating_add(credit_cost(*v)));
+    if cost > election.credits as u64 {
+        return Err(QuadraticVoteError::OverBudget {
+            credits: election.credits,
+            cost,
+        });
+    }
+
+    Ok(cost)
+}
+
+pub fn get_result(election: &QuadraticElection, votes: &[QuadraticVote]) -> QuadraticResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let mut votes_tally: Vec<QuadraticTally> = (0..election.options.len())
+        .map(|i| QuadraticTally {
+            option_index: i,
+            net_votes: 0,
+            votes_for: 0,
+            votes_against: 0,
+            credits_spent: 0,
+        })
+        .collect();
+    for (vote, weight) in weighted(votes) {
+        for (tally, v) in votes_tally.iter_mut().zip(&vote.votes) {
+            tally.net_votes += *v * weight as i64;
+            if *v > 0 {
+                tally.votes_for += v.unsigned_abs() * weight as u64;
+            } else {
+                tally.votes_against += v.unsigned_abs() * weight as u64;
+            }
+            tally.credits_spent += credit_cost(*v) * weight as u64;
+        }
+    }
+    let credits_spent = votes_tally.iter().map(|i| i.credits_spent).sum();
+    tie_breaker.record_round(votes_tally.iter().map(|i| i.net_votes as f64));
+
+    let winner = if votes.is_empty() {
+        None
+    } else {
+        let most = votes_tally.iter().map(|i| i.net_votes).max();
+        let level: Vec<usize> = votes_tally
+            .iter()
+            .filter(|i| Some(i.net_votes) == most)
+            .map(|i| i.option_index)
+            .collect();
+        Some(tie_breaker.break_tie(TieKind::Elect, &level, |rng| *level.choose(rng).unwrap()))
+    };
+    votes_tally.sort_by_key(|i| Reverse(i.net_votes));
+
+    QuadraticResult {
+        options: election.options.clone(),
+        credits: election.credits,
+        allow_negative: election.allow_negative,
+        votes_tally,
+        winner,
+        credits_spent,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Quadratic;
+
+impl VotingSystem for Quadratic {
+    type Ballot = QuadraticVote;
+    type Config = QuadraticElection;
+    type Outcome = QuadraticResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            report.winner.into_iter().collect(),
+            rank_by_score(
+                report
+                    .votes_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.net_votes)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn election(allow_negative: bool) -> QuadraticElection {
+        QuadraticElection {
+            options: ["A", "B", "C"].iter().map(|i| i.to_string()).collect(),
+            credits: 16,
+            allow_negative,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    fn vote(votes: &[i64]) -> QuadraticVote {
+        QuadraticVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes: votes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_validate_vote() {
+        assert_eq!(validate_vote(&election(false), &[4, 0, 0]), Ok(16));
+        assert_eq!(validate_vote(&election(false), &[2, 2, 2]), Ok(12));
+        assert_eq!(
+            validate_vote(&election(false), &[3, 3, 0]),
+            Err(QuadraticVoteError::OverBudget {
+                credits: 16,
+                cost: 18
+            })
+        );
+        assert_eq!(
+            validate_vote(&election(false), &[1, -1, 0]),
+            Err(QuadraticVoteError::NegativeVote { option: 1 })
+        );
+        assert_eq!(validate_vote(&election(true), &[1, -3, 0]), Ok(10));
+        assert_eq!(
+            validate_vote(&election(true), &[1, 1]),
+            Err(QuadraticVoteError::WrongOptionCount {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_result() {
+        // One voter spends everything on A, two spread their votes over B and
+        // C and one spends against A
+        let votes = vec![
+            vote(&[4, 0, 0]),
+            vote(&[0, 2, 2]),
+            vote(&[0, 3, 1]),
+            vote(&[-3, 1, 0]),
+        ];
+
+        let result = get_result(&election(true), &votes);
+        assert_eq!(result.winner, Some(1));
+        assert_eq!(result.votes_tally[0].option_index, 1);
+        assert_eq!(result.votes_tally[0].net_votes, 6);
+        assert_eq!(result.votes_tally[0].credits_spent, 14);
+
+        let a = result.votes_tally.iter().find(|i| i.option_index == 0);
+        let a = a.unwrap();
+        assert_eq!(a.net_votes, 1);
+        assert_eq!(a.votes_for, 4);
+        assert_eq!(a.votes_against, 3);
+        assert_eq!(a.credits_spent, 25);
+        assert_eq!(result.credits_spent, 44);
+
+        let outcome = Quadratic::outcome(&result);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![2], vec![0]]);
+    }
+}
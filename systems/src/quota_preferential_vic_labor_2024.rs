@@ -1,23 +1,51 @@
-use std::{collections::HashMap, fmt::format};
+use std::{collections::HashMap, fmt, str::FromStr};
 
 use serde_derive::{Deserialize, Serialize};
 
+use crate::{
+    numeric::{Arithmetic, Number, Rounding},
+    tie_break::{borda_from_rankings, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, total_weight, weight_scale, weighted, Weight},
+};
+
 const PAPER_SCORE: i64 = 1000;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Vote {
-    created_by: uuid::Uuid,
-    votes: Vec<usize>,
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Candidates in order of preference, voters may stop numbering at any
+    /// point
+    pub votes: Vec<usize>,
 }
 
+impl_weighted_ballot!(Vote);
+
 impl Vote {
     pub fn new(created_by: uuid::Uuid, votes: Vec<usize>) -> Vote {
-        Vote { created_by, votes }
+        Vote {
+            created_by,
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    pub fn with_weight(self, weight: Weight) -> Vote {
+        Vote { weight, ..self }
+    }
+
+    /// The paper's weight as a whole number, once `get_result` has scaled
+    /// every weight by the common denominator
+    fn units(&self) -> i64 {
+        self.weight.numerator as i64
     }
 
     pub fn new_unknown(votes: Vec<usize>) -> Vote {
         Vote {
             created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
             votes,
         }
     }
@@ -49,23 +77,141 @@ impl Candidate {
     }
 }
 
+/// How the quota is worked out from the formal votes, each counted as
+/// 1,000 points
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quota {
+    /// Points over vacancies plus one, taken to the next whole figure as
+    /// rule 2.3 says
+    #[default]
+    DroopRoundedUp,
+    /// Points over vacancies plus one, rounded down, plus one
+    DroopPlusOne,
+    /// Points over vacancies
+    Hare,
+    /// Points over vacancies plus one, a candidate must go over it to be
+    /// elected
+    HagenbachBischoff,
+}
+
+impl Quota {
+    pub fn value(&self, arithmetic: &Arithmetic, points: i64, vacancies: usize) -> Number {
+        let vacancies = vacancies as i64;
+        match self {
+            Quota::DroopRoundedUp => Number::from((points + vacancies) / (vacancies + 1)),
+            Quota::DroopPlusOne => Number::from(points / (vacancies + 1) + 1),
+            Quota::Hare => arithmetic.round(&Number::new(points, vacancies)),
+            Quota::HagenbachBischoff => arithmetic.round(&Number::new(points, vacancies + 1)),
+        }
+    }
+
+    pub fn is_reached(&self, score: &Number, quota: &Number) -> bool {
+        match self {
+            Quota::HagenbachBischoff => score > quota,
+            _ => score >= quota,
+        }
+    }
+
+    /// The name the quota is stored under, `Display` gives the one the
+    /// decision log reads
+    pub fn key(&self) -> &'static str {
+        match self {
+            Quota::DroopRoundedUp => "droop_rounded_up",
+            Quota::DroopPlusOne => "droop_plus_one",
+            Quota::Hare => "hare",
+            Quota::HagenbachBischoff => "hagenbach_bischoff",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQuotaError;
+
+impl fmt::Display for ParseQuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Quota must be droop_rounded_up, droop_plus_one, hare or hagenbach_bischoff"
+        )
+    }
+}
+
+impl std::error::Error for ParseQuotaError {}
+
+/// Parses the name from `Quota::key`
+impl FromStr for Quota {
+    type Err = ParseQuotaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "droop_rounded_up" => Quota::DroopRoundedUp,
+            "droop_plus_one" => Quota::DroopPlusOne,
+            "hare" => Quota::Hare,
+            "hagenbach_bischoff" => Quota::HagenbachBischoff,
+            _ => return Err(ParseQuotaError),
+        })
+    }
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quota::DroopRoundedUp => write!(f, "Droop quota rounded up"),
+            Quota::DroopPlusOne => write!(f, "Droop quota plus one"),
+            Quota::Hare => write!(f, "Hare quota"),
+            Quota::HagenbachBischoff => write!(f, "Hagenbach-Bischoff quota"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Election {
     pub candidates: Vec<Candidate>,
     pub percent_female: f64,
     pub elected_count: usize,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    /// Under `TieBreak::Method` equalities the rules leave to the Returning
+    /// Officer are settled alphabetically
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// How the quota and surplus transfer values are rounded
+    #[serde(default = "Election::default_arithmetic")]
+    pub arithmetic: Arithmetic,
+    #[serde(default)]
+    pub quota: Quota,
 }
 
 impl Election {
-    pub fn quota<T: Into<usize>>(&self, total_votes: T) -> i64 {
-        ((total_votes.into() * PAPER_SCORE as usize) / (self.candidates.len() + 1)) as i64
+    /// The rules count whole points and drop the fractional part of a
+    /// transfer value
+    pub const WHOLE_POINTS: Arithmetic = Arithmetic::FixedPoint {
+        places: 0,
+        rounding: Rounding::Down,
+    };
+
+    fn default_arithmetic() -> Arithmetic {
+        Election::WHOLE_POINTS
+    }
+
+    pub fn quota<T: Into<usize>>(&self, total_votes: T) -> Number {
+        self.quota.value(
+            &self.arithmetic,
+            total_votes.into() as i64 * PAPER_SCORE,
+            self.elected_count,
+        )
+    }
+
+    fn reaches_quota(&self, score: &Number, quota: &Number) -> bool {
+        self.quota.is_reached(score, quota)
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Paper {
     pub vote: Vote,
-    pub value: i64,
+    pub value: Number,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -73,8 +219,11 @@ pub struct CountSheetEntry {
     pub option_index: usize,
     pub papers: Vec<Paper>,
     pub separated_papers: Vec<uuid::Uuid>,
-    pub transfer_value: i64,
-    pub remainders: i64,
+    /// The value of the last surplus transferred from this candidate
+    pub transfer_value: Number,
+    /// Points lost to rounding over every surplus transferred from this
+    /// candidate
+    pub remainders: Number,
 }
 
 impl CountSheetEntry {
@@ -83,24 +232,38 @@ impl CountSheetEntry {
             option_index,
             papers: vec![],
             separated_papers: vec![],
-            transfer_value: 0,
-            remainders: 0,
+            transfer_value: Number::zero(),
+            remainders: Number::zero(),
         }
     }
 
-    pub fn score(&self) -> i64 {
-        self.papers.iter().map(|i| i.value).sum::<i64>()
+    pub fn score(&self) -> Number {
+        self.papers.iter().map(|i| i.value.clone()).sum()
     }
 
-    pub fn add_vote(&mut self, vote: Vote, value: i64) {
+    pub fn add_vote(&mut self, vote: Vote, value: Number) {
         self.papers.push(Paper { vote, value });
     }
 }
 
+/// One surplus passed on at a single transfer value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SurplusTransfer {
+    pub from: usize,
+    pub surplus: Number,
+    /// Papers the surplus was shared between, exhausted papers included
+    pub papers: i64,
+    pub transfer_value: Number,
+    /// The part of the surplus the rounded transfer value didn't carry
+    pub rounding_loss: Number,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CountSheet {
     pub entries: HashMap<usize, CountSheetEntry>,
     pub exhausted_papers: Vec<Paper>,
+    #[serde(default)]
+    pub transfers: Vec<SurplusTransfer>,
 }
 
 impl CountSheet {
@@ -108,6 +271,7 @@ impl CountSheet {
         CountSheet {
             entries: HashMap::from_iter(candidates.iter().map(|i| (*i, CountSheetEntry::new(*i)))),
             exhausted_papers: vec![],
+            transfers: vec![],
         }
     }
 
@@ -119,7 +283,7 @@ impl CountSheet {
         self.entries.get_mut(&option_index).unwrap()
     }
 
-    pub fn top_score(&self, candidates: &[usize]) -> i64 {
+    pub fn top_score(&self, candidates: &[usize]) -> Number {
         self.entries
             .iter()
             .filter(|i| candidates.contains(i.0))
@@ -128,7 +292,7 @@ impl CountSheet {
             .unwrap()
     }
 
-    pub fn lowest_score(&self, candidates: &[usize]) -> i64 {
+    pub fn lowest_score(&self, candidates: &[usize]) -> Number {
         self.entries
             .iter()
             .filter(|i| candidates.contains(i.0))
@@ -137,27 +301,27 @@ impl CountSheet {
             .unwrap()
     }
 
-    pub fn matching_score(&self, candidates: &[usize], score: i64) -> Vec<usize> {
+    pub fn matching_score(&self, candidates: &[usize], score: &Number) -> Vec<usize> {
         self.entries
             .iter()
-            .filter(|i| i.1.score() == score && candidates.contains(i.0))
+            .filter(|i| i.1.score() == *score && candidates.contains(i.0))
             .map(|i| *i.0)
             .collect()
     }
 
-    pub fn add_vote(&mut self, option_index: usize, vote: Vote, value: i64) {
+    pub fn add_vote(&mut self, option_index: usize, vote: Vote, value: Number) {
         self.entries
             .get_mut(&option_index)
             .unwrap()
             .add_vote(vote, value);
     }
 
-    pub fn transfer_paper(&mut self, from: usize, to: usize, paper: &Paper, value: i64) {
+    pub fn transfer_paper(&mut self, from: usize, to: usize, paper: &Paper, value: Number) {
         let real_paper = self.remove_paper(from, paper);
         self.add_vote(to, real_paper.vote, value);
     }
 
-    pub fn transfer_paper_4_2(&mut self, from: usize, to: usize, paper: &Paper, value: i64) {
+    pub fn transfer_paper_4_2(&mut self, from: usize, to: usize, paper: &Paper, value: Number) {
         let real_paper = self.remove_paper(from, paper);
         self.entries
             .get_mut(&from)
@@ -182,6 +346,31 @@ impl CountSheet {
         let real_paper = self.remove_paper(option_index, paper);
         self.exhausted_papers.push(real_paper);
     }
+
+    /// Works out the value each of `papers` carries from `from`'s surplus,
+    /// keeping what rounding lost against the candidate
+    pub fn record_transfer(
+        &mut self,
+        arithmetic: &Arithmetic,
+        from: usize,
+        surplus: Number,
+        papers: i64,
+    ) -> SurplusTransfer {
+        let rounded = arithmetic.divide(&surplus, &Number::from(papers));
+        let transfer = SurplusTransfer {
+            from,
+            rounding_loss: &rounded.loss * &Number::from(papers),
+            surplus,
+            papers,
+            transfer_value: rounded.value,
+        };
+
+        let entry = self.get_mut(from);
+        entry.transfer_value = transfer.transfer_value.clone();
+        entry.remainders += transfer.rounding_loss.clone();
+        self.transfers.push(transfer.clone());
+        transfer
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -252,23 +441,76 @@ pub struct VoteBundle {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ElectionResult {
     pub elected_candidates: Vec<usize>,
-    pub quota: i64,
+    pub quota: Number,
     pub count_sheet: CountSheetCollection,
     pub decision_log: DecisionLog,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Picks one of the candidates level on `active_cs`, the tally is kept as a
+/// round for the tie break to count back through
+fn break_tie(
+    tie_breaker: &mut TieBreaker,
+    decision_log: &mut DecisionLog,
+    election: &Election,
+    active_cs: &CountSheet,
+    kind: TieKind,
+    tied: &[usize],
+    rule: &str,
+) -> usize {
+    tie_breaker.record_round((0..election.candidates.len()).map(|i| {
+        active_cs
+            .entries
+            .get(&i)
+            .map_or(0.0, |i| i.score().to_f64())
+    }));
+
+    let mut tied = tied.to_vec();
+    tied.sort();
+    if tied.len() > 1 {
+        match tie_breaker.policy() {
+            TieBreak::Method => decision_log.add(format!("{} selecting alphabetically", rule)),
+            policy => decision_log.add(format!("{} breaking the tie by {}", rule, policy)),
+        }
+    }
+    tie_breaker.break_tie(kind, &tied, |_| {
+        *tied
+            .iter()
+            .min_by(|a, b| {
+                election.candidates[**a]
+                    .name
+                    .cmp(&election.candidates[**b].name)
+            })
+            .unwrap()
+    })
+}
+
+fn log_transfer_value(decision_log: &mut DecisionLog, transfer: &SurplusTransfer) {
+    decision_log.add(format!(
+        "Transfer value for $C{} is {} over {} papers",
+        transfer.from, transfer.transfer_value, transfer.papers
+    ));
+    if !transfer.rounding_loss.is_zero() {
+        decision_log.add(format!(
+            "Rounding the transfer value of $C{} lost {} points",
+            transfer.from, transfer.rounding_loss
+        ));
+    }
 }
 
 fn distribute_surplus(
     decision_log: &mut DecisionLog,
     active_cs: &mut CountSheet,
-    quota: i64,
+    arithmetic: &Arithmetic,
+    quota: &Number,
     continuing_candidates: &[usize],
     to_distribute: usize,
 ) {
     let mut papers = active_cs.get_mut(to_distribute).papers.clone();
 
     papers.sort_by(|a, b| {
-        let a_next_preference = a.vote.top_continuing_preference(&continuing_candidates);
-        let b_next_preference = b.vote.top_continuing_preference(&continuing_candidates);
+        let a_next_preference = a.vote.top_continuing_preference(continuing_candidates);
+        let b_next_preference = b.vote.top_continuing_preference(continuing_candidates);
         a_next_preference.cmp(&b_next_preference)
     });
 
@@ -278,8 +520,8 @@ fn distribute_surplus(
     ));
 
     let score = active_cs.get_mut(to_distribute).score();
-    let surplus = score - quota as i64;
-    if surplus <= 0 {
+    let surplus = &score - quota;
+    if !surplus.is_positive() {
         decision_log.add(format!(
             "As per 3.7 no surplus for $C{} meaning nothing to do",
             to_distribute
@@ -287,18 +529,26 @@ fn distribute_surplus(
         return;
     }
 
-    let total_ballots = papers.len() as i64;
+    let total_ballots = papers.iter().map(|i| i.vote.units()).sum::<i64>();
     // Divide the surplus by the number of papers, including the exhaustive papers
-    // Whole number part is the transfer value
-    let transfer_value = surplus / total_ballots;
+    // The transfer value is rounded as the election's arithmetic says
+    let transfer = active_cs.record_transfer(arithmetic, to_distribute, surplus, total_ballots);
+    log_transfer_value(decision_log, &transfer);
+    let transfer_value = transfer.transfer_value;
 
     let mut transfer_tally = HashMap::new();
     for paper in papers {
-        if let Some(next_top) = paper.vote.top_continuing_preference(&continuing_candidates) {
-            *transfer_tally.entry(next_top as i32).or_insert(0) += 1;
-            active_cs.transfer_paper(to_distribute, next_top, &paper, transfer_value);
+        let units = paper.vote.units();
+        if let Some(next_top) = paper.vote.top_continuing_preference(continuing_candidates) {
+            *transfer_tally.entry(next_top as i32).or_insert(0) += units;
+            active_cs.transfer_paper(
+                to_distribute,
+                next_top,
+                &paper,
+                &transfer_value * &Number::from(units),
+            );
         } else {
-            *transfer_tally.entry(-1).or_insert(0) += 1;
+            *transfer_tally.entry(-1).or_insert(0) += units;
             active_cs.exhaust_paper(to_distribute, &paper);
         }
     }
@@ -310,14 +560,16 @@ fn distribute_surplus(
             decision_log.add(format!(
                 "Transferred {}({}) votes to $C{}",
                 count,
-                count * transfer_value,
+                &transfer_value * &Number::from(count),
                 candidate
             ));
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn fill_aa_requirements(
+    tie_breaker: &mut TieBreaker,
     decision_log: &mut DecisionLog,
     election: &Election,
     votes: &[Vote],
@@ -325,6 +577,7 @@ fn fill_aa_requirements(
     defeated_candidates: &[usize],
     active_cs: &mut CountSheet,
     backup_candidates: usize,
+    seated_anyway: &[usize],
 ) -> Vec<usize> {
     decision_log.add("Filling AA requirements");
 
@@ -346,8 +599,13 @@ fn fill_aa_requirements(
        7.2. Exclude the remaining male candidates
     */
 
+    // Women the count seats anyway can't also fill the place being taken
     let mut continuing_candidates = (0..election.candidates.len())
-        .filter(|i| election.candidates[*i].is_female && !elected_candidates.contains(i))
+        .filter(|i| {
+            election.candidates[*i].is_female
+                && !elected_candidates.contains(i)
+                && !seated_anyway.contains(i)
+        })
         .collect::<Vec<_>>();
     let mut newly_elected = vec![];
     decision_log.add(format!(
@@ -367,7 +625,7 @@ fn fill_aa_requirements(
         they had earlier in the count), then re-introduce any defeated female candidates into the
         count at a zero number of points.
     */
-    let quota = election.quota(votes.len());
+    let quota = election.quota(total_weight(votes));
 
     /*
         7.5. Distribute any surpluses not previously distributed from the already elected candidates, in
@@ -381,30 +639,36 @@ fn fill_aa_requirements(
 
         let entry = active_cs.get(elected_candidate);
 
-        let surplus = entry.score() - quota as i64;
+        let surplus = &entry.score() - &quota;
 
-        if surplus <= 0 {
+        if !surplus.is_positive() {
             continue;
         }
 
-        let total_ballots = entry.papers.len() as i64;
-        // Divide the surplus by the number of papers, including the exhaustive papers
-        // Whole number part is the transfer value
-        let transfer_value = surplus / total_ballots;
-
+        let total_ballots = entry.papers.iter().map(|i| i.vote.units()).sum::<i64>();
         let papers = entry.papers.clone();
         decision_log.add(format!(
             "As per 7.5 distributing surplus of $C{} with a surplus of {}",
             elected_candidate, surplus
         ));
+        // Divide the surplus by the number of papers, including the exhaustive papers
+        let transfer = active_cs.record_transfer(
+            &election.arithmetic,
+            elected_candidate,
+            surplus,
+            total_ballots,
+        );
+        log_transfer_value(decision_log, &transfer);
+        let transfer_value = transfer.transfer_value;
 
         for paper in papers {
             if let Some(next_top) = paper.vote.top_continuing_preference(&continuing_candidates) {
-                active_cs.transfer_paper(elected_candidate, next_top, &paper, transfer_value);
-                *transfer_log.entry(next_top).or_insert(0) += transfer_value;
+                let value = &transfer_value * &Number::from(paper.vote.units());
+                active_cs.transfer_paper(elected_candidate, next_top, &paper, value.clone());
+                *transfer_log.entry(next_top).or_insert_with(Number::zero) += value;
 
                 // as per 7.5 stop if they reach the quota
-                if active_cs.get(next_top).score() >= quota {
+                if election.reaches_quota(&active_cs.get(next_top).score(), &quota) {
                     decision_log.add(format!("Candidate $C{} has reached the quota", next_top));
                     newly_elected.push(next_top);
                     continuing_candidates.retain(|i| *i != next_top);
@@ -443,7 +707,7 @@ fn fill_aa_requirements(
             active_cs.add_vote(next_top, paper.vote.clone(), paper.value);
 
             // as per 7.6 stop if they reach the quota
-            if active_cs.get(next_top).score() >= quota {
+            if election.reaches_quota(&active_cs.get(next_top).score(), &quota) {
                 decision_log.add(format!("Candidate $C{} has reached the quota", next_top));
                 newly_elected.push(next_top);
                 continuing_candidates.retain(|i| *i != next_top);
@@ -482,11 +746,11 @@ fn fill_aa_requirements(
         let papers = active_cs.get(candidate).papers.clone();
         for paper in papers {
             if let Some(next_top) = paper.vote.top_continuing_preference(&continuing_candidates) {
-                active_cs.transfer_paper(candidate, next_top, &paper, paper.value);
-                *transfer_log.entry(next_top).or_insert(0) += paper.value;
+                active_cs.transfer_paper(candidate, next_top, &paper, paper.value.clone());
+                *transfer_log.entry(next_top).or_insert_with(Number::zero) += paper.value;
 
                 // as per 7.7 stop if they reach the quota
-                if active_cs.get(next_top).score() >= quota {
+                if election.reaches_quota(&active_cs.get(next_top).score(), &quota) {
                     decision_log.add(format!("Candidate $C{} has reached the quota", next_top));
                     newly_elected.push(next_top);
                     continuing_candidates.retain(|i| *i != next_top);
@@ -522,7 +786,7 @@ fn fill_aa_requirements(
     */
     decision_log.add("As per 7.8 re-distributing papers of non elected male candidates");
     let excluded_male_cc = (0..election.candidates.len())
-        .filter(|i| !elected_candidates.contains(&i) && !election.candidates[*i].is_female)
+        .filter(|i| !elected_candidates.contains(i) && !election.candidates[*i].is_female)
         .collect::<Vec<_>>();
     for candidate in excluded_male_cc {
         let mut transfer_log = HashMap::new();
@@ -530,11 +794,11 @@ fn fill_aa_requirements(
         let papers = active_cs.get(candidate).papers.clone();
         for paper in papers {
             if let Some(next_top) = paper.vote.top_continuing_preference(&continuing_candidates) {
-                active_cs.transfer_paper(candidate, next_top, &paper, paper.value);
-                *transfer_log.entry(next_top).or_insert(0) += paper.value;
+                active_cs.transfer_paper(candidate, next_top, &paper, paper.value.clone());
+                *transfer_log.entry(next_top).or_insert_with(Number::zero) += paper.value;
 
                 // as per 7.8 stop if they reach the quota
-                if active_cs.get(next_top).score() >= quota {
+                if election.reaches_quota(&active_cs.get(next_top).score(), &quota) {
                     decision_log.add(format!("Candidate $C{} has reached the quota", next_top));
                     newly_elected.push(next_top);
                     continuing_candidates.retain(|i| *i != next_top);
@@ -555,7 +819,7 @@ fn fill_aa_requirements(
         distribution of any surpluses of the women elected by virtue of the Affirmative Action
         provisions.
     */
-    if newly_elected.len() > 0 {
+    if !newly_elected.is_empty() {
         decision_log.add(format!(
             "The following females have reached the quota $C{:?}",
             newly_elected
@@ -568,8 +832,8 @@ fn fill_aa_requirements(
 
         let continuing_candidates: Vec<_> = get_continuing_candidates(
             election.candidates.len(),
-            &elected_candidates,
-            &defeated_candidates,
+            elected_candidates,
+            defeated_candidates,
         )
         .into_iter()
         .filter(|i| !newly_elected.contains(i))
@@ -580,7 +844,8 @@ fn fill_aa_requirements(
                 distribute_surplus(
                     decision_log,
                     active_cs,
-                    quota,
+                    &election.arithmetic,
+                    &quota,
                     &continuing_candidates,
                     *candidate,
                 );
@@ -608,16 +873,79 @@ fn fill_aa_requirements(
             ));
         }
     } else {
-        decision_log.add(
+        /*
+            7.10. Continue the count in accordance with clauses 3 and 4 of this Schedule.
+        */
+        // The male candidates stay excluded, so as in 4.2 the lowest female
+        // candidate is defeated until one reaches the quota or only one is left
+        decision_log.add("As per 7.10 no female candidate reached the quota continuing the count");
+        while continuing_candidates.len() > 1 {
+            let top_score = active_cs.top_score(&continuing_candidates);
+            if election.reaches_quota(&top_score, &quota) {
+                break;
+            }
+
+            let lowest_score = active_cs.lowest_score(&continuing_candidates);
+            let candidates_with_lowest_score =
+                active_cs.matching_score(&continuing_candidates, &lowest_score);
+            let defeated_candidate = break_tie(
+                tie_breaker,
+                decision_log,
+                election,
+                active_cs,
+                TieKind::Exclude,
+                &candidates_with_lowest_score,
+                "as per 4.5 Multiple candidates with the same score",
+            );
+            decision_log.add(format!(
+                "Defeated candidate selected: $C{}",
+                defeated_candidate
+            ));
+            continuing_candidates.retain(|i| *i != defeated_candidate);
+
+            for paper in active_cs.get(defeated_candidate).papers.clone() {
+                if let Some(next_top) = paper.vote.top_continuing_preference(&continuing_candidates)
+                {
+                    active_cs.transfer_paper_4_2(
+                        defeated_candidate,
+                        next_top,
+                        &paper,
+                        paper.value.clone(),
+                    );
+                }
+            }
+        }
+
+        if continuing_candidates.is_empty() {
+            decision_log.add(
 format!(
-                "After an attempt of following the AA requirements in 7 no candidates were elected in there place electing the original non aa candidate $C{}",
-                backup_candidates
-            )
-        );
-        newly_elected.push(backup_candidates);
+                    "After an attempt of following the AA requirements in 7 no candidates were elected in there place electing the original non aa candidate $C{}",
+                    backup_candidates
+                )
+            );
+            newly_elected.push(backup_candidates);
+        } else {
+            let top_score = active_cs.top_score(&continuing_candidates);
+            let candidates_with_top_score =
+                active_cs.matching_score(&continuing_candidates, &top_score);
+            let elected = break_tie(
+                tie_breaker,
+                decision_log,
+                election,
+                active_cs,
+                TieKind::Elect,
+                &candidates_with_top_score,
+                "Multiple candidates with the same score",
+            );
+            decision_log.add(format!(
+                "As per 7.10 candidate $C{} elected with a score of {}",
+                elected, top_score
+            ));
+            newly_elected.push(elected);
+        }
     }
 
-    return newly_elected;
+    newly_elected
 }
 
 fn get_continuing_candidates(
@@ -632,6 +960,12 @@ fn get_continuing_candidates(
 
 pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
     let mut decision_log = DecisionLog::new(election);
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_rankings(
+            election.candidates.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.as_slice(), weight)),
+        )
+    });
 
     if election.candidates.len() <= election.elected_count {
         decision_log.add(
@@ -639,12 +973,13 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         );
         return ElectionResult {
             elected_candidates: (0..election.candidates.len()).collect::<Vec<_>>(),
-            quota: 0,
+            quota: Number::zero(),
             count_sheet: CountSheetCollection {
                 count_sheets: vec![],
                 aa_count_sheets: vec![],
             },
             decision_log,
+            ties: tie_breaker.into_ties(),
         };
     }
 
@@ -692,7 +1027,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
 
     let mut elected_candidates: Vec<usize> = vec![];
     let mut defeated_candidates: Vec<usize> = vec![];
-    let mut aa_round_sheets = vec![];
+    let aa_round_sheets = vec![];
 
     /*
         2.2 Sort the formal papers according to the first preferences shown for each candidate and record
@@ -704,7 +1039,16 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
 
         AKA sort papers by first preference and record the tallies
     */
-    let mut votes = votes.to_vec();
+    // Weights are scaled to whole numbers so each paper is worth 1,000 points
+    // for every unit of weight
+    let scale = weight_scale(votes);
+    let mut votes = votes
+        .iter()
+        .map(|i| {
+            i.clone()
+                .with_weight(Weight::whole(i.weight.scaled(scale) as u64))
+        })
+        .collect::<Vec<_>>();
 
     let mut active_cs = CountSheet::new(&(0..election.candidates.len()).collect::<Vec<_>>());
 
@@ -718,7 +1062,11 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
     // Record the tallies
     for vote in &votes {
         let first_preference = vote.votes[0];
-        active_cs.add_vote(first_preference, vote.clone(), PAPER_SCORE);
+        active_cs.add_vote(
+            first_preference,
+            vote.clone(),
+            Number::from(PAPER_SCORE * vote.units()),
+        );
     }
 
     /*
@@ -730,10 +1078,13 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
        Note: this formula provides that only the required number of vacancies can receive a “quota” of points.
     */
 
-    let total_votes = votes.len() as i64;
+    let total_votes = total_weight(&votes) as i64;
     let quota = election.quota(total_votes as usize);
 
-    decision_log.add(format!("Total votes: {} Quota: {}", total_votes, quota));
+    decision_log.add(format!(
+        "Total votes: {} Quota: {} by the {}",
+        total_votes, quota, election.quota
+    ));
 
     let elected_female_count = |elected_candidates: &[usize]| {
         elected_candidates
@@ -746,7 +1097,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         let females_needed = min_female_positions as i64 - female_count;
         let candidates_needed =
             election.elected_count as i64 - (elected_candidates.len() as i64 + 1);
-        return candidates_needed >= females_needed;
+        candidates_needed >= females_needed
     };
     let get_continuing_candidates =
         |elected_candidates: &[usize], defeated_candidates: &[usize]| {
@@ -781,29 +1132,25 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         // Is the top candidate over quota? and a woman?
         // The top candidate has reached quota
         // As per 2.4 this needs to be equal or exceed the quota
-        if top_score >= quota {
-            let mut candidates_with_top_score: Vec<_> =
-                active_cs.matching_score(&continuing_candidates, top_score);
+        if election.reaches_quota(&top_score, &quota) {
+            let candidates_with_top_score: Vec<_> =
+                active_cs.matching_score(&continuing_candidates, &top_score);
 
             decision_log.add(format!(
                 "Top score: {} held by $C{:?} is over the quota",
                 top_score, candidates_with_top_score
             ));
 
-            if candidates_with_top_score.len() > 1 {
-                decision_log
-                    .add("Multiple candidates with the same score selecting alphabetically");
-                // As per 2.4 where is an equality of the value of first preference papers, the Returning Officer shall determine the order by lot.
-                // To simulate this we just sort the candidates alphabetically
-                // Change to always pick a female for simplicity
-                candidates_with_top_score.sort_by(|a, b| {
-                    election.candidates[*a]
-                        .name
-                        .cmp(&election.candidates[*b].name)
-                });
-            }
-
-            let top_candidate = candidates_with_top_score[0];
+            // As per 2.4 where is an equality of the value of first preference papers, the Returning Officer shall determine the order by lot.
+            let top_candidate = break_tie(
+                &mut tie_breaker,
+                &mut decision_log,
+                election,
+                &active_cs,
+                TieKind::Elect,
+                &candidates_with_top_score,
+                "Multiple candidates with the same score",
+            );
             decision_log.add(format!("Top candidate selected: $C{}", top_candidate));
 
             // Ref 7.1
@@ -830,10 +1177,11 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             elected_candidates,
             quota,
             count_sheet: CountSheetCollection {
-                count_sheets: vec![],
+                count_sheets: vec![active_cs],
                 aa_count_sheets: aa_round_sheets,
             },
             decision_log,
+            ties: tie_breaker.into_ties(),
         };
     }
 
@@ -850,7 +1198,6 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
     */
 
     let mut aa_attempted = false;
-    let mut count_sheet_entry_runoffs: Vec<CountSheet> = vec![];
 
     let mut removed_votes: Vec<uuid::Uuid> = vec![];
 
@@ -877,17 +1224,16 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         */
 
         let top_score = active_cs.top_score(&to_redistributed);
-        let mut highest_scoring_candidates = active_cs.matching_score(&to_redistributed, top_score);
-        if highest_scoring_candidates.len() > 1 {
-            decision_log.add("In 3.1 it does not specify how to break ties if two candidates have the same top score just picking alphabetically");
-            highest_scoring_candidates.sort_by(|a, b| {
-                election.candidates[*a]
-                    .name
-                    .cmp(&election.candidates[*b].name)
-            });
-        }
-
-        let top_candidate = highest_scoring_candidates[0];
+        let highest_scoring_candidates = active_cs.matching_score(&to_redistributed, &top_score);
+        let top_candidate = break_tie(
+            &mut tie_breaker,
+            &mut decision_log,
+            election,
+            &active_cs,
+            TieKind::Elect,
+            &highest_scoring_candidates,
+            "In 3.1 it does not specify how to break ties if two candidates have the same top score",
+        );
         to_redistributed.retain(|i| *i != top_candidate);
 
         decision_log.add(format!(
@@ -900,7 +1246,8 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         distribute_surplus(
             &mut decision_log,
             &mut active_cs,
-            quota,
+            &election.arithmetic,
+            &quota,
             &continuing_candidates,
             top_candidate,
         );
@@ -913,7 +1260,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             their surpluses dealt with first, even if theirs are larger.
         */
         let top_score = active_cs.top_score(&continuing_candidates);
-        if top_score < quota {
+        if !election.reaches_quota(&top_score, &quota) {
             decision_log.add(format!(
                 "As per 3.6 no candidates over quota with the top score: {} meaning nothing to do",
                 top_score
@@ -921,24 +1268,23 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             continue;
         }
 
-        let mut candidates_with_top_score: Vec<_> =
-            active_cs.matching_score(&continuing_candidates, top_score);
+        let candidates_with_top_score: Vec<_> =
+            active_cs.matching_score(&continuing_candidates, &top_score);
         decision_log.add(format!(
             "Top score: {} held by $C{:?}",
             top_score, candidates_with_top_score
         ));
 
-        if candidates_with_top_score.len() > 1 {
-            // As per 3.5 If two surpluses are equal, the Returning Officer must decide which to take first.
-            decision_log.add("Multiple candidates with the same score selecting alphabetically");
-            candidates_with_top_score.sort_by(|a, b| {
-                election.candidates[*a]
-                    .name
-                    .cmp(&election.candidates[*b].name)
-            });
-        }
-
-        let top_candidate = candidates_with_top_score[0];
+        // As per 3.5 If two surpluses are equal, the Returning Officer must decide which to take first.
+        let top_candidate = break_tie(
+            &mut tie_breaker,
+            &mut decision_log,
+            election,
+            &active_cs,
+            TieKind::Elect,
+            &candidates_with_top_score,
+            "Multiple candidates with the same score",
+        );
         let just_elected = if !election.candidates[top_candidate].is_female
             && !aa_possible(&elected_candidates)
         {
@@ -953,6 +1299,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             } else {
                 active_cs = saved_cs;
                 let elected = fill_aa_requirements(
+                    &mut tie_breaker,
                     &mut decision_log,
                     election,
                     &votes,
@@ -960,6 +1307,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                     &defeated_candidates,
                     &mut active_cs,
                     top_candidate,
+                    &[],
                 );
                 aa_attempted = true;
                 elected
@@ -979,10 +1327,11 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                 elected_candidates,
                 quota,
                 count_sheet: CountSheetCollection {
-                    count_sheets: count_sheet_entry_runoffs,
+                    count_sheets: vec![active_cs],
                     aa_count_sheets: aa_round_sheets,
                 },
                 decision_log,
+                ties: tie_breaker.into_ties(),
             };
         }
     }
@@ -1001,7 +1350,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
 
     decision_log.add("As per 4.1 checking for candidates with no points");
     for candidate in continuing_candidates.clone() {
-        if active_cs.get(candidate).score() == 0 {
+        if active_cs.get(candidate).score().is_zero() {
             decision_log.add(format!(
                 "Candidate $C{} has no points marking as defeated",
                 candidate
@@ -1026,10 +1375,11 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             elected_candidates,
             quota,
             count_sheet: CountSheetCollection {
-                count_sheets: count_sheet_entry_runoffs,
+                count_sheets: vec![active_cs],
                 aa_count_sheets: aa_round_sheets,
             },
             decision_log,
+            ties: tie_breaker.into_ties(),
         };
     }
 
@@ -1078,15 +1428,15 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                 ));
                 continuing_candidates[1]
             } else {
-                decision_log.add("Scores are equal selecting alphabetically Once again rules do not say what to do here");
-                // Pick based on name
-                let a_name = &election.candidates[continuing_candidates[0]].name;
-                let b_name = &election.candidates[continuing_candidates[1]].name;
-                if a_name < b_name {
-                    continuing_candidates[0]
-                } else {
-                    continuing_candidates[1]
-                }
+                break_tie(
+                    &mut tie_breaker,
+                    &mut decision_log,
+                    election,
+                    &active_cs,
+                    TieKind::Elect,
+                    &continuing_candidates[..2],
+                    "Scores are equal, once again the rules do not say what to do here",
+                )
             };
 
             if !election.candidates[elected].is_female && !aa_possible(&elected_candidates) {
@@ -1095,6 +1445,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                     elected
                 ));
                 let aa_elected = fill_aa_requirements(
+                    &mut tie_breaker,
                     &mut decision_log,
                     election,
                     &votes,
@@ -1102,6 +1453,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                     &defeated_candidates,
                     &mut active_cs,
                     elected,
+                    &[],
                 );
                 elected_candidates.extend(aa_elected);
             } else {
@@ -1149,6 +1501,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                     "Sorted continuing candidates by score $C{:?}",
                     continuing_candidates,
                 ));
+                let seated_anyway = continuing_candidates.clone();
 
                 for candidate in continuing_candidates {
                     if !election.candidates[candidate].is_female
@@ -1159,6 +1512,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                             candidate
                         ));
                         let aa_elected = fill_aa_requirements(
+                            &mut tie_breaker,
                             &mut decision_log,
                             election,
                             &votes,
@@ -1166,6 +1520,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                             &defeated_candidates,
                             &mut active_cs,
                             candidate,
+                            &seated_anyway,
                         );
                         elected_candidates.extend(aa_elected);
                     } else {
@@ -1203,8 +1558,8 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             get_continuing_candidates(&elected_candidates, &defeated_candidates);
 
         let lowest_score = active_cs.lowest_score(&continuing_candidates);
-        let mut candidates_with_lowest_score =
-            active_cs.matching_score(&continuing_candidates, lowest_score);
+        let candidates_with_lowest_score =
+            active_cs.matching_score(&continuing_candidates, &lowest_score);
 
         decision_log.add(format!(
             "as per 4.2 the candidate with the lowest score {} total of points is removed with the candidates $C{:?}",
@@ -1216,17 +1571,15 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             by lot which candidate is to be eliminated first.
         */
         // as per other sections where is an equality of the value of first preference papers, the Returning Officer shall determine the order by lot.
-        if candidates_with_lowest_score.len() > 1 {
-            decision_log
-                .add("as per 4.5 Multiple candidates with the same score selecting alphabetically");
-            candidates_with_lowest_score.sort_by(|a, b| {
-                election.candidates[*a]
-                    .name
-                    .cmp(&election.candidates[*b].name)
-            });
-        }
-
-        let defeated_candidate = candidates_with_lowest_score[0];
+        let defeated_candidate = break_tie(
+            &mut tie_breaker,
+            &mut decision_log,
+            election,
+            &active_cs,
+            TieKind::Exclude,
+            &candidates_with_lowest_score,
+            "as per 4.5 Multiple candidates with the same score",
+        );
 
         decision_log.add(format!(
             "Defeated candidate selected: $C{}",
@@ -1249,7 +1602,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             // As per 4.4 do not allocate more papers to the candidate who has reached the quota
             let cc_needing_votes = continuing_candidates
                 .iter()
-                .filter(|i| active_cs.get(**i).score() < quota)
+                .filter(|i| !election.reaches_quota(&active_cs.get(**i).score(), &quota))
                 .cloned()
                 .collect::<Vec<_>>();
 
@@ -1257,7 +1610,12 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
             if let Some(next_top) = paper.vote.top_continuing_preference(&cc_needing_votes) {
                 // papers need to be kept separate
                 *transfer_tally.entry(next_top).or_insert(0) += 1;
-                active_cs.transfer_paper_4_2(defeated_candidate, next_top, &paper, paper.value);
+                active_cs.transfer_paper_4_2(
+                    defeated_candidate,
+                    next_top,
+                    &paper,
+                    paper.value.clone(),
+                );
             }
 
             for (candidate, count) in transfer_tally {
@@ -1273,28 +1631,25 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         */
 
         let top_score = active_cs.top_score(&continuing_candidates);
-        if top_score >= quota {
-            let mut candidates_with_top_score: Vec<_> =
-                active_cs.matching_score(&continuing_candidates, top_score);
+        if election.reaches_quota(&top_score, &quota) {
+            let candidates_with_top_score: Vec<_> =
+                active_cs.matching_score(&continuing_candidates, &top_score);
 
             decision_log.add(format!(
                 "as per 4.3 the top score {} held by $C{:?} is over the quota",
                 top_score, candidates_with_top_score
             ));
 
-            if candidates_with_top_score.len() > 1 {
-                decision_log.add(
-                    "As per 3.5 Multiple candidates with the same score selecting alphabetically",
-                );
-                // As per 3.5 If two surpluses are equal, the Returning Officer must decide which to take first.
-                candidates_with_top_score.sort_by(|a, b| {
-                    election.candidates[*a]
-                        .name
-                        .cmp(&election.candidates[*b].name)
-                });
-            }
-
-            let top_candidate = candidates_with_top_score[0];
+            // As per 3.5 If two surpluses are equal, the Returning Officer must decide which to take first.
+            let top_candidate = break_tie(
+                &mut tie_breaker,
+                &mut decision_log,
+                election,
+                &active_cs,
+                TieKind::Elect,
+                &candidates_with_top_score,
+                "As per 3.5 Multiple candidates with the same score",
+            );
 
             decision_log.add(format!("Top candidate selected: $C{}", top_candidate));
 
@@ -1318,6 +1673,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
 
                 // Do AA here
                 let elected = fill_aa_requirements(
+                    &mut tie_breaker,
                     &mut decision_log,
                     election,
                     &votes,
@@ -1325,6 +1681,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                     &defeated_candidates,
                     &mut active_cs,
                     top_candidate,
+                    &[],
                 );
                 elected_candidates.extend(elected);
 
@@ -1355,7 +1712,7 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
                 // Set aside the papers
                 let run_off_entry = active_cs.get(*elected_candidate);
                 for paper in &run_off_entry.papers {
-                    removed_votes.push(paper.vote.created_by.clone());
+                    removed_votes.push(paper.vote.created_by);
                 }
             } else {
                 /*
@@ -1367,10 +1724,14 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
 
                 decision_log.add("As per 4.4 transferring the surplus of the elected candidate since all the surplus papers have been transferred");
 
+                // The candidate just elected is no longer continuing
+                let continuing_candidates =
+                    get_continuing_candidates(&elected_candidates, &defeated_candidates);
                 distribute_surplus(
                     &mut decision_log,
                     &mut active_cs,
-                    quota,
+                    &election.arithmetic,
+                    &quota,
                     &continuing_candidates,
                     *elected_candidate,
                 );
@@ -1387,10 +1748,37 @@ pub fn get_result(election: &Election, votes: &[Vote]) -> ElectionResult {
         elected_candidates,
         quota,
         count_sheet: CountSheetCollection {
-            count_sheets: count_sheet_entry_runoffs,
+            count_sheets: vec![active_cs],
             aa_count_sheets: aa_round_sheets,
         },
         decision_log,
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct QuotaPreferentialVicLabor2024;
+
+impl VotingSystem for QuotaPreferentialVicLabor2024 {
+    type Ballot = Vote;
+    type Config = Election;
+    type Outcome = ElectionResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        // Candidates who lost their papers later in the count place higher
+        let mut last_held: HashMap<usize, (usize, Number)> = HashMap::new();
+        for (count, count_sheet) in report.count_sheet.count_sheets.iter().enumerate() {
+            for (option_index, entry) in &count_sheet.entries {
+                if count == 0 || entry.score().is_positive() {
+                    last_held.insert(*option_index, (count, entry.score()));
+                }
+            }
+        }
+
+        Outcome::from_places(report.elected_candidates.clone(), rank_by_score(last_held))
     }
 }
 
@@ -1408,6 +1796,10 @@ mod tests {
             ],
             percent_female: 1.0,
             elected_count: 3,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let votes = vec![
@@ -1440,6 +1832,10 @@ mod tests {
             ],
             percent_female: 0.5,
             elected_count: 5,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let votes = vec![
@@ -1477,6 +1873,193 @@ mod tests {
         assert_eq!(result.elected_candidates, vec![0, 1, 2, 3, 4]);
     }
 
+    #[test]
+    fn test_quota_formulas() {
+        let mut election = Election {
+            candidates: (0..8)
+                .map(|i| Candidate::new(format!("C{}", i), i % 2 == 0))
+                .collect(),
+            percent_female: 0.5,
+            elected_count: 6,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
+        };
+
+        // The worked example in 2.3, (150 x 1000) / (6 + 1) = 21428 4/7
+        assert_eq!(election.quota(150usize), Number::from(21429));
+        election.quota = Quota::DroopPlusOne;
+        assert_eq!(election.quota(150usize), Number::from(21429));
+        election.quota = Quota::Hare;
+        assert_eq!(election.quota(150usize), Number::from(25000));
+        election.quota = Quota::HagenbachBischoff;
+        assert_eq!(election.quota(150usize), Number::from(21428));
+        assert_eq!(Quota::from_str(election.quota.key()), Ok(election.quota));
+        assert!(!election.reaches_quota(&Number::from(21428), &Number::from(21428)));
+        election.arithmetic = Arithmetic::Exact;
+        assert_eq!(election.quota(150usize), Number::new(150000, 7));
+
+        election.quota = Quota::DroopRoundedUp;
+        let votes = (0..150)
+            .map(|i| Vote::new_unknown(vec![i % 8]))
+            .collect::<Vec<_>>();
+        let result = get_result(&election, &votes);
+        assert_eq!(result.quota, Number::from(21429));
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Total votes: 150 Quota: 21429 by the Droop quota rounded up".to_string()));
+    }
+
+    /// Two vacancies over 9,000 points, B's 3,000 sits right on the Droop
+    /// quota
+    fn get_quota_result(quota: Quota) -> ElectionResult {
+        let election = Election {
+            candidates: vec![
+                Candidate::new("A", false),
+                Candidate::new("B", false),
+                Candidate::new("C", false),
+                Candidate::new("D", false),
+            ],
+            percent_female: 0.,
+            elected_count: 2,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota,
+        };
+
+        let mut votes = vec![];
+        for _ in 0..4 {
+            votes.push(Vote::new_unknown(vec![0, 3]));
+        }
+        for _ in 0..3 {
+            votes.push(Vote::new_unknown(vec![1]));
+        }
+        votes.push(Vote::new_unknown(vec![2, 3]));
+        votes.push(Vote::new_unknown(vec![3]));
+
+        get_result(&election, &votes)
+    }
+
+    #[test]
+    fn test_get_result_droop_rounded_up_quota() {
+        let result = get_quota_result(Quota::DroopRoundedUp);
+        assert_eq!(result.quota, Number::from(3000));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        // Reaching the quota is enough, so both fill on the first count
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Top score: 3000 held by [B] is over the quota".to_string()));
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"All vacancies filled by candidates over quota".to_string()));
+    }
+
+    #[test]
+    fn test_get_result_droop_plus_one_quota() {
+        let result = get_quota_result(Quota::DroopPlusOne);
+        assert_eq!(result.quota, Number::from(3001));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        // B is a point short, so A's surplus of 999 moves on to D
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Transfer value for A is 249 over 4 papers".to_string()));
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Comparing candidates B and D with scores 3000 and 2996".to_string()));
+    }
+
+    #[test]
+    fn test_get_result_hare_quota() {
+        let result = get_quota_result(Quota::Hare);
+        assert_eq!(result.quota, Number::from(4500));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        // No one reaches 4,500, the seats fill once the others are excluded
+        assert!(!result
+            .decision_log
+            .decisions
+            .iter()
+            .any(|i| i.starts_with("Top candidate selected")));
+        assert!(result
+            .decision_log
+            .decisions
+            .iter()
+            .any(|i| i.starts_with("As per 4.6.3 electing candidate [A, B]")));
+    }
+
+    #[test]
+    fn test_get_result_hagenbach_bischoff_quota() {
+        let result = get_quota_result(Quota::HagenbachBischoff);
+        assert_eq!(result.quota, Number::from(3000));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        // B has to go over 3,000, A's surplus of 1,000 leaves D level with B
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Transfer value for A is 250 over 4 papers".to_string()));
+        assert!(result
+            .decision_log
+            .decisions
+            .contains(&"Comparing candidates B and D with scores 3000 and 3000".to_string()));
+    }
+
+    #[test]
+    fn test_surplus_rounding_loss() {
+        let mut election = Election {
+            candidates: vec![
+                Candidate::new("AW", true),
+                Candidate::new("BW", true),
+                Candidate::new("CW", true),
+                Candidate::new("DW", true),
+                Candidate::new("EW", true),
+            ],
+            percent_female: 0.5,
+            elected_count: 2,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
+        };
+
+        let votes = vec![
+            Vote::new_unknown(vec![0, 1]),
+            Vote::new_unknown(vec![0, 1]),
+            Vote::new_unknown(vec![0, 1]),
+            Vote::new_unknown(vec![0, 1]),
+            Vote::new_unknown(vec![1]),
+            Vote::new_unknown(vec![2]),
+            Vote::new_unknown(vec![3]),
+            Vote::new_unknown(vec![4]),
+        ];
+
+        // A quota of 2667 points leaves a surplus of 1333 over 4 papers
+        let result = get_result(&election, &votes);
+        assert_eq!(result.quota, Number::from(2667));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        let transfer = &result.count_sheet.count_sheets[0].transfers[0];
+        assert_eq!(transfer.from, 0);
+        assert_eq!(transfer.transfer_value, Number::from(333));
+        assert_eq!(transfer.rounding_loss, Number::from(1));
+        assert_eq!(
+            result.count_sheet.count_sheets[0].get(0).remainders,
+            Number::from(1)
+        );
+
+        election.arithmetic = Arithmetic::Exact;
+        let result = get_result(&election, &votes);
+        assert_eq!(result.quota, Number::from(2667));
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+        let transfer = &result.count_sheet.count_sheets[0].transfers[0];
+        assert_eq!(transfer.transfer_value, Number::new(1333, 4));
+        assert!(transfer.rounding_loss.is_zero());
+    }
+
     #[test]
     fn test_get_results_e3_f50_wmm_section_3() {
         let election = Election {
@@ -1494,6 +2077,10 @@ mod tests {
             ],
             percent_female: 0.5,
             elected_count: 3,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         // 0, 1 win by quota 2 wins by distribution
@@ -1534,6 +2121,10 @@ mod tests {
             ],
             percent_female: 0.5,
             elected_count: 3,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         // 0, 1 win by quota 2 wins by distribution
@@ -1570,6 +2161,10 @@ mod tests {
             ],
             percent_female: 0.,
             elected_count: 4,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let votes = vec![
@@ -1605,6 +2200,10 @@ mod tests {
             ],
             percent_female: 0.,
             elected_count: 4,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         // 0, 1 win by quota 2 wins by distribution
@@ -1636,6 +2235,10 @@ mod tests {
             ],
             percent_female: 0.,
             elected_count: 4,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         // 0, 1 win by quota 2 wins by distribution
@@ -1674,6 +2277,10 @@ mod tests {
             ],
             percent_female: 0.,
             elected_count: 4,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         // 0, 1 win by quota 2 wins by distribution
@@ -1715,6 +2322,10 @@ mod tests {
             ],
             percent_female: 0.,
             elected_count: 6,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let mut votes = vec![];
@@ -1744,6 +2355,10 @@ mod tests {
             ],
             percent_female: 0.5,
             elected_count: 3,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let mut votes = vec![];
@@ -1777,6 +2392,10 @@ mod tests {
             ],
             percent_female: 0.5,
             elected_count: 5,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Election::WHOLE_POINTS,
+            quota: Quota::DroopRoundedUp,
         };
 
         let mut votes = vec![];
@@ -1795,7 +2414,13 @@ mod tests {
         }
         votes.push(Vote::new_unknown(vec![0]));
 
+        // 7 needs 5 x 0.5 = 2.5, rounded down to 2 women. C and B are
+        // elected by 2.4 and 3.6, E, H and I are defeated by 4.1 and F by
+        // 4.2. Under 4.6.3 D, A and G would fill the last three places with G
+        // the only woman, so as per 7.1 A isn't elected. G is seated anyway,
+        // so F is the woman left for A's place once 7.5 to 7.8 are done. She
+        // is short of the quota and is elected as the count continues by 7.10
         let result = get_result(&election, &votes);
-        assert_eq!(result.elected_candidates, vec![2, 1, 3, 0, 6]);
+        assert_eq!(result.elected_candidates, vec![2, 1, 3, 5, 6]);
     }
 }
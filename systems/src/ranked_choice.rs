@@ -0,0 +1,36 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::weight::{weighted, WeightedBallot};
+
+pub trait RankedChoiceVote<T> {
+    fn ranked_votes(&self) -> Vec<T>;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RankedChoiceVoteTally<T> {
+    pub votes: Vec<T>,
+    /// Weighted count of the ballots with this ranking
+    pub count: usize,
+}
+
+pub fn tally_ranked_votes<T, J>(votes: &[T]) -> Vec<RankedChoiceVoteTally<J>>
+where
+    T: RankedChoiceVote<J> + WeightedBallot,
+    J: Eq + std::hash::Hash + Clone,
+{
+    let mut map: HashMap<Vec<J>, usize> = HashMap::new();
+
+    for (vote, weight) in weighted(votes) {
+        *map.entry(vote.ranked_votes()).or_insert(0) += weight;
+    }
+
+    let mut result: Vec<RankedChoiceVoteTally<J>> = map
+        .into_iter()
+        .map(|(votes, count)| RankedChoiceVoteTally { votes, count })
+        .collect();
+    result.sort_by_key(|a| Reverse(a.count));
+
+    result
+}
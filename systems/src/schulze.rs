@@ -0,0 +1,232 @@
+use std::cmp::{max, min, Reverse};
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::get_matchups,
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchulzeElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when more than one option is unbeaten
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchulzeVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The preference given to each option, 0 being the most preferred
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(SchulzeVote);
+
+impl RankedChoiceVote<usize> for SchulzeVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchulzeResult {
+    pub options: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    /// `strongest_paths[i][j]` is the strength of the strongest beatpath from
+    /// option `i` to option `j`, measured in winning votes
+    pub strongest_paths: Vec<Vec<usize>>,
+    /// Options from first to last place, options neither of which beats the
+    /// other share a place
+    pub ranking: Vec<Vec<usize>>,
+    pub winner: usize,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Widest path between every pair of options using the Floyd–Warshall variant
+/// from Schulze's paper, links only exist where one option beats the other
+pub fn get_strongest_paths(matchups: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let option_count = matchups.len();
+    let mut paths = vec![vec![0; option_count]; option_count];
+
+    for i in 0..option_count {
+        for j in 0..option_count {
+            if i != j && matchups[i][j] > matchups[j][i] {
+                paths[i][j] = matchups[i][j];
+            }
+        }
+    }
+
+    for i in 0..option_count {
+        for j in 0..option_count {
+            if i == j {
+                continue;
+            }
+            for k in 0..option_count {
+                if i != k && j != k {
+                    paths[j][k] = max(paths[j][k], min(paths[j][i], paths[i][k]));
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// Orders options by how many others they beat on strongest paths
+pub fn get_ranking(strongest_paths: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let option_count = strongest_paths.len();
+    rank_by_score((0..option_count).map(|i| {
+        let beaten = (0..option_count)
+            .filter(|j| strongest_paths[i][*j] > strongest_paths[*j][i])
+            .count();
+        (i, beaten)
+    }))
+}
+
+pub fn get_result(election: &SchulzeElection, votes: &[SchulzeVote]) -> SchulzeResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes)
+                .map(|(vote, weight)| (vote.votes.iter().map(|i| Reverse(*i)).collect(), weight)),
+        )
+    });
+
+    let matchups = get_matchups(election.options.len(), votes);
+    let strongest_paths = get_strongest_paths(&matchups);
+    let ranking = get_ranking(&strongest_paths);
+
+    let winner = tie_breaker.break_tie(TieKind::Elect, &ranking[0], |rng| {
+        *ranking[0].choose(rng).unwrap()
+    });
+
+    SchulzeResult {
+        options: election.options.clone(),
+        matchups,
+        strongest_paths,
+        ranking,
+        winner,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Schulze;
+
+impl VotingSystem for Schulze {
+    type Ballot = SchulzeVote;
+    type Config = SchulzeElection;
+    type Outcome = SchulzeResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::new(
+            vec![report.winner],
+            report.ranking.clone(),
+            level_places(&report.ranking),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<usize>) -> SchulzeVote {
+        SchulzeVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    /// Converts an order of options into the preference given to each option
+    fn ranked(order: &[usize]) -> Vec<usize> {
+        let mut votes = vec![0; order.len()];
+        for (preference, option_index) in order.iter().enumerate() {
+            votes[*option_index] = preference;
+        }
+        votes
+    }
+
+    #[test]
+    fn test_get_result_wikipedia_example() {
+        let election = SchulzeElection {
+            options: ["A", "B", "C", "D", "E"]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let (a, b, c, d, e) = (0, 1, 2, 3, 4);
+        let groups = vec![
+            (5, vec![a, c, b, e, d]),
+            (5, vec![a, d, e, c, b]),
+            (8, vec![b, e, d, a, c]),
+            (3, vec![c, a, b, e, d]),
+            (7, vec![c, a, e, b, d]),
+            (2, vec![c, b, a, d, e]),
+            (7, vec![d, c, e, b, a]),
+            (8, vec![e, b, a, d, c]),
+        ];
+        let mut votes = vec![];
+        for (count, order) in groups {
+            for _ in 0..count {
+                votes.push(vote(ranked(&order)));
+            }
+        }
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.matchups[a][b], 20);
+        assert_eq!(
+            result.strongest_paths,
+            vec![
+                vec![0, 28, 28, 30, 24],
+                vec![25, 0, 28, 33, 24],
+                vec![25, 29, 0, 29, 24],
+                vec![25, 28, 28, 0, 24],
+                vec![25, 28, 28, 31, 0],
+            ]
+        );
+        assert_eq!(result.winner, e);
+        assert_eq!(
+            result.ranking,
+            vec![vec![e], vec![a], vec![c], vec![b], vec![d]]
+        );
+    }
+
+    #[test]
+    fn test_get_result_tied_ranking() {
+        let election = SchulzeElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![vote(vec![0, 1, 2]), vote(vec![1, 0, 2])];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.ranking, vec![vec![0, 1], vec![2]]);
+        assert!(result.ranking[0].contains(&result.winner));
+
+        let outcome = Schulze::outcome(&result);
+        assert_eq!(outcome.ties, vec![vec![0, 1]]);
+    }
+}
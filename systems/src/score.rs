@@ -0,0 +1,239 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreElection {
+    pub options: Vec<String>,
+    pub max_score: usize,
+    /// Seeds the RNG used when the runoff is still tied
+    pub seed: String,
+    /// Under `TieBreak::Method` options level on points go to a runoff on
+    /// the most top scores, any other policy settles them directly
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Score between 0 and `max_score` given to each option
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(ScoreVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreTally {
+    pub option_index: usize,
+    pub vote_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreRunoff {
+    pub participants: Vec<usize>,
+    pub winners: Vec<usize>,
+    pub score_checked: usize,
+}
+
+fn runoff(participants: &[usize], votes: &[ScoreVote], max_score: usize) -> ScoreRunoff {
+    let mut tally: HashMap<usize, HashMap<usize, usize>> = HashMap::new();
+    for i in participants {
+        let mut inner_hash_map = HashMap::new();
+        for j in 0..(max_score + 1) {
+            inner_hash_map.insert(j, 0);
+        }
+        tally.insert(*i, inner_hash_map);
+    }
+
+    for (vote, weight) in weighted(votes) {
+        for (option_index, v) in vote.votes.iter().enumerate() {
+            if let Some(entry) = tally.get_mut(&option_index) {
+                let col = entry.get_mut(v).unwrap();
+                *col += weight;
+            }
+        }
+    }
+
+    let mut winners: Vec<usize>;
+    let mut top = max_score;
+    loop {
+        let max = tally.values().map(|i| i[&top]).max().unwrap_or(0);
+
+        winners = participants
+            .iter()
+            .filter(|i| tally[i][&top] == max)
+            .copied()
+            .collect();
+
+        if winners.len() == 1 || top <= 1 {
+            break;
+        }
+
+        top -= 1;
+    }
+
+    ScoreRunoff {
+        participants: participants.to_vec(),
+        winners,
+        score_checked: top,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreResult {
+    pub options: Vec<String>,
+    pub runoff: Option<ScoreRunoff>,
+    pub winner: usize,
+    pub vote_tally: Vec<ScoreTally>,
+    pub votes: Vec<ScoreVote>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(election: &ScoreElection, votes: &[ScoreVote]) -> ScoreResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let mut vote_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        for (option_index, v) in vote.votes.iter().enumerate() {
+            vote_tally[option_index] += v * weight;
+        }
+    }
+
+    tie_breaker.record_round(vote_tally.iter().map(|i| *i as f64));
+
+    let mut vote_tally: Vec<ScoreTally> = vote_tally
+        .iter()
+        .enumerate()
+        .map(|(option_index, vote_count)| ScoreTally {
+            option_index,
+            vote_count: *vote_count,
+        })
+        .collect();
+
+    vote_tally.sort_by_key(|a| Reverse(a.vote_count));
+
+    let top_score = vote_tally.iter().map(|i| i.vote_count).max().unwrap();
+    let matching_score = vote_tally
+        .iter()
+        .filter(|i| i.vote_count == top_score)
+        .map(|i| i.option_index)
+        .collect::<Vec<_>>();
+
+    let mut runoff_result = None;
+    if matching_score.len() > 1 && election.tie_break == TieBreak::Method {
+        runoff_result = Some(runoff(&matching_score, votes, election.max_score));
+    }
+
+    let winner = tie_breaker.break_tie(
+        TieKind::Elect,
+        &matching_score,
+        |rng| match &runoff_result {
+            Some(runoff) if runoff.winners.len() > 1 => *runoff.winners.choose(rng).unwrap(),
+            Some(runoff) => runoff.winners[0],
+            None => matching_score[0],
+        },
+    );
+
+    ScoreResult {
+        options: election.options.clone(),
+        winner,
+        runoff: runoff_result,
+        vote_tally,
+        votes: votes.to_vec(),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Score;
+
+impl VotingSystem for Score {
+    type Ballot = ScoreVote;
+    type Config = ScoreElection;
+    type Outcome = ScoreResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let places = rank_by_score(
+            report
+                .vote_tally
+                .iter()
+                .map(|i| (i.option_index, i.vote_count)),
+        );
+        let ties = places
+            .iter()
+            .take(1)
+            .filter(|place| place.len() > 1)
+            .cloned()
+            .collect();
+
+        Outcome::new(vec![report.winner], places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<usize>) -> ScoreVote {
+        ScoreVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_result_highest_total() {
+        let election = ScoreElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            max_score: 5,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![vote(vec![5, 3]), vote(vec![2, 3])];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.winner, 0);
+        assert!(result.runoff.is_none());
+    }
+
+    #[test]
+    fn test_get_result_tie_goes_to_runoff() {
+        let election = ScoreElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            max_score: 5,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // Both total 6 but A has more top scores
+        let votes = vec![vote(vec![5, 3]), vote(vec![1, 3])];
+
+        let result = get_result(&election, &votes);
+        let runoff = result.runoff.unwrap();
+        assert_eq!(runoff.winners, vec![0]);
+        assert_eq!(runoff.score_checked, 5);
+        assert_eq!(result.winner, 0);
+    }
+}
@@ -0,0 +1,185 @@
+//! Commit-reveal seeds for drawing lots. The organiser publishes the SHA-256
+//! of a secret when the election is created and reveals the secret once
+//! voting locks, so nobody can steer the draw by picking a title and anyone
+//! can check the seed afterwards. Voters may mix in entropy of their own
+//! before the reveal.
+//!
+//! The seed is the hex SHA-256 of `SHA-256(secret)` followed by
+//! `SHA-256(entropy)` for each contribution, taken in sorted order.
+
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The commitment published for a secret
+pub fn commit(secret: &str) -> String {
+    to_hex(&Sha256::digest(secret.as_bytes()))
+}
+
+/// A commitment is a lowercase hex SHA-256 digest
+pub fn is_commitment(commitment: &str) -> bool {
+    commitment.len() == 64
+        && commitment
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+/// Mixes the revealed secret with the voters' entropy, the order the
+/// entropy was given in doesn't matter
+pub fn derive_seed(secret: &str, entropy: &[String]) -> String {
+    let mut entropy = entropy.to_vec();
+    entropy.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(Sha256::digest(secret.as_bytes()));
+    for contribution in &entropy {
+        hasher.update(Sha256::digest(contribution.as_bytes()));
+    }
+    to_hex(&hasher.finalize())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The revealed secret doesn't hash to the commitment
+    CommitmentMismatch,
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::CommitmentMismatch => {
+                write!(f, "The seed secret doesn't match the commitment")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+/// Where an election's seed came from, carried in each result so observers
+/// can re-run the draw
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SeedDerivation {
+    /// Left out for elections created before seeds were committed, their
+    /// seed is the title
+    pub commitment: Option<String>,
+    /// Hidden until voting locks, until then the seed is the commitment and
+    /// any draw is provisional
+    pub secret: Option<String>,
+    /// Sorted voter entropy mixed in at the reveal
+    pub entropy: Vec<String>,
+    pub seed: String,
+}
+
+impl SeedDerivation {
+    pub fn from_title(title: &str) -> SeedDerivation {
+        SeedDerivation {
+            commitment: None,
+            secret: None,
+            entropy: Vec::new(),
+            seed: title.to_string(),
+        }
+    }
+
+    pub fn committed(commitment: &str) -> SeedDerivation {
+        SeedDerivation {
+            commitment: Some(commitment.to_string()),
+            secret: None,
+            entropy: Vec::new(),
+            seed: commitment.to_string(),
+        }
+    }
+
+    pub fn reveal(
+        commitment: &str,
+        secret: &str,
+        entropy: &[String],
+    ) -> Result<SeedDerivation, SeedError> {
+        if commit(secret) != commitment {
+            return Err(SeedError::CommitmentMismatch);
+        }
+
+        let mut sorted = entropy.to_vec();
+        sorted.sort();
+        Ok(SeedDerivation {
+            commitment: Some(commitment.to_string()),
+            secret: Some(secret.to_string()),
+            seed: derive_seed(secret, &sorted),
+            entropy: sorted,
+        })
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.secret.is_some()
+    }
+
+    /// Re-derives the seed from the published parts
+    pub fn verify(&self) -> bool {
+        match (&self.commitment, &self.secret) {
+            (Some(commitment), Some(secret)) => {
+                commit(secret) == *commitment && derive_seed(secret, &self.entropy) == self.seed
+            }
+            (Some(commitment), None) => self.entropy.is_empty() && *commitment == self.seed,
+            (None, _) => self.secret.is_none() && self.entropy.is_empty(),
+        }
+    }
+}
+
+/// A result with the derivation of the seed its draws used
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SeededResult<R> {
+    #[serde(flatten)]
+    pub result: R,
+    pub seed: SeedDerivation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit() {
+        assert_eq!(
+            commit("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(is_commitment(&commit("abc")));
+        assert!(!is_commitment("BA7816BF"));
+    }
+
+    #[test]
+    fn test_reveal() {
+        let commitment = commit("secret");
+
+        assert_eq!(
+            SeedDerivation::reveal(&commitment, "guess", &[]),
+            Err(SeedError::CommitmentMismatch)
+        );
+
+        let derivation = SeedDerivation::reveal(
+            &commitment,
+            "secret",
+            &["voter b".to_string(), "voter a".to_string()],
+        )
+        .unwrap();
+        assert_eq!(derivation.entropy, vec!["voter a", "voter b"]);
+        assert_eq!(
+            derivation.seed,
+            derive_seed("secret", &["voter a".to_string(), "voter b".to_string()])
+        );
+        assert_ne!(derivation.seed, derive_seed("secret", &[]));
+        assert!(derivation.verify());
+
+        let mut tampered = derivation.clone();
+        tampered.entropy.pop();
+        assert!(!tampered.verify());
+
+        assert!(SeedDerivation::committed(&commitment).verify());
+        assert!(!SeedDerivation::committed(&commitment).is_revealed());
+    }
+}
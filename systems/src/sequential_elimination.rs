@@ -0,0 +1,377 @@
+use log::info;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    condorcet_method::{get_matchups, get_smith_set},
+    preferential_voting::{
+        borda_scores, break_elimination_tie, get_preference_tally, outcome_from_log,
+        CandidateVoteRoundResult, LogEntry, PreferentialVote,
+    },
+    ranked_choice::{tally_ranked_votes, RankedChoiceVoteTally},
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{Outcome, VotingSystem},
+    weight::{total_weight, weighted},
+};
+
+/// How each round picks who to eliminate, the round log holds Borda scores
+/// for Baldwin and Nanson and first preferences for Coombs and Tideman's
+/// Alternative
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EliminationMethod {
+    /// Eliminates the lowest Borda score
+    Baldwin,
+    /// Eliminates every option below the average Borda score
+    Nanson,
+    /// Eliminates the option with the most last place votes, stopping once
+    /// an option has a majority of first preferences
+    Coombs,
+    /// Eliminates every option outside the Smith set, or the fewest first
+    /// preferences when they are all in it
+    TidemanAlternative,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EliminationElection {
+    pub options: Vec<String>,
+    pub method: EliminationMethod,
+    /// Seeds the RNG used when a tie survives every preference level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EliminationResult {
+    pub method: EliminationMethod,
+    pub candidates: Vec<String>,
+    pub matchups: Vec<Vec<usize>>,
+    /// Same shape as `ElectionWinner::log`
+    pub log: Vec<LogEntry>,
+    pub winner: usize,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+/// Standing options on a ballot from most to least preferred
+fn standing_preferences(vote: &PreferentialVote, eliminated: &[usize]) -> Vec<usize> {
+    let mut preferences = (0..vote.votes.len())
+        .filter(|i| !eliminated.contains(i))
+        .collect::<Vec<usize>>();
+    preferences.sort_by_key(|i| vote.votes[*i]);
+    preferences
+}
+
+/// Borda points amongst the standing options, the last standing preference
+/// on a ballot gets nothing
+pub fn get_borda_scores(
+    candidate_count: usize,
+    votes: &[PreferentialVote],
+    eliminated: &[usize],
+) -> Vec<usize> {
+    let mut scores = vec![0; candidate_count];
+    for (vote, weight) in weighted(votes) {
+        let preferences = standing_preferences(vote, eliminated);
+        for (place, option_index) in preferences.iter().enumerate() {
+            scores[*option_index] += (preferences.len() - 1 - place) * weight;
+        }
+    }
+    scores
+}
+
+/// Votes for each standing option at the given end of the ballots
+fn get_place_counts(
+    candidate_count: usize,
+    votes: &[PreferentialVote],
+    eliminated: &[usize],
+    last: bool,
+) -> Vec<usize> {
+    let mut counts = vec![0; candidate_count];
+    for (vote, weight) in weighted(votes) {
+        let preferences = standing_preferences(vote, eliminated);
+        let option_index = if last {
+            preferences.last()
+        } else {
+            preferences.first()
+        };
+        if let Some(option_index) = option_index {
+            counts[*option_index] += weight;
+        }
+    }
+    counts
+}
+
+fn lowest_standing(
+    tie_breaker: &mut TieBreaker,
+    preference_tally: &[Vec<usize>],
+    standing: &[usize],
+    scores: &[usize],
+) -> usize {
+    let lowest_score = standing.iter().map(|i| scores[*i]).min().unwrap();
+    let lowest = standing
+        .iter()
+        .copied()
+        .filter(|i| scores[*i] == lowest_score)
+        .collect::<Vec<usize>>();
+    tie_breaker.break_tie(TieKind::Exclude, &lowest, |rng| {
+        break_elimination_tie(rng, preference_tally, &lowest, 0)
+    })
+}
+
+/// Options from `removed` lowest score first, so the log lists them in the
+/// order they would have gone one at a time
+fn sort_removed(mut removed: Vec<usize>, scores: &[usize]) -> Vec<usize> {
+    removed.sort_by_key(|i| scores[*i]);
+    removed
+}
+
+pub fn get_result(election: &EliminationElection, votes: &[PreferentialVote]) -> EliminationResult {
+    let candidate_count = election.options.len();
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(candidate_count, votes));
+    let preference_tally = get_preference_tally(candidate_count, votes);
+    let matchups = get_matchups(candidate_count, votes);
+
+    let mut log = Vec::new();
+    let mut eliminated: Vec<usize> = Vec::new();
+    let winner = loop {
+        let standing = (0..candidate_count)
+            .filter(|i| !eliminated.contains(i))
+            .collect::<Vec<usize>>();
+        let scores = match election.method {
+            EliminationMethod::Baldwin | EliminationMethod::Nanson => {
+                get_borda_scores(candidate_count, votes, &eliminated)
+            }
+            EliminationMethod::Coombs | EliminationMethod::TidemanAlternative => {
+                get_place_counts(candidate_count, votes, &eliminated, false)
+            }
+        };
+
+        let entry: Vec<CandidateVoteRoundResult> = election
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, name)| CandidateVoteRoundResult {
+                name: name.clone(),
+                votes: scores[i],
+            })
+            .collect();
+        info!("Candidates this round {:?}", entry);
+        log.push(LogEntry {
+            eliminated: eliminated.clone(),
+            votes: entry,
+        });
+        tie_breaker.record_round(scores.iter().map(|i| *i as f64));
+
+        if standing.len() == 1 {
+            break standing[0];
+        }
+        if election.method == EliminationMethod::Coombs {
+            if let Some(majority) = standing
+                .iter()
+                .find(|i| scores[**i] * 2 > total_weight(votes))
+            {
+                break *majority;
+            }
+        }
+
+        let removed = match election.method {
+            EliminationMethod::Baldwin => {
+                vec![lowest_standing(
+                    &mut tie_breaker,
+                    &preference_tally,
+                    &standing,
+                    &scores,
+                )]
+            }
+            EliminationMethod::Nanson => {
+                let total: usize = standing.iter().map(|i| scores[*i]).sum();
+                let below_average = standing
+                    .iter()
+                    .copied()
+                    .filter(|i| scores[*i] * standing.len() < total)
+                    .collect::<Vec<usize>>();
+                if below_average.is_empty() {
+                    // Everyone is on the average so they are all level
+                    vec![tie_breaker.break_tie(TieKind::Exclude, &standing, |rng| {
+                        break_elimination_tie(rng, &preference_tally, &standing, 0)
+                    })]
+                } else {
+                    sort_removed(below_average, &scores)
+                }
+            }
+            EliminationMethod::Coombs => {
+                let last_places = get_place_counts(candidate_count, votes, &eliminated, true);
+                let most_last_places = standing.iter().map(|i| last_places[*i]).max().unwrap();
+                let most = standing
+                    .iter()
+                    .copied()
+                    .filter(|i| last_places[*i] == most_last_places)
+                    .collect::<Vec<usize>>();
+                vec![tie_breaker.break_tie(TieKind::Exclude, &most, |rng| {
+                    break_elimination_tie(rng, &preference_tally, &most, 0)
+                })]
+            }
+            EliminationMethod::TidemanAlternative => {
+                let smith_set = get_smith_set(&matchups, &standing);
+                if smith_set.len() < standing.len() {
+                    let outside = standing
+                        .iter()
+                        .copied()
+                        .filter(|i| !smith_set.contains(i))
+                        .collect::<Vec<usize>>();
+                    sort_removed(outside, &scores)
+                } else {
+                    vec![lowest_standing(
+                        &mut tie_breaker,
+                        &preference_tally,
+                        &standing,
+                        &scores,
+                    )]
+                }
+            }
+        };
+        info!("Removing candidates {:?}", removed);
+        eliminated.extend(removed);
+    };
+
+    info!("Winner(s) {:?}", election.options[winner]);
+
+    EliminationResult {
+        method: election.method,
+        candidates: election.options.clone(),
+        matchups,
+        log,
+        winner,
+        votes: tally_ranked_votes(votes),
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct SequentialElimination;
+
+impl VotingSystem for SequentialElimination {
+    type Ballot = PreferentialVote;
+    type Config = EliminationElection;
+    type Outcome = EliminationResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        outcome_from_log(report.candidates.len(), &report.log, report.winner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weight::Weight;
+
+    fn vote(votes: Vec<usize>) -> PreferentialVote {
+        PreferentialVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    fn election(option_count: usize, method: EliminationMethod) -> EliminationElection {
+        EliminationElection {
+            options: ["A", "B", "C", "D"][..option_count]
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+            method,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_baldwin() {
+        // Borda scores are A 6, B 7, C 2
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 1, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![1, 0, 2]),
+            vote(vec![2, 1, 0]),
+        ];
+
+        let result = get_result(&election(3, EliminationMethod::Baldwin), &votes);
+        assert_eq!(result.log.len(), 3);
+        assert_eq!(result.log[0].votes[1].votes, 7);
+        assert_eq!(result.log[1].eliminated, vec![2]);
+        assert_eq!(result.log[2].eliminated, vec![2, 0]);
+        assert_eq!(result.winner, 1);
+    }
+
+    #[test]
+    fn test_get_result_nanson_eliminates_below_average() {
+        // Borda scores are A 13, B 12, C 3, D 2 against an average of 7.5
+        let mut votes = vec![];
+        for _ in 0..3 {
+            votes.push(vote(vec![0, 1, 2, 3]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![1, 0, 3, 2]));
+        }
+
+        let result = get_result(&election(4, EliminationMethod::Nanson), &votes);
+        assert_eq!(result.log.len(), 3);
+        assert_eq!(result.log[1].eliminated, vec![3, 2]);
+        assert_eq!(result.winner, 0);
+
+        let outcome = SequentialElimination::outcome(&result);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_get_result_coombs() {
+        // Instant runoff would drop B and elect A, Coombs drops C for being
+        // ranked last most often and B then has a majority
+        let mut votes = vec![];
+        for _ in 0..4 {
+            votes.push(vote(vec![0, 1, 2]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![2, 1, 0]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![1, 0, 2]));
+        }
+
+        let result = get_result(&election(3, EliminationMethod::Coombs), &votes);
+        assert_eq!(result.log.len(), 2);
+        assert_eq!(result.log[1].eliminated, vec![2]);
+        assert_eq!(result.log[1].votes[1].votes, 5);
+        assert_eq!(result.winner, 1);
+    }
+
+    #[test]
+    fn test_get_result_tideman_alternative() {
+        // A > B 7-2, B > C 6-3, C > A 5-4 and everyone ranks D last
+        let mut votes = vec![];
+        for _ in 0..4 {
+            votes.push(vote(vec![0, 1, 2, 3]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![1, 2, 0, 3]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![2, 0, 1, 3]));
+        }
+
+        let result = get_result(&election(4, EliminationMethod::TidemanAlternative), &votes);
+        // D is outside the Smith set, then B has the fewest first
+        // preferences and C beats A head to head
+        assert_eq!(result.log.len(), 4);
+        assert_eq!(result.log[1].eliminated, vec![3]);
+        assert_eq!(result.log[2].eliminated, vec![3, 1]);
+        assert_eq!(result.log[3].eliminated, vec![3, 1, 0]);
+        assert_eq!(result.winner, 2);
+    }
+}
@@ -1,17 +1,34 @@
+use std::cmp::Reverse;
+
 use serde_derive::{Deserialize, Serialize};
 
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SNTVElection {
     pub options: Vec<String>,
     pub elected_count: usize,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SNTVVote {
     pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
     pub votes: Vec<bool>,
 }
 
+impl_weighted_ballot!(SNTVVote);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SNTVTally {
     pub option_index: usize,
@@ -25,18 +42,32 @@ pub struct SNTVResult {
     pub vote_tally: Vec<SNTVTally>,
     pub votes: Vec<SNTVVote>,
     pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
 }
 
 pub fn get_result(election: &SNTVElection, votes: &[SNTVVote]) -> SNTVResult {
     let mut vote_tally = vec![0; election.options.len()];
-    for vote in votes {
+    for (vote, weight) in weighted(votes) {
         for (option_index, v) in vote.votes.iter().enumerate() {
             if *v {
-                vote_tally[option_index] += 1;
+                vote_tally[option_index] += weight;
             }
         }
     }
 
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+    tie_breaker.record_round(vote_tally.iter().map(|i| *i as f64));
+    let winners = tie_breaker.take_top(
+        &rank_by_score(vote_tally.iter().copied().enumerate()),
+        election.elected_count,
+        |_, place| place.to_vec(),
+    );
+
     let mut vote_tally: Vec<SNTVTally> = vote_tally
         .iter()
         .enumerate()
@@ -46,13 +77,7 @@ pub fn get_result(election: &SNTVElection, votes: &[SNTVVote]) -> SNTVResult {
         })
         .collect();
 
-    vote_tally.sort_by(|a, b| b.vote_count.cmp(&a.vote_count));
-
-    let winners: Vec<usize> = vote_tally.clone()[0..election.elected_count]
-        .to_vec()
-        .iter()
-        .map(|i| i.option_index)
-        .collect();
+    vote_tally.sort_by_key(|a| Reverse(a.vote_count));
 
     SNTVResult {
         options: election.options.clone(),
@@ -60,5 +85,30 @@ pub fn get_result(election: &SNTVElection, votes: &[SNTVVote]) -> SNTVResult {
         vote_tally: vote_tally.clone(),
         votes: votes.to_vec(),
         vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct SingleNonTransferableVote;
+
+impl VotingSystem for SingleNonTransferableVote {
+    type Ballot = SNTVVote;
+    type Config = SNTVElection;
+    type Outcome = SNTVResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        Outcome::from_places(
+            report.winners.clone(),
+            rank_by_score(
+                report
+                    .vote_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.vote_count)),
+            ),
+        )
     }
 }
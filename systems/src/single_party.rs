@@ -0,0 +1,94 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    voting_system::{Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SinglePartyElection {
+    pub options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SinglePartyVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// False for a blank ballot
+    pub voted: bool,
+}
+
+impl_weighted_ballot!(SinglePartyVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SinglePartyResult {
+    pub options: Vec<String>,
+    pub filled_votes: usize,
+    pub blank_votes: usize,
+    pub won: bool,
+    pub vote_count: usize,
+}
+
+pub fn get_result(election: &SinglePartyElection, votes: &[SinglePartyVote]) -> SinglePartyResult {
+    let mut valid_votes = 0;
+    let mut blank_votes = 0;
+    for (vote, weight) in weighted(votes) {
+        if vote.voted {
+            valid_votes += weight;
+        } else {
+            blank_votes += weight;
+        }
+    }
+
+    SinglePartyResult {
+        options: election.options.clone(),
+        filled_votes: valid_votes,
+        blank_votes,
+        won: valid_votes > (valid_votes + blank_votes) / 2,
+        vote_count: votes.len(),
+    }
+}
+
+pub struct SingleParty;
+
+impl VotingSystem for SingleParty {
+    type Ballot = SinglePartyVote;
+    type Config = SinglePartyElection;
+    type Outcome = SinglePartyResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let elected = if report.won { vec![0] } else { Vec::new() };
+        Outcome::new(elected, vec![vec![0]], Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result_needs_majority() {
+        let election = SinglePartyElection {
+            options: vec!["A".to_string()],
+        };
+
+        let votes = [true, false]
+            .into_iter()
+            .map(|voted| SinglePartyVote {
+                created_by: uuid::Uuid::new_v4(),
+                weight: Weight::ONE,
+                voted,
+            })
+            .collect::<Vec<_>>();
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.filled_votes, 1);
+        assert_eq!(result.blank_votes, 1);
+        assert!(!result.won);
+    }
+}
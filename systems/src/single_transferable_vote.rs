@@ -0,0 +1,1097 @@
+use std::{
+    cmp::{max, min},
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use log::info;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    numeric::{Arithmetic, Number, Rounded},
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_rankings, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{level_places, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, total_weight, weighted, Weight},
+};
+
+/// How the votes an elected option has beyond the quota move on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StvTransfer {
+    /// A quota of the option's ballots is picked at random and set aside,
+    /// the rest move on at full value
+    #[default]
+    Random,
+    /// Every ballot with a further preference moves on at the surplus
+    /// divided by the number of those ballots, never more than it carried in
+    Gregory,
+    /// Every ballot moves on at its value scaled by the surplus over the
+    /// option's total, ballots with no further preference exhaust
+    WeightedInclusiveGregory,
+}
+
+impl StvTransfer {
+    /// The name the transfer is stored under
+    pub fn key(&self) -> &'static str {
+        match self {
+            StvTransfer::Random => "random",
+            StvTransfer::Gregory => "gregory",
+            StvTransfer::WeightedInclusiveGregory => "weighted_inclusive_gregory",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStvTransferError;
+
+impl fmt::Display for ParseStvTransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Transfer must be random, gregory or weighted_inclusive_gregory"
+        )
+    }
+}
+
+impl std::error::Error for ParseStvTransferError {}
+
+/// Parses the name from `StvTransfer::key`
+impl FromStr for StvTransfer {
+    type Err = ParseStvTransferError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "random" => StvTransfer::Random,
+            "gregory" => StvTransfer::Gregory,
+            "weighted_inclusive_gregory" => StvTransfer::WeightedInclusiveGregory,
+            _ => return Err(ParseStvTransferError),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StvQuota {
+    /// Votes over seats
+    #[default]
+    Hare,
+    /// Votes over seats plus one, rounded down, plus one
+    Droop,
+    /// Votes over seats plus one, an option must go over it to be elected
+    HagenbachBischoff,
+}
+
+impl StvQuota {
+    /// The name the quota is stored under
+    pub fn key(&self) -> &'static str {
+        match self {
+            StvQuota::Hare => "hare",
+            StvQuota::Droop => "droop",
+            StvQuota::HagenbachBischoff => "hagenbach_bischoff",
+        }
+    }
+
+    pub fn value(&self, vote_count: usize, elected_count: usize) -> f64 {
+        let vote_count = vote_count as f64;
+        let elected_count = elected_count as f64;
+        match self {
+            StvQuota::Hare => vote_count / elected_count,
+            StvQuota::Droop => (vote_count / (elected_count + 1.0)).floor() + 1.0,
+            StvQuota::HagenbachBischoff => vote_count / (elected_count + 1.0),
+        }
+    }
+
+    /// `value` as an exact fraction
+    pub fn exact_value(&self, vote_count: usize, elected_count: usize) -> Number {
+        let vote_count = vote_count as i64;
+        let elected_count = elected_count as i64;
+        match self {
+            StvQuota::Hare => Number::new(vote_count, elected_count),
+            StvQuota::Droop => Number::from(vote_count / (elected_count + 1) + 1),
+            StvQuota::HagenbachBischoff => Number::new(vote_count, elected_count + 1),
+        }
+    }
+
+    pub fn is_reached<T: PartialOrd>(&self, votes: T, quota: T) -> bool {
+        match self {
+            StvQuota::HagenbachBischoff => votes > quota,
+            _ => votes >= quota,
+        }
+    }
+
+    /// Whole number of ballots needed when ballots keep their full value
+    fn ballot_count(&self, vote_count: usize, elected_count: usize) -> usize {
+        let quota = self.value(vote_count, elected_count);
+        match self {
+            StvQuota::Hare => max(quota.floor() as usize, 1),
+            StvQuota::Droop => quota as usize,
+            StvQuota::HagenbachBischoff => quota.floor() as usize + 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStvQuotaError;
+
+impl fmt::Display for ParseStvQuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Quota must be hare, droop or hagenbach_bischoff")
+    }
+}
+
+impl std::error::Error for ParseStvQuotaError {}
+
+/// Parses the name from `StvQuota::key`
+impl FromStr for StvQuota {
+    type Err = ParseStvQuotaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "hare" => StvQuota::Hare,
+            "droop" => StvQuota::Droop,
+            "hagenbach_bischoff" => StvQuota::HagenbachBischoff,
+            _ => return Err(ParseStvQuotaError),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StvElection {
+    pub options: Vec<String>,
+    pub elected_count: usize,
+    #[serde(default)]
+    pub transfer: StvTransfer,
+    #[serde(default)]
+    pub quota: StvQuota,
+    /// Seeds the RNG used for ties and for picking which ballots make up a quota
+    pub seed: String,
+    /// Under `TieBreak::Method` ties are broken on the most preferences at
+    /// each level in turn, then by lot
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// How fractional transfer values are kept, only used by the Gregory
+    /// transfers
+    #[serde(default)]
+    pub arithmetic: Arithmetic,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct StvVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Options in order of preference
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(StvVote);
+
+impl RankedChoiceVote<usize> for StvVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.clone()
+    }
+}
+
+/// Ballots that moved together from one option at one transfer value
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct StvParcelTransfer {
+    pub from: usize,
+    pub ballot_count: usize,
+    /// Value of each ballot before the transfer
+    pub ballot_value: f64,
+    /// Value of each ballot after the transfer
+    pub transfer_value: f64,
+    /// Ballots that went to each option
+    pub to: HashMap<usize, usize>,
+    /// Ballots with no further preference
+    pub exhausted: usize,
+    /// Value the moved ballots lost to rounding the transfer value
+    #[serde(default)]
+    pub rounding_loss: Number,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct StvRound {
+    /// Ballots held by each option
+    pub vote_counts: HashMap<usize, usize>,
+    /// Value of the ballots held by each option, the same as `vote_counts`
+    /// unless ballots have moved at a reduced transfer value
+    pub vote_values: HashMap<usize, f64>,
+    pub eliminated_candidates: HashSet<usize>,
+    pub elected_candidates: HashSet<usize>,
+    /// Parcels moved at the end of the round
+    pub transfers: Vec<StvParcelTransfer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct StvResult {
+    pub candidates: Vec<String>,
+    pub elected_candidates: Vec<usize>,
+    pub starting_eliminated_candidates: Vec<usize>,
+    pub transfer: StvTransfer,
+    pub quota: f64,
+    pub votes: Vec<RankedChoiceVoteTally<usize>>,
+    pub rounds: Vec<StvRound>,
+    pub vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn break_elected_tie(
+    rng: &mut Pcg64,
+    preference_tally: &HashMap<usize, Vec<usize>>,
+    candidates: &[usize],
+    pref_number: usize,
+) -> usize {
+    let highest_value = candidates
+        .iter()
+        .map(|c| preference_tally[c][pref_number])
+        .max()
+        .unwrap();
+
+    let matching_highest = candidates
+        .iter()
+        .filter(|c| preference_tally[c][pref_number] == highest_value)
+        .copied()
+        .collect::<Vec<usize>>();
+
+    info!(
+        "Trying to break elected tie checking pref number {} between {:?} highest value is {}",
+        pref_number,
+        candidates
+            .iter()
+            .map(|c| (*c, preference_tally[c][pref_number]))
+            .collect::<Vec<(usize, usize)>>(),
+        highest_value
+    );
+
+    if matching_highest.len() == 1 {
+        return *matching_highest.first().unwrap();
+    }
+
+    if pref_number + 1 >= preference_tally[&0].len() {
+        return *matching_highest.choose(rng).unwrap();
+    }
+
+    break_elected_tie(rng, preference_tally, &matching_highest, pref_number + 1)
+}
+
+fn break_elimination_tie(
+    rng: &mut Pcg64,
+    preference_tally: &HashMap<usize, Vec<usize>>,
+    candidates: &[usize],
+    pref_number: usize,
+) -> usize {
+    let lowest_value = candidates
+        .iter()
+        .map(|c| preference_tally[c][pref_number])
+        .min()
+        .unwrap();
+
+    let matching_lowest = candidates
+        .iter()
+        .filter(|c| preference_tally[c][pref_number] == lowest_value)
+        .copied()
+        .collect::<Vec<usize>>();
+
+    info!(
+        "Trying to break elimination tie checking pref number {} between {:?} lowest value is {}",
+        pref_number,
+        candidates
+            .iter()
+            .map(|i| (*i, preference_tally[i][pref_number]))
+            .collect::<Vec<(usize, usize)>>(),
+        lowest_value
+    );
+
+    if matching_lowest.len() == 1 {
+        return *matching_lowest.first().unwrap();
+    }
+
+    if pref_number + 1 >= preference_tally[&0].len() {
+        return *matching_lowest.choose(rng).unwrap();
+    }
+
+    break_elimination_tie(rng, preference_tally, &matching_lowest, pref_number + 1)
+}
+
+pub(crate) fn borda_scores(option_count: usize, votes: &[StvVote]) -> Vec<f64> {
+    borda_from_rankings(
+        option_count,
+        weighted(votes).map(|(vote, weight)| (vote.votes.as_slice(), weight)),
+    )
+}
+
+fn get_preference_tally(votes: &[StvVote], options: &[String]) -> HashMap<usize, Vec<usize>> {
+    let mut preference_tally: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..options.len() {
+        preference_tally.insert(i, vec![0; options.len()]);
+    }
+
+    for (vote, weight) in weighted(votes) {
+        for (round, vote) in vote.votes.iter().enumerate() {
+            let option_votes = preference_tally.get_mut(vote).unwrap();
+            let count = option_votes.get_mut(round).unwrap();
+            *count += weight;
+        }
+    }
+
+    preference_tally
+}
+
+pub fn get_result(
+    election: &StvElection,
+    votes: &[StvVote],
+    starting_eliminated_candidates: &[usize],
+) -> StvResult {
+    if election.transfer != StvTransfer::Random {
+        return get_gregory_result(election, votes, starting_eliminated_candidates);
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    // Each ballot counts as many papers as it has units of weight, units are
+    // set aside as they are drawn rather than the ballot being copied
+    let mut units = weighted(votes)
+        .map(|(_, weight)| weight)
+        .collect::<Vec<usize>>();
+
+    let quota = election
+        .quota
+        .ballot_count(units.iter().sum(), election.elected_count);
+    let mut elected_candidates = HashSet::new();
+    let mut eliminated_candidates: HashSet<usize> =
+        starting_eliminated_candidates.iter().copied().collect();
+    let mut rounds: Vec<StvRound> = Vec::new();
+    let preference_tally = get_preference_tally(votes, &election.options);
+
+    while elected_candidates.len() < election.elected_count && units.iter().sum::<usize>() >= quota
+    {
+        let mut vote_counts: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, vote) in votes.iter().enumerate() {
+            if units[i] == 0 {
+                continue;
+            }
+            let highest_valid_vote = vote
+                .votes
+                .iter()
+                .find(|v| !elected_candidates.contains(*v) && !eliminated_candidates.contains(*v))
+                .unwrap();
+            vote_counts.entry(*highest_valid_vote).or_default().push(i);
+        }
+        let counts = vote_counts
+            .iter()
+            .map(|(k, v)| (*k, ballot_weight(v, &units)))
+            .collect::<HashMap<usize, usize>>();
+        tie_breaker.record_round(
+            (0..election.options.len()).map(|i| counts.get(&i).map_or(0.0, |v| *v as f64)),
+        );
+
+        let quota_hitting_candidates = counts
+            .clone()
+            .into_iter()
+            .filter(|(_, v)| *v >= quota)
+            .collect::<HashMap<usize, usize>>();
+        if !quota_hitting_candidates.is_empty() {
+            let max_votes = *quota_hitting_candidates.values().max().unwrap();
+            let mut candidates_with_most_votes = quota_hitting_candidates
+                .iter()
+                .filter(|(_, v)| **v == max_votes)
+                .map(|(k, _)| *k)
+                .collect::<Vec<usize>>();
+            candidates_with_most_votes
+                .sort_by(|a, b| election.options[*a].cmp(&election.options[*b]));
+
+            let elected_candidate =
+                tie_breaker.break_tie(TieKind::Elect, &candidates_with_most_votes, |rng| {
+                    break_elected_tie(rng, &preference_tally, &candidates_with_most_votes, 0)
+                });
+
+            info!(
+                "quota hit, candidates {:?} max votes {} final selected {}:{}",
+                quota_hitting_candidates.keys(),
+                max_votes,
+                elected_candidate,
+                election.options[elected_candidate]
+            );
+            elected_candidates.insert(elected_candidate);
+
+            let ballots = vote_counts.get_mut(&elected_candidate).unwrap();
+
+            ballots.shuffle(tie_breaker.rng());
+
+            // The ballot that completes the quota is split, its other units
+            // stay in the count
+            let mut set_aside = quota;
+            for ballot in ballots.iter() {
+                let taken = min(units[*ballot], set_aside);
+                units[*ballot] -= taken;
+                set_aside -= taken;
+                if set_aside == 0 {
+                    break;
+                }
+            }
+        } else {
+            // Eliminate if no quota is hit
+            let lowest_count = *counts.values().min().unwrap();
+            let mut lowest_matching_candidates = counts
+                .iter()
+                .filter(|(_, v)| **v == lowest_count)
+                .map(|(k, _)| *k)
+                .collect::<Vec<usize>>();
+            lowest_matching_candidates
+                .sort_by(|a, b| election.options[*a].cmp(&election.options[*b]));
+
+            info!(
+                "No quota hit, lowest count is {} matching candidates {:?}",
+                lowest_count, lowest_matching_candidates
+            );
+            let to_eliminate =
+                tie_breaker.break_tie(TieKind::Exclude, &lowest_matching_candidates, |rng| {
+                    break_elimination_tie(rng, &preference_tally, &lowest_matching_candidates, 0)
+                });
+
+            info!(
+                "eliminating lowest {}:{}",
+                election.options[to_eliminate], counts[&to_eliminate]
+            );
+            eliminated_candidates.insert(to_eliminate);
+        }
+
+        info!(
+            "Round {} vote count {:?}",
+            rounds.len(),
+            counts
+                .iter()
+                .map(|(k, v)| (election.options[*k].clone(), *v))
+                .collect::<HashMap<String, usize>>()
+        );
+
+        // Fill out the counts with zeros for eliminated candidates
+        let vote_counts = (0..election.options.len())
+            .map(|i| (i, counts.get(&i).copied().unwrap_or(0)))
+            .collect::<HashMap<usize, usize>>();
+
+        rounds.push(StvRound {
+            vote_counts: vote_counts.clone(),
+            vote_values: vote_counts.iter().map(|(k, v)| (*k, *v as f64)).collect(),
+            eliminated_candidates: eliminated_candidates.clone(),
+            elected_candidates: elected_candidates.clone(),
+            transfers: Vec::new(),
+        });
+    }
+
+    let elected_candidates = elected_candidates.iter().copied().collect();
+
+    StvResult {
+        candidates: election.options.clone(),
+        elected_candidates,
+        starting_eliminated_candidates: starting_eliminated_candidates.to_vec(),
+        transfer: election.transfer,
+        quota: quota as f64,
+        votes: tally_ranked_votes(votes),
+        rounds,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+/// Ballots that reached an option together at the same value
+#[derive(Debug, Clone)]
+struct Parcel {
+    ballots: Vec<usize>,
+    value: Number,
+}
+
+/// Total weight of the ballots
+fn ballot_weight(ballots: &[usize], weights: &[usize]) -> usize {
+    ballots.iter().map(|i| weights[*i]).sum()
+}
+
+fn parcel_total(pile: &[Parcel], weights: &[usize]) -> Number {
+    pile.iter()
+        .map(|i| &Number::from(ballot_weight(&i.ballots, weights) as i64) * &i.value)
+        .sum()
+}
+
+fn next_continuing(vote: &StvVote, continuing: &HashSet<usize>) -> Option<usize> {
+    vote.votes.iter().copied().find(|i| continuing.contains(i))
+}
+
+/// Moves the ballots of `parcel` on to their next continuing preference at
+/// `transfer_value`, ballots with no further preference are returned
+fn transfer_parcel(
+    from: usize,
+    parcel: &Parcel,
+    transfer_value: Rounded,
+    votes: &[StvVote],
+    weights: &[usize],
+    continuing: &HashSet<usize>,
+    piles: &mut HashMap<usize, Vec<Parcel>>,
+) -> (StvParcelTransfer, Vec<usize>) {
+    let mut moved: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut exhausted = Vec::new();
+    for ballot in &parcel.ballots {
+        match next_continuing(&votes[*ballot], continuing) {
+            Some(option_index) => moved.entry(option_index).or_default().push(*ballot),
+            None => exhausted.push(*ballot),
+        }
+    }
+
+    let to: HashMap<usize, usize> = moved
+        .iter()
+        .map(|(k, v)| (*k, ballot_weight(v, weights)))
+        .collect();
+    let moved_count = to.values().sum::<usize>() as i64;
+    for (option_index, ballots) in moved {
+        piles.entry(option_index).or_default().push(Parcel {
+            ballots,
+            value: transfer_value.value.clone(),
+        });
+    }
+
+    (
+        StvParcelTransfer {
+            from,
+            ballot_count: ballot_weight(&parcel.ballots, weights),
+            ballot_value: parcel.value.to_f64(),
+            transfer_value: transfer_value.value.to_f64(),
+            to,
+            exhausted: ballot_weight(&exhausted, weights),
+            rounding_loss: &transfer_value.loss * &Number::from(moved_count),
+        },
+        exhausted,
+    )
+}
+
+/// A value passed on as it is
+fn exact(value: Number) -> Rounded {
+    Rounded {
+        value,
+        loss: Number::zero(),
+    }
+}
+
+/// Counts with fractional surplus transfers, one election or exclusion per
+/// round with its parcels moved before the next round
+fn get_gregory_result(
+    election: &StvElection,
+    votes: &[StvVote],
+    starting_eliminated_candidates: &[usize],
+) -> StvResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed)
+        .with_borda(|| borda_scores(election.options.len(), votes));
+
+    let weights = weighted(votes).map(|(_, i)| i).collect::<Vec<usize>>();
+    let quota = election
+        .quota
+        .exact_value(total_weight(votes), election.elected_count);
+    let mut elected_order: Vec<usize> = Vec::new();
+    let mut elected_candidates: HashSet<usize> = HashSet::new();
+    let mut eliminated_candidates: HashSet<usize> =
+        starting_eliminated_candidates.iter().copied().collect();
+    let mut continuing: HashSet<usize> = (0..election.options.len())
+        .filter(|i| !eliminated_candidates.contains(i))
+        .collect();
+    let mut rounds: Vec<StvRound> = Vec::new();
+    let preference_tally = get_preference_tally(votes, &election.options);
+
+    let mut piles: HashMap<usize, Vec<Parcel>> = HashMap::new();
+    for (i, vote) in votes.iter().enumerate() {
+        if let Some(option_index) = next_continuing(vote, &continuing) {
+            piles.entry(option_index).or_default().push(Parcel {
+                ballots: vec![i],
+                value: Number::from(1),
+            });
+        }
+    }
+    // Merge the single ballot parcels into one parcel of first preferences
+    for pile in piles.values_mut() {
+        let ballots = pile.drain(..).flat_map(|i| i.ballots).collect();
+        pile.push(Parcel {
+            ballots,
+            value: Number::from(1),
+        });
+    }
+
+    while elected_order.len() < election.elected_count && !continuing.is_empty() {
+        let vote_counts: HashMap<usize, usize> = (0..election.options.len())
+            .map(|i| {
+                let count = piles.get(&i).map_or(0, |pile| {
+                    pile.iter()
+                        .map(|p| ballot_weight(&p.ballots, &weights))
+                        .sum()
+                });
+                (i, count)
+            })
+            .collect();
+        let exact_values: HashMap<usize, Number> = (0..election.options.len())
+            .map(|i| {
+                let value = if elected_candidates.contains(&i) {
+                    quota.clone()
+                } else {
+                    piles
+                        .get(&i)
+                        .map_or(Number::zero(), |pile| parcel_total(pile, &weights))
+                };
+                (i, value)
+            })
+            .collect();
+        let vote_values: HashMap<usize, f64> =
+            exact_values.iter().map(|(k, v)| (*k, v.to_f64())).collect();
+        info!("Round {} vote values {:?}", rounds.len(), vote_values);
+        tie_breaker.record_round((0..election.options.len()).map(|i| vote_values[&i]));
+
+        let mut transfers = Vec::new();
+        let mut reaching_quota = continuing
+            .iter()
+            .copied()
+            .filter(|i| election.quota.is_reached(&exact_values[i], &quota))
+            .collect::<Vec<usize>>();
+        reaching_quota.sort();
+
+        if !reaching_quota.is_empty() {
+            let max_value = reaching_quota
+                .iter()
+                .map(|i| exact_values[i].clone())
+                .max()
+                .unwrap();
+            let highest = reaching_quota
+                .iter()
+                .copied()
+                .filter(|i| exact_values[i] == max_value)
+                .collect::<Vec<usize>>();
+            let elected_candidate = tie_breaker.break_tie(TieKind::Elect, &highest, |rng| {
+                break_elected_tie(rng, &preference_tally, &highest, 0)
+            });
+            info!(
+                "quota hit, electing {}:{}",
+                elected_candidate, election.options[elected_candidate]
+            );
+            continuing.remove(&elected_candidate);
+            elected_candidates.insert(elected_candidate);
+            elected_order.push(elected_candidate);
+
+            let surplus = &max_value - &quota;
+            if elected_order.len() < election.elected_count && surplus.is_positive() {
+                let pile = piles.remove(&elected_candidate).unwrap_or_default();
+                let mut kept = Vec::new();
+                match election.transfer {
+                    StvTransfer::WeightedInclusiveGregory => {
+                        for parcel in &pile {
+                            let transfer_value = election
+                                .arithmetic
+                                .divide(&(&parcel.value * &surplus), &max_value);
+                            let (transfer, exhausted) = transfer_parcel(
+                                elected_candidate,
+                                parcel,
+                                transfer_value,
+                                votes,
+                                &weights,
+                                &continuing,
+                                &mut piles,
+                            );
+                            transfers.push(transfer);
+                            kept.push(Parcel {
+                                ballots: exhausted,
+                                value: parcel.value.clone(),
+                            });
+                        }
+                    }
+                    _ => {
+                        let transferable = pile
+                            .iter()
+                            .flat_map(|i| i.ballots.iter())
+                            .filter(|i| next_continuing(&votes[**i], &continuing).is_some())
+                            .map(|i| weights[*i])
+                            .sum::<usize>();
+                        for parcel in &pile {
+                            let transfer_value = if transferable == 0 {
+                                exact(Number::zero())
+                            } else {
+                                let transfer_value = election
+                                    .arithmetic
+                                    .divide(&surplus, &Number::from(transferable as i64));
+                                if transfer_value.value > parcel.value {
+                                    exact(parcel.value.clone())
+                                } else {
+                                    transfer_value
+                                }
+                            };
+                            let (transfer, exhausted) = transfer_parcel(
+                                elected_candidate,
+                                parcel,
+                                transfer_value,
+                                votes,
+                                &weights,
+                                &continuing,
+                                &mut piles,
+                            );
+                            transfers.push(transfer);
+                            kept.push(Parcel {
+                                ballots: exhausted,
+                                value: parcel.value.clone(),
+                            });
+                        }
+                    }
+                }
+                kept.retain(|i| !i.ballots.is_empty());
+                piles.insert(elected_candidate, kept);
+            }
+        } else if continuing.len() + elected_order.len() <= election.elected_count {
+            // Everyone left fills a seat
+            let mut remaining = continuing.drain().collect::<Vec<usize>>();
+            remaining.sort_by(|a, b| exact_values[b].cmp(&exact_values[a]).then(a.cmp(b)));
+            info!("Electing remaining {:?}", remaining);
+            elected_candidates.extend(remaining.iter().copied());
+            elected_order.extend(remaining);
+        } else {
+            let min_value = continuing
+                .iter()
+                .map(|i| exact_values[i].clone())
+                .min()
+                .unwrap();
+            let mut lowest = continuing
+                .iter()
+                .copied()
+                .filter(|i| exact_values[i] == min_value)
+                .collect::<Vec<usize>>();
+            lowest.sort();
+            let to_eliminate = tie_breaker.break_tie(TieKind::Exclude, &lowest, |rng| {
+                break_elimination_tie(rng, &preference_tally, &lowest, 0)
+            });
+            info!(
+                "eliminating lowest {}:{}",
+                election.options[to_eliminate], min_value
+            );
+            continuing.remove(&to_eliminate);
+            eliminated_candidates.insert(to_eliminate);
+
+            for parcel in piles.remove(&to_eliminate).unwrap_or_default() {
+                let (transfer, _) = transfer_parcel(
+                    to_eliminate,
+                    &parcel,
+                    exact(parcel.value.clone()),
+                    votes,
+                    &weights,
+                    &continuing,
+                    &mut piles,
+                );
+                transfers.push(transfer);
+            }
+        }
+
+        rounds.push(StvRound {
+            vote_counts,
+            vote_values,
+            eliminated_candidates: eliminated_candidates.clone(),
+            elected_candidates: elected_candidates.clone(),
+            transfers,
+        });
+    }
+
+    StvResult {
+        candidates: election.options.clone(),
+        elected_candidates: elected_order,
+        starting_eliminated_candidates: starting_eliminated_candidates.to_vec(),
+        transfer: election.transfer,
+        quota: quota.to_f64(),
+        votes: tally_ranked_votes(votes),
+        rounds,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct SingleTransferableVote;
+
+impl VotingSystem for SingleTransferableVote {
+    type Ballot = StvVote;
+    type Config = StvElection;
+    type Outcome = StvResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots, &[])
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut elected = Vec::new();
+        let mut removed = Vec::new();
+        let mut ties = Vec::new();
+        let mut decided: HashSet<usize> = report
+            .starting_eliminated_candidates
+            .iter()
+            .copied()
+            .collect();
+        for round in &report.rounds {
+            let mut newly_elected = round
+                .elected_candidates
+                .iter()
+                .filter(|i| !decided.contains(i))
+                .copied()
+                .collect::<Vec<usize>>();
+            let mut newly_eliminated = round
+                .eliminated_candidates
+                .iter()
+                .filter(|i| !decided.contains(i))
+                .copied()
+                .collect::<Vec<usize>>();
+            newly_elected.sort();
+            newly_eliminated.sort();
+
+            for option_index in newly_elected.iter().chain(newly_eliminated.iter()) {
+                let level = (0..report.candidates.len())
+                    .filter(|i| {
+                        !decided.contains(i)
+                            && round.vote_values[i] == round.vote_values[option_index]
+                    })
+                    .collect::<Vec<usize>>();
+                if level.len() > 1 {
+                    ties.push(level);
+                }
+            }
+
+            decided.extend(newly_elected.iter().chain(newly_eliminated.iter()));
+            elected.extend(newly_elected);
+            removed.extend(newly_eliminated);
+        }
+
+        let mut standing = (0..report.candidates.len())
+            .filter(|i| !decided.contains(i))
+            .collect::<Vec<usize>>();
+        let final_value = |i: &usize| {
+            report
+                .rounds
+                .last()
+                .map_or(0.0, |round| round.vote_values[i])
+        };
+        standing.sort_by(|a, b| final_value(b).total_cmp(&final_value(a)).then(a.cmp(b)));
+        let mut places: Vec<Vec<usize>> = Vec::new();
+        for (i, option_index) in standing.iter().enumerate() {
+            if i > 0 && final_value(&standing[i - 1]) == final_value(option_index) {
+                places.last_mut().unwrap().push(*option_index);
+            } else {
+                places.push(vec![*option_index]);
+            }
+        }
+        ties.extend(level_places(&places));
+        places.extend(removed.iter().rev().map(|i| vec![*i]));
+        if !report.starting_eliminated_candidates.is_empty() {
+            places.push(report.starting_eliminated_candidates.clone());
+        }
+
+        Outcome::new(elected, places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::Rounding;
+
+    fn vote(votes: Vec<usize>) -> StvVote {
+        StvVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_result_two_seats() {
+        let election = StvElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            elected_count: 2,
+            transfer: StvTransfer::Random,
+            quota: StvQuota::Hare,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Arithmetic::Exact,
+        };
+
+        let votes = vec![
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 1, 2]),
+            vote(vec![0, 2, 1]),
+            vote(vec![1, 0, 2]),
+            vote(vec![1, 2, 0]),
+            vote(vec![2, 1, 0]),
+        ];
+
+        let mut result = get_result(&election, &votes, &[]);
+        result.elected_candidates.sort();
+        assert_eq!(result.elected_candidates, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_get_result_random_weighted_ballots() {
+        let election = StvElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            elected_count: 2,
+            transfer: StvTransfer::Random,
+            quota: StvQuota::Droop,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Arithmetic::Exact,
+        };
+
+        // A million papers are drawn from without copying the ballot
+        let votes = vec![
+            StvVote {
+                weight: Weight::whole(1_000_000),
+                ..vote(vec![0, 1])
+            },
+            vote(vec![1]),
+        ];
+
+        let result = get_result(&election, &votes, &[]);
+        assert_eq!(result.quota, 333_334.0);
+        assert_eq!(result.rounds[0].vote_counts[&0], 1_000_000);
+        assert_eq!(result.elected_candidates.len(), 2);
+        // The quota is taken out of the heavy ballot and the rest moves on
+        assert_eq!(result.rounds[1].vote_counts[&1], 666_667);
+        assert_eq!(result.vote_count, 2);
+    }
+
+    fn gregory_election(transfer: StvTransfer, quota: StvQuota) -> StvElection {
+        StvElection {
+            options: vec![
+                "A".to_string(),
+                "B".to_string(),
+                "C".to_string(),
+                "D".to_string(),
+            ],
+            elected_count: 2,
+            transfer,
+            quota,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+            arithmetic: Arithmetic::Exact,
+        }
+    }
+
+    fn gregory_votes() -> Vec<StvVote> {
+        let mut votes = vec![];
+        for _ in 0..6 {
+            votes.push(vote(vec![0, 1]));
+        }
+        for _ in 0..2 {
+            votes.push(vote(vec![0]));
+        }
+        for _ in 0..3 {
+            votes.push(vote(vec![2]));
+        }
+        votes.push(vote(vec![3]));
+        votes
+    }
+
+    #[test]
+    fn test_get_result_weighted_inclusive_gregory() {
+        let election = gregory_election(StvTransfer::WeightedInclusiveGregory, StvQuota::Droop);
+
+        let result = get_result(&election, &gregory_votes(), &[]);
+        assert_eq!(result.quota, 5.0);
+        assert_eq!(result.elected_candidates, vec![0, 2]);
+        // A's surplus of 3 moves every ballot on at 3 / 8
+        let transfer = &result.rounds[0].transfers[0];
+        assert_eq!(transfer.from, 0);
+        assert_eq!(transfer.ballot_count, 8);
+        assert_eq!(transfer.ballot_value, 1.0);
+        assert_eq!(transfer.transfer_value, 0.375);
+        assert_eq!(transfer.to[&1], 6);
+        assert_eq!(transfer.exhausted, 2);
+        assert_eq!(result.rounds[1].vote_values[&1], 2.25);
+        assert_eq!(result.rounds[1].vote_values[&0], 5.0);
+    }
+
+    #[test]
+    fn test_get_result_gregory_fixed_point() {
+        let mut election = gregory_election(StvTransfer::WeightedInclusiveGregory, StvQuota::Droop);
+        election.arithmetic = Arithmetic::FixedPoint {
+            places: 2,
+            rounding: Rounding::Down,
+        };
+
+        let result = get_result(&election, &gregory_votes(), &[]);
+        // 3 / 8 is cut to 0.37, losing 0.005 on each of the 6 moved ballots
+        let transfer = &result.rounds[0].transfers[0];
+        assert_eq!(transfer.transfer_value, 0.37);
+        assert_eq!(transfer.rounding_loss, Number::new(3, 100));
+        assert_eq!(result.rounds[1].vote_values[&1], 2.22);
+
+        election.arithmetic = Arithmetic::Exact;
+        let result = get_result(&election, &gregory_votes(), &[]);
+        assert!(result.rounds[0].transfers[0].rounding_loss.is_zero());
+    }
+
+    #[test]
+    fn test_get_result_gregory() {
+        let election = gregory_election(StvTransfer::Gregory, StvQuota::Droop);
+
+        let result = get_result(&election, &gregory_votes(), &[]);
+        // Only the 6 ballots with a further preference share the surplus
+        let transfer = &result.rounds[0].transfers[0];
+        assert_eq!(transfer.transfer_value, 0.5);
+        assert_eq!(result.rounds[1].vote_values[&1], 3.0);
+        assert_eq!(result.rounds[1].vote_counts[&0], 2);
+        // D is excluded and exhausts, then B and C are level on 3 and B
+        // goes on fewer first preferences
+        assert_eq!(result.rounds[1].transfers[0].from, 3);
+        assert_eq!(result.elected_candidates, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_get_result_gregory_weighted_ballots() {
+        let election = gregory_election(StvTransfer::Gregory, StvQuota::Droop);
+
+        // One ballot for each group of identical ballots, weighted by its size
+        let weighted = |votes: Vec<usize>, weight: u64| StvVote {
+            weight: Weight::whole(weight),
+            ..vote(votes)
+        };
+        let votes = vec![
+            weighted(vec![0, 1], 6),
+            weighted(vec![0], 2),
+            weighted(vec![2], 3),
+            weighted(vec![3], 1),
+        ];
+
+        let result = get_result(&election, &votes, &[]);
+        let expected = get_result(&election, &gregory_votes(), &[]);
+        assert_eq!(result.quota, expected.quota);
+        assert_eq!(result.elected_candidates, expected.elected_candidates);
+        let transfer = &result.rounds[0].transfers[0];
+        assert_eq!(transfer.ballot_count, 8);
+        assert_eq!(transfer.transfer_value, 0.5);
+        assert_eq!(transfer.to[&1], 6);
+        assert_eq!(result.rounds[1].vote_values[&1], 3.0);
+        assert_eq!(result.vote_count, 4);
+    }
+
+    #[test]
+    fn test_quota_values() {
+        assert_eq!(StvQuota::Hare.value(12, 2), 6.0);
+        assert_eq!(StvQuota::Droop.value(12, 2), 5.0);
+        assert_eq!(StvQuota::HagenbachBischoff.value(12, 2), 4.0);
+        assert!(!StvQuota::HagenbachBischoff.is_reached(4.0, 4.0));
+        assert!(StvQuota::Droop.is_reached(5.0, 5.0));
+    }
+
+    #[test]
+    fn test_setting_keys() {
+        for transfer in [
+            StvTransfer::Random,
+            StvTransfer::Gregory,
+            StvTransfer::WeightedInclusiveGregory,
+        ] {
+            assert_eq!(StvTransfer::from_str(transfer.key()), Ok(transfer));
+        }
+        for quota in [StvQuota::Hare, StvQuota::Droop, StvQuota::HagenbachBischoff] {
+            assert_eq!(StvQuota::from_str(quota.key()), Ok(quota));
+        }
+        assert_eq!(StvTransfer::from_str("meek"), Err(ParseStvTransferError));
+        assert_eq!(StvQuota::from_str("imperiali"), Err(ParseStvQuotaError));
+    }
+}
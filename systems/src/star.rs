@@ -0,0 +1,202 @@
+use std::cmp::Reverse;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// Stars between 0 and 5 given to each option
+    pub votes: Vec<usize>,
+}
+
+impl_weighted_ballot!(StarVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarTally {
+    pub option_index: usize,
+    pub points_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarRunoffScore {
+    pub option_index: usize,
+    pub vote_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarResult {
+    pub options: Vec<String>,
+    pub points_tally: Vec<StarTally>,
+    pub runoff: Vec<StarRunoffScore>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub votes: Vec<StarVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(election: &StarElection, votes: &[StarVote]) -> StarResult {
+    let mut points_tally = vec![0; election.options.len()];
+    for (vote, weight) in weighted(votes) {
+        for (i, points) in vote.votes.iter().enumerate() {
+            points_tally[i] += *points * weight;
+        }
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+    tie_breaker.record_round(points_tally.iter().map(|i| *i as f64));
+    let finalists = tie_breaker.take_top(
+        &rank_by_score(points_tally.iter().copied().enumerate()),
+        2,
+        |_, place| place.to_vec(),
+    );
+
+    let mut points_tally: Vec<StarTally> = points_tally
+        .into_iter()
+        .enumerate()
+        .map(|(i, points)| StarTally {
+            option_index: i,
+            points_count: points,
+        })
+        .collect();
+    points_tally.sort_by_key(|a| Reverse(a.points_count));
+
+    // Run off
+    let mut a = StarRunoffScore {
+        option_index: finalists[0],
+        vote_count: 0,
+    };
+    let mut b = StarRunoffScore {
+        option_index: finalists[1],
+        vote_count: 0,
+    };
+    for (vote, weight) in weighted(votes) {
+        let a_votes = vote.votes[a.option_index];
+        let b_votes = vote.votes[b.option_index];
+        if a_votes == b_votes {
+            continue;
+        }
+
+        if a_votes > b_votes {
+            a.vote_count += weight;
+        } else {
+            b.vote_count += weight;
+        }
+    }
+
+    let mut runoff_tally = vec![0.0; election.options.len()];
+    runoff_tally[a.option_index] = a.vote_count as f64;
+    runoff_tally[b.option_index] = b.vote_count as f64;
+    tie_breaker.record_round(runoff_tally);
+    let winner = match a.vote_count.cmp(&b.vote_count) {
+        std::cmp::Ordering::Greater => a.option_index,
+        std::cmp::Ordering::Less => b.option_index,
+        std::cmp::Ordering::Equal => {
+            tie_breaker.break_tie(TieKind::Elect, &[a.option_index, b.option_index], |_| {
+                a.option_index
+            })
+        }
+    };
+
+    let mut runoff = vec![a, b];
+    runoff.sort_by_key(|a| Reverse(a.vote_count));
+
+    StarResult {
+        options: election.options.clone(),
+        points_tally,
+        winner,
+        runoff,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct Star;
+
+impl VotingSystem for Star {
+    type Ballot = StarVote;
+    type Config = StarElection;
+    type Outcome = StarResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let finalists = report
+            .runoff
+            .iter()
+            .map(|i| i.option_index)
+            .collect::<Vec<usize>>();
+        let mut places =
+            rank_by_score(report.runoff.iter().map(|i| (i.option_index, i.vote_count)));
+        places.extend(rank_by_score(
+            report
+                .points_tally
+                .iter()
+                .filter(|i| !finalists.contains(&i.option_index))
+                .map(|i| (i.option_index, i.points_count)),
+        ));
+
+        Outcome::from_places(vec![report.winner], places)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<usize>) -> StarVote {
+        StarVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_result_runoff_overturns_score() {
+        let election = StarElection {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // A has the highest total but more voters prefer B in the runoff
+        let votes = vec![
+            vote(vec![5, 0, 0]),
+            vote(vec![5, 0, 0]),
+            vote(vec![3, 4, 0]),
+            vote(vec![3, 4, 0]),
+            vote(vec![3, 4, 0]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.points_tally[0].option_index, 0);
+        assert_eq!(result.points_tally[0].points_count, 19);
+        assert_eq!(result.winner, 1);
+    }
+}
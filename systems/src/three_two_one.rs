@@ -0,0 +1,324 @@
+use std::cmp::Reverse;
+
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    ranked_choice::{tally_ranked_votes, RankedChoiceVote, RankedChoiceVoteTally},
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThreeTwoOneElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used to break semifinalist and finalist ties
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GoodOkBad {
+    Good,
+    Ok,
+    Bad,
+}
+
+impl From<GoodOkBad> for i32 {
+    fn from(val: GoodOkBad) -> Self {
+        match val {
+            GoodOkBad::Bad => 0,
+            GoodOkBad::Ok => 1,
+            GoodOkBad::Good => 2,
+        }
+    }
+}
+
+impl From<GoodOkBad> for usize {
+    fn from(val: GoodOkBad) -> Self {
+        match val {
+            GoodOkBad::Bad => 0,
+            GoodOkBad::Ok => 1,
+            GoodOkBad::Good => 2,
+        }
+    }
+}
+
+impl From<i32> for GoodOkBad {
+    fn from(val: i32) -> Self {
+        match val {
+            0 => GoodOkBad::Bad,
+            1 => GoodOkBad::Ok,
+            2 => GoodOkBad::Good,
+            _ => panic!("Invalid value for GoodOkBad"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThreeTwoOneVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    pub votes: Vec<GoodOkBad>,
+}
+
+impl_weighted_ballot!(ThreeTwoOneVote);
+
+impl RankedChoiceVote<usize> for ThreeTwoOneVote {
+    fn ranked_votes(&self) -> Vec<usize> {
+        self.votes.iter().map(|i| usize::from(*i)).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ThreeTwoOneTally {
+    pub option_index: usize,
+    pub good_count: usize,
+    pub ok_count: usize,
+    pub bad_count: usize,
+    pub score: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThreeTwoOneResult {
+    pub options: Vec<String>,
+    pub points_tally: Vec<ThreeTwoOneTally>,
+    pub semifinalists: Vec<usize>,
+    pub finalists: Vec<usize>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub vote_tally: Vec<RankedChoiceVoteTally<usize>>,
+    pub ties: Vec<TieRecord>,
+}
+
+fn break_semifinalist_tie(rng: &mut Pcg64, tied: &[&ThreeTwoOneTally]) -> usize {
+    let max_score = tied.iter().map(|i| i.score).max().unwrap();
+    let mut matching_max_score = tied
+        .iter()
+        .filter(|i| i.score == max_score)
+        .collect::<Vec<_>>();
+
+    matching_max_score.shuffle(rng);
+    matching_max_score[0].option_index
+}
+
+fn break_finalist_tie(rng: &mut Pcg64, tied: &[&ThreeTwoOneTally]) -> usize {
+    let min_score = tied.iter().map(|i| i.score).min().unwrap();
+    let mut matching_max_score = tied
+        .iter()
+        .filter(|i| i.score == min_score)
+        .collect::<Vec<_>>();
+
+    matching_max_score.shuffle(rng);
+    matching_max_score[0].option_index
+}
+
+fn option_indexes(tallies: &[&ThreeTwoOneTally]) -> Vec<usize> {
+    tallies.iter().map(|i| i.option_index).collect()
+}
+
+pub fn get_result(election: &ThreeTwoOneElection, votes: &[ThreeTwoOneVote]) -> ThreeTwoOneResult {
+    // https://electowiki.org/wiki/3-2-1_voting
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes)
+                .map(|(vote, weight)| (vote.votes.iter().map(|i| i32::from(*i)).collect(), weight)),
+        )
+    });
+
+    let mut points_tally = Vec::new();
+    for i in 0..election.options.len() {
+        points_tally.push(ThreeTwoOneTally {
+            option_index: i,
+            good_count: 0,
+            ok_count: 0,
+            bad_count: 0,
+            score: 0,
+        });
+    }
+
+    for (vote, weight) in weighted(votes) {
+        for (i, points) in vote.votes.iter().enumerate() {
+            match points {
+                GoodOkBad::Good => points_tally[i].good_count += weight,
+                GoodOkBad::Ok => points_tally[i].ok_count += weight,
+                GoodOkBad::Bad => points_tally[i].bad_count += weight,
+            }
+
+            let score: usize = (*points).into();
+            points_tally[i].score += score * weight;
+        }
+    }
+
+    let mut good_counts = vec![0.0; election.options.len()];
+    let mut bad_counts = vec![0.0; election.options.len()];
+    for tally in &points_tally {
+        good_counts[tally.option_index] = tally.good_count as f64;
+        // Fewer bad ratings puts an option ahead
+        bad_counts[tally.option_index] = -(tally.bad_count as f64);
+    }
+    tie_breaker.record_round(good_counts);
+
+    // First sort by good count
+    points_tally.sort_by(|a, b| a.good_count.cmp(&b.good_count).reverse());
+
+    let mut semifinalists = Vec::new();
+    {
+        let mut good_tally = points_tally.clone();
+        while semifinalists.len() < 3 {
+            let matching_top = good_tally
+                .iter()
+                .filter(|i| i.good_count == good_tally[0].good_count)
+                .collect::<Vec<_>>();
+
+            let to_add =
+                tie_breaker.break_tie(TieKind::Elect, &option_indexes(&matching_top), |rng| {
+                    break_semifinalist_tie(rng, &matching_top)
+                });
+            let position = good_tally
+                .iter()
+                .position(|i| i.option_index == to_add)
+                .unwrap();
+            semifinalists.push(good_tally.remove(position));
+        }
+    }
+
+    // Then sort by bad count
+    tie_breaker.record_round(bad_counts);
+    semifinalists.sort_by_key(|a| Reverse(a.bad_count));
+    let mut finalists = Vec::new();
+    {
+        let mut bad_tally = semifinalists.clone();
+        let matching_bottom = bad_tally
+            .iter()
+            .filter(|i| i.bad_count == bad_tally[0].bad_count)
+            .collect::<Vec<_>>();
+
+        let to_remove =
+            tie_breaker.break_tie(TieKind::Exclude, &option_indexes(&matching_bottom), |rng| {
+                break_finalist_tie(rng, &matching_bottom)
+            });
+        bad_tally.remove(
+            bad_tally
+                .iter()
+                .position(|i| i.option_index == to_remove)
+                .unwrap(),
+        );
+        finalists.push(bad_tally[0]);
+        finalists.push(bad_tally[1]);
+    }
+
+    points_tally.sort_by(|a, b| a.score.cmp(&b.score).reverse());
+
+    // The winner is the finalist who is rated above the other on more ballots
+    let a_index = finalists[0].option_index;
+    let b_index = finalists[1].option_index;
+    let mut a_count = 0;
+    let mut b_count = 0;
+    for (vote, weight) in weighted(votes) {
+        let a: i32 = vote.votes[a_index].into();
+        let b: i32 = vote.votes[b_index].into();
+        if a > b {
+            a_count += weight;
+        } else if b > a {
+            b_count += weight;
+        }
+    }
+
+    let mut runoff_tally = vec![0.0; election.options.len()];
+    runoff_tally[a_index] = a_count as f64;
+    runoff_tally[b_index] = b_count as f64;
+    tie_breaker.record_round(runoff_tally);
+    let winner = match a_count.cmp(&b_count) {
+        std::cmp::Ordering::Greater => a_index,
+        std::cmp::Ordering::Less => b_index,
+        std::cmp::Ordering::Equal => {
+            tie_breaker.break_tie(TieKind::Elect, &[a_index, b_index], |_| b_index)
+        }
+    };
+
+    ThreeTwoOneResult {
+        vote_tally: tally_ranked_votes(votes),
+        options: election.options.clone(),
+        semifinalists: semifinalists.iter().map(|i| i.option_index).collect(),
+        finalists: finalists.iter().map(|i| i.option_index).collect(),
+        points_tally,
+        winner,
+        vote_count: votes.len(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct ThreeTwoOne;
+
+impl VotingSystem for ThreeTwoOne {
+    type Ballot = ThreeTwoOneVote;
+    type Config = ThreeTwoOneElection;
+    type Outcome = ThreeTwoOneResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let finalists = report.finalists.clone();
+        let semifinalists = report.semifinalists.clone();
+
+        let mut places = vec![finalists, semifinalists.clone()];
+        places.extend(rank_by_score(
+            report
+                .points_tally
+                .iter()
+                .filter(|i| !semifinalists.contains(&i.option_index))
+                .map(|i| (i.option_index, i.score)),
+        ));
+
+        Outcome::new(vec![report.winner], places, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result() {
+        use GoodOkBad::*;
+
+        let election = ThreeTwoOneElection {
+            options: vec![
+                "A".to_string(),
+                "B".to_string(),
+                "C".to_string(),
+                "D".to_string(),
+            ],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // D never makes the semifinals, C has the most bad ratings and B beats A head to head
+        let votes = [
+            vec![Good, Ok, Bad, Bad],
+            vec![Good, Good, Bad, Bad],
+            vec![Ok, Good, Good, Bad],
+            vec![Bad, Good, Bad, Ok],
+        ]
+        .into_iter()
+        .map(|votes| ThreeTwoOneVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        })
+        .collect::<Vec<_>>();
+
+        let result = get_result(&election, &votes);
+        assert!(!result.semifinalists.contains(&3));
+        assert!(!result.finalists.contains(&2));
+        assert_eq!(result.winner, 1);
+    }
+}
@@ -0,0 +1,452 @@
+//! Tie breaking shared by every count. An election picks a policy, the count
+//! hands each tie it cannot separate to a `TieBreaker` and the result carries
+//! the record of every tie and how it was settled.
+
+use std::{fmt, str::FromStr};
+
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+use serde_derive::{Deserialize, Serialize};
+
+/// How ties are broken, any policy that leaves options level falls back to lot
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum TieBreak {
+    /// The rule each method used before a policy could be chosen, so older
+    /// elections count the same way they always have
+    #[default]
+    Method,
+    /// The option ahead at the earliest round where the tied options differ
+    ForwardCountBack,
+    /// The option ahead at the most recent earlier round where they differ
+    BackwardCountBack,
+    /// The option ahead at the round just before the tie
+    PriorRound,
+    /// The option with the highest Borda score across every ballot
+    Borda,
+    /// Drawn by lot from the election's seed
+    Lot,
+    /// The option listed first is favoured, unlisted options come after the
+    /// listed ones by index
+    FixedOrder { order: Vec<usize> },
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TieBreak::Method => write!(f, "method"),
+            TieBreak::ForwardCountBack => write!(f, "forward_count_back"),
+            TieBreak::BackwardCountBack => write!(f, "backward_count_back"),
+            TieBreak::PriorRound => write!(f, "prior_round"),
+            TieBreak::Borda => write!(f, "borda"),
+            TieBreak::Lot => write!(f, "lot"),
+            TieBreak::FixedOrder { order } => write!(
+                f,
+                "fixed_order:{}",
+                order
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTieBreakError;
+
+impl fmt::Display for ParseTieBreakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tie break must be method, forward_count_back, backward_count_back, prior_round, borda, lot or fixed_order:0,1,.."
+        )
+    }
+}
+
+impl std::error::Error for ParseTieBreakError {}
+
+impl FromStr for TieBreak {
+    type Err = ParseTieBreakError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "method" => TieBreak::Method,
+            "forward_count_back" => TieBreak::ForwardCountBack,
+            "backward_count_back" => TieBreak::BackwardCountBack,
+            "prior_round" => TieBreak::PriorRound,
+            "borda" => TieBreak::Borda,
+            "lot" => TieBreak::Lot,
+            s => {
+                let order = s.strip_prefix("fixed_order:").ok_or(ParseTieBreakError)?;
+                TieBreak::FixedOrder {
+                    order: order
+                        .split(',')
+                        .filter(|i| !i.trim().is_empty())
+                        .map(|i| i.trim().parse().map_err(|_| ParseTieBreakError))
+                        .collect::<Result<Vec<usize>, ParseTieBreakError>>()?,
+                }
+            }
+        })
+    }
+}
+
+/// Which side of a tie the count is choosing
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieKind {
+    /// The option to elect, advance or place ahead
+    Elect,
+    /// The option to exclude or place behind
+    Exclude,
+}
+
+/// What settled a tie
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum TieResolution {
+    /// The method's own rule, see `TieBreak::Method`
+    Method,
+    /// The tallies of an earlier round, counting rounds from zero
+    CountBack {
+        round: usize,
+    },
+    Borda,
+    Lot,
+    FixedOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TieRecord {
+    /// The latest round the count had recorded when the tie arose
+    pub round: usize,
+    pub kind: TieKind,
+    pub tied: Vec<usize>,
+    pub chosen: usize,
+    pub resolved_by: TieResolution,
+}
+
+/// Breaks ties through a count by the election's policy, holding the round
+/// tallies a count back looks at and the record of each tie it breaks
+pub struct TieBreaker {
+    policy: TieBreak,
+    rng: Pcg64,
+    rounds: Vec<Vec<f64>>,
+    borda: Option<Vec<f64>>,
+    ties: Vec<TieRecord>,
+}
+
+impl TieBreaker {
+    pub fn new(policy: &TieBreak, seed: &str) -> TieBreaker {
+        TieBreaker {
+            policy: policy.clone(),
+            rng: Seeder::from(seed).make_rng(),
+            rounds: Vec::new(),
+            borda: None,
+            ties: Vec::new(),
+        }
+    }
+
+    /// Borda scores by option, only worked out when the policy needs them
+    pub fn with_borda(mut self, scores: impl FnOnce() -> Vec<f64>) -> TieBreaker {
+        if self.policy == TieBreak::Borda {
+            self.borda = Some(scores());
+        }
+        self
+    }
+
+    pub fn policy(&self) -> &TieBreak {
+        &self.policy
+    }
+
+    /// The seeded generator, for the draws a method makes other than ties
+    pub fn rng(&mut self) -> &mut Pcg64 {
+        &mut self.rng
+    }
+
+    /// Records each option's tally for a round of the count
+    pub fn record_round(&mut self, tally: impl IntoIterator<Item = f64>) {
+        self.rounds.push(tally.into_iter().collect());
+    }
+
+    /// Picks one of the `tied` options. `method` is the method's own rule and
+    /// is only used under `TieBreak::Method`, where it is asked even when a
+    /// single option is left so it draws from the RNG just as it always has
+    pub fn break_tie(
+        &mut self,
+        kind: TieKind,
+        tied: &[usize],
+        method: impl FnOnce(&mut Pcg64) -> usize,
+    ) -> usize {
+        let (chosen, resolved_by) = match self.policy {
+            TieBreak::Method => (method(&mut self.rng), TieResolution::Method),
+            _ if tied.len() == 1 => return tied[0],
+            _ => self.resolve(kind, tied),
+        };
+        if tied.len() < 2 {
+            return chosen;
+        }
+        self.ties.push(TieRecord {
+            round: self.rounds.len().saturating_sub(1),
+            kind,
+            tied: tied.to_vec(),
+            chosen,
+            resolved_by,
+        });
+        chosen
+    }
+
+    /// Puts the `tied` options in order, the first being the most favoured
+    /// for `TieKind::Elect` or the first to go for `TieKind::Exclude`
+    pub fn order(
+        &mut self,
+        kind: TieKind,
+        tied: &[usize],
+        method: impl FnOnce(&mut Pcg64) -> Vec<usize>,
+    ) -> Vec<usize> {
+        if tied.len() < 2 {
+            return tied.to_vec();
+        }
+
+        if self.policy == TieBreak::Method {
+            let order = method(&mut self.rng);
+            let round = self.rounds.len().saturating_sub(1);
+            for i in 0..order.len() - 1 {
+                self.ties.push(TieRecord {
+                    round,
+                    kind,
+                    tied: order[i..].to_vec(),
+                    chosen: order[i],
+                    resolved_by: TieResolution::Method,
+                });
+            }
+            return order;
+        }
+
+        let mut remaining = tied.to_vec();
+        let mut order = Vec::new();
+        while !remaining.is_empty() {
+            let chosen = self.break_tie(kind, &remaining, |_| unreachable!());
+            remaining.retain(|i| *i != chosen);
+            order.push(chosen);
+        }
+        order
+    }
+
+    /// The first `count` options from `places`, best place first, ordering
+    /// the place that straddles the cut so only part of it is taken
+    pub fn take_top(
+        &mut self,
+        places: &[Vec<usize>],
+        count: usize,
+        method: impl FnOnce(&mut Pcg64, &[usize]) -> Vec<usize>,
+    ) -> Vec<usize> {
+        let mut taken = Vec::new();
+        for place in places {
+            if taken.len() + place.len() <= count {
+                taken.extend(place);
+                continue;
+            }
+            let needed = count - taken.len();
+            if needed > 0 {
+                let order = self.order(TieKind::Elect, place, |rng| method(rng, place));
+                taken.extend(order.into_iter().take(needed));
+            }
+            break;
+        }
+        taken
+    }
+
+    /// Starts a further count on the same generator, forgetting the rounds
+    /// and ties recorded so far
+    pub fn restart(self) -> TieBreaker {
+        TieBreaker {
+            rounds: Vec::new(),
+            ties: Vec::new(),
+            ..self
+        }
+    }
+
+    pub fn ties(&self) -> &[TieRecord] {
+        &self.ties
+    }
+
+    pub fn into_ties(self) -> Vec<TieRecord> {
+        self.ties
+    }
+
+    fn resolve(&mut self, kind: TieKind, tied: &[usize]) -> (usize, TieResolution) {
+        let mut level = tied.to_vec();
+        let earlier = self.rounds.len().saturating_sub(1);
+        let resolved_by = match &self.policy {
+            TieBreak::ForwardCountBack => (0..earlier).find(|round| {
+                narrow(&mut level, kind, &self.rounds[*round]);
+                level.len() == 1
+            }),
+            TieBreak::BackwardCountBack => (0..earlier).rev().find(|round| {
+                narrow(&mut level, kind, &self.rounds[*round]);
+                level.len() == 1
+            }),
+            TieBreak::PriorRound => earlier.checked_sub(1).filter(|round| {
+                narrow(&mut level, kind, &self.rounds[*round]);
+                level.len() == 1
+            }),
+            TieBreak::Borda => {
+                if let Some(borda) = &self.borda {
+                    narrow(&mut level, kind, borda);
+                }
+                if level.len() == 1 {
+                    return (level[0], TieResolution::Borda);
+                }
+                None
+            }
+            TieBreak::FixedOrder { order } => {
+                let position =
+                    |i: &usize| order.iter().position(|j| j == i).unwrap_or(order.len() + i);
+                let chosen = match kind {
+                    TieKind::Elect => level.iter().min_by_key(|i| position(i)),
+                    TieKind::Exclude => level.iter().max_by_key(|i| position(i)),
+                };
+                return (*chosen.unwrap(), TieResolution::FixedOrder);
+            }
+            TieBreak::Lot | TieBreak::Method => None,
+        };
+
+        match resolved_by {
+            Some(round) => (level[0], TieResolution::CountBack { round }),
+            None => (*level.choose(&mut self.rng).unwrap(), TieResolution::Lot),
+        }
+    }
+}
+
+/// Keeps the level options with the highest score, or lowest when excluding
+fn narrow(level: &mut Vec<usize>, kind: TieKind, scores: &[f64]) {
+    let values = level.iter().map(|i| scores[*i]);
+    let target = match kind {
+        TieKind::Elect => values.fold(f64::MIN, f64::max),
+        TieKind::Exclude => values.fold(f64::MAX, f64::min),
+    };
+    level.retain(|i| scores[*i] == target);
+}
+
+/// Borda scores from ranked ballots, a point for every option ranked below
+/// and unranked options counting as below every ranked one
+pub fn borda_from_rankings<'a>(
+    option_count: usize,
+    ballots: impl IntoIterator<Item = (&'a [usize], usize)>,
+) -> Vec<f64> {
+    let mut scores = vec![0.0; option_count];
+    for (ranking, weight) in ballots {
+        for (position, option_index) in ranking.iter().enumerate() {
+            scores[*option_index] += (option_count - 1 - position) as f64 * weight as f64;
+        }
+    }
+    scores
+}
+
+/// Borda scores from ballots that give each option a value, higher being
+/// better, a point for every option valued lower and half for every one
+/// valued the same
+pub fn borda_from_values<V: PartialOrd>(
+    option_count: usize,
+    ballots: impl IntoIterator<Item = (Vec<V>, usize)>,
+) -> Vec<f64> {
+    let mut scores = vec![0.0; option_count];
+    for (values, weight) in ballots {
+        for (i, value) in values.iter().enumerate() {
+            let points: f64 = values
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other)| {
+                    if value > other {
+                        1.0
+                    } else if value == other {
+                        0.5
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            scores[i] += points * weight as f64;
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(policy: TieBreak) -> TieBreaker {
+        let mut breaker = TieBreaker::new(&policy, "test");
+        breaker.record_round([5.0, 3.0, 3.0, 1.0]);
+        breaker.record_round([6.0, 4.0, 5.0, 1.0]);
+        breaker.record_round([7.0, 6.0, 6.0, 6.0]);
+        breaker
+    }
+
+    #[test]
+    fn test_count_back_directions() {
+        let mut forward = breaker(TieBreak::ForwardCountBack);
+        assert_eq!(forward.break_tie(TieKind::Exclude, &[1, 2, 3], |_| 0), 3);
+        assert_eq!(
+            forward.ties()[0].resolved_by,
+            TieResolution::CountBack { round: 0 }
+        );
+
+        let mut backward = breaker(TieBreak::BackwardCountBack);
+        assert_eq!(backward.break_tie(TieKind::Elect, &[1, 2], |_| 0), 2);
+        assert_eq!(
+            backward.ties()[0].resolved_by,
+            TieResolution::CountBack { round: 1 }
+        );
+        assert_eq!(backward.ties()[0].round, 2);
+
+        let mut prior = breaker(TieBreak::PriorRound);
+        assert_eq!(prior.break_tie(TieKind::Exclude, &[1, 2], |_| 0), 1);
+    }
+
+    #[test]
+    fn test_fixed_order_and_fallback_to_lot() {
+        let mut fixed = breaker(TieBreak::FixedOrder { order: vec![3, 1] });
+        assert_eq!(
+            fixed.order(TieKind::Elect, &[1, 2, 3], |_| vec![]),
+            [3, 1, 2]
+        );
+        assert_eq!(fixed.ties().len(), 2);
+
+        // Nothing separates the options so the policy falls back to lot
+        let mut borda = TieBreaker::new(&TieBreak::Borda, "test").with_borda(|| vec![1.0, 1.0]);
+        borda.break_tie(TieKind::Elect, &[0, 1], |_| 0);
+        assert_eq!(borda.ties()[0].resolved_by, TieResolution::Lot);
+
+        let mut method = breaker(TieBreak::Method);
+        assert_eq!(method.break_tie(TieKind::Elect, &[1, 2], |_| 2), 2);
+        assert_eq!(method.ties()[0].resolved_by, TieResolution::Method);
+    }
+
+    #[test]
+    fn test_parse_tie_break() {
+        for policy in [
+            TieBreak::Method,
+            TieBreak::BackwardCountBack,
+            TieBreak::FixedOrder {
+                order: vec![2, 0, 1],
+            },
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+        assert!("coin".parse::<TieBreak>().is_err());
+        assert!("fixed_order:a".parse::<TieBreak>().is_err());
+    }
+
+    #[test]
+    fn test_borda_scores() {
+        let a: &[usize] = &[0, 1];
+        assert_eq!(borda_from_rankings(3, [(a, 2)]), [4.0, 2.0, 0.0]);
+        assert_eq!(borda_from_values(3, [(vec![2, 2, 0], 1)]), [1.5, 1.5, 0.0]);
+    }
+}
@@ -0,0 +1,244 @@
+use log::info;
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    tie_break::{TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwoRoundElection {
+    pub options: Vec<String>,
+    /// The two options on the second ballot, fixed once the second round is
+    /// opened. Until then they are worked out from the first round
+    pub finalists: Option<Vec<usize>>,
+    /// Seeds the RNG used when options are level
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwoRoundVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    /// The round the ballot was cast in, 1 or 2
+    pub round: usize,
+    /// The option being voted for
+    pub vote: usize,
+}
+
+impl_weighted_ballot!(TwoRoundVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwoRoundResult {
+    pub options: Vec<String>,
+    /// First round votes needed to win outright
+    pub majority: usize,
+    pub first_round: Vec<usize>,
+    /// The option with a majority in the first round, if any
+    pub first_round_winner: Option<usize>,
+    /// None when the first round was decided or had no votes
+    pub finalists: Option<Vec<usize>>,
+    /// Votes for each finalist, in the order of `finalists`, once the second
+    /// round has ballots
+    pub second_round: Option<Vec<usize>>,
+    /// None while the second round is still to be held
+    pub winner: Option<usize>,
+    pub first_round_vote_count: usize,
+    pub second_round_vote_count: usize,
+    pub ties: Vec<TieRecord>,
+}
+
+fn tally(option_count: usize, votes: &[TwoRoundVote], round: usize) -> Vec<usize> {
+    let mut tally = vec![0; option_count];
+    for (vote, weight) in weighted(votes).filter(|(vote, _)| vote.round == round) {
+        tally[vote.vote] += weight;
+    }
+    tally
+}
+
+/// The two options with the most first round votes, ties put in order by the
+/// election's policy
+fn pick_finalists(tie_breaker: &mut TieBreaker, first_round: &[usize]) -> Vec<usize> {
+    let mut finalists = Vec::new();
+    for place in rank_by_score(first_round.iter().copied().enumerate()) {
+        let place = tie_breaker.order(TieKind::Elect, &place, |rng| {
+            let mut place = place.clone();
+            place.shuffle(rng);
+            place
+        });
+        finalists.extend(place.into_iter().take(2 - finalists.len()));
+        if finalists.len() == 2 {
+            break;
+        }
+    }
+    finalists
+}
+
+pub fn get_result(election: &TwoRoundElection, votes: &[TwoRoundVote]) -> TwoRoundResult {
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed);
+
+    let option_count = election.options.len();
+    let first_round = tally(option_count, votes, 1);
+    let first_round_vote_count: usize = first_round.iter().sum();
+    let majority = first_round_vote_count / 2 + 1;
+    info!("First round {:?}", first_round);
+    tie_breaker.record_round(first_round.iter().map(|i| *i as f64));
+
+    let first_round_winner = (0..option_count).find(|i| first_round[*i] >= majority);
+
+    let finalists = match (&election.finalists, first_round_winner) {
+        (Some(finalists), _) => Some(finalists.clone()),
+        (None, None) if first_round_vote_count > 0 && option_count > 1 => {
+            Some(pick_finalists(&mut tie_breaker, &first_round))
+        }
+        _ => None,
+    };
+
+    let second_round_tally = tally(option_count, votes, 2);
+    let second_round_vote_count: usize = second_round_tally.iter().sum();
+    let second_round = match &finalists {
+        Some(finalists) if second_round_vote_count > 0 => Some(
+            finalists
+                .iter()
+                .map(|i| second_round_tally[*i])
+                .collect::<Vec<usize>>(),
+        ),
+        _ => None,
+    };
+    info!("Second round {:?}", second_round);
+
+    let winner = match (first_round_winner, &finalists, &second_round) {
+        (Some(winner), _, _) => Some(winner),
+        (None, Some(finalists), Some(second_round)) => {
+            tie_breaker.record_round(second_round_tally.iter().map(|i| *i as f64));
+            let most = *second_round.iter().max().unwrap();
+            let level = finalists
+                .iter()
+                .zip(second_round)
+                .filter(|(_, count)| **count == most)
+                .map(|(i, _)| *i)
+                .collect::<Vec<usize>>();
+            Some(tie_breaker.break_tie(TieKind::Elect, &level, |rng| *level.choose(rng).unwrap()))
+        }
+        _ => None,
+    };
+
+    TwoRoundResult {
+        options: election.options.clone(),
+        majority,
+        first_round,
+        first_round_winner,
+        finalists,
+        second_round,
+        winner,
+        first_round_vote_count,
+        second_round_vote_count,
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct TwoRound;
+
+impl VotingSystem for TwoRound {
+    type Ballot = TwoRoundVote;
+    type Config = TwoRoundElection;
+    type Outcome = TwoRoundResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut places = Vec::new();
+        let mut ties = Vec::new();
+        if let (Some(finalists), Some(second_round)) = (&report.finalists, &report.second_round) {
+            let second_round_places =
+                rank_by_score(finalists.iter().copied().zip(second_round.iter().copied()));
+            ties.extend(
+                second_round_places
+                    .iter()
+                    .filter(|place| place.len() > 1)
+                    .cloned(),
+            );
+            places.extend(second_round_places);
+        }
+        let placed = places.concat();
+        places.extend(rank_by_score(
+            report
+                .first_round
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !placed.contains(i))
+                .map(|(i, count)| (i, *count)),
+        ));
+
+        Outcome::new(report.winner.into_iter().collect(), places, ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn votes(groups: &[(usize, usize, usize)]) -> Vec<TwoRoundVote> {
+        let mut votes = vec![];
+        for (count, round, vote) in groups {
+            for _ in 0..*count {
+                votes.push(TwoRoundVote {
+                    created_by: uuid::Uuid::new_v4(),
+                    weight: Weight::ONE,
+                    round: *round,
+                    vote: *vote,
+                });
+            }
+        }
+        votes
+    }
+
+    fn election(finalists: Option<Vec<usize>>) -> TwoRoundElection {
+        TwoRoundElection {
+            options: ["A", "B", "C"].iter().map(|i| i.to_string()).collect(),
+            finalists,
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        }
+    }
+
+    #[test]
+    fn test_get_result_first_round_majority() {
+        let votes = votes(&[(4, 1, 0), (2, 1, 1), (1, 1, 2)]);
+
+        let result = get_result(&election(None), &votes);
+        assert_eq!(result.majority, 4);
+        assert_eq!(result.first_round_winner, Some(0));
+        assert_eq!(result.finalists, None);
+        assert_eq!(result.winner, Some(0));
+    }
+
+    #[test]
+    fn test_get_result_second_round() {
+        let first_round = votes(&[(4, 1, 0), (3, 1, 1), (2, 1, 2)]);
+
+        // Without a majority the top two go through and nobody has won yet
+        let result = get_result(&election(None), &first_round);
+        assert_eq!(result.first_round_winner, None);
+        assert_eq!(result.finalists, Some(vec![0, 1]));
+        assert_eq!(result.second_round, None);
+        assert_eq!(result.winner, None);
+
+        let mut all_votes = first_round;
+        all_votes.extend(votes(&[(4, 2, 0), (5, 2, 1)]));
+        let result = get_result(&election(Some(vec![0, 1])), &all_votes);
+        assert_eq!(result.second_round, Some(vec![4, 5]));
+        assert_eq!(result.second_round_vote_count, 9);
+        assert_eq!(result.winner, Some(1));
+
+        let outcome = TwoRound::outcome(&result);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![0], vec![2]]);
+    }
+}
@@ -0,0 +1,426 @@
+use log::info;
+use rand::seq::SliceRandom;
+use serde_derive::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    tie_break::{borda_from_values, TieBreak, TieBreaker, TieKind, TieRecord},
+    voting_system::{rank_by_score, Outcome, VotingSystem},
+    weight::{impl_weighted_ballot, weighted, Weight},
+};
+
+// HERE https://en.m.wikipedia.org/wiki/Usual_judgment
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsualJudgmentElection {
+    pub options: Vec<String>,
+    /// Seeds the RNG used when a tie is drawn by lot
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq, Ord, PartialOrd, EnumIter,
+)]
+pub enum Grade {
+    Bad,
+    Inadequate,
+    Passable,
+    Fair,
+    Good,
+    VeryGood,
+    Excellent,
+}
+
+impl From<Grade> for i32 {
+    fn from(val: Grade) -> Self {
+        match val {
+            Grade::Excellent => 6,
+            Grade::VeryGood => 5,
+            Grade::Good => 4,
+            Grade::Fair => 3,
+            Grade::Passable => 2,
+            Grade::Inadequate => 1,
+            Grade::Bad => 0,
+        }
+    }
+}
+
+impl From<Grade> for usize {
+    fn from(val: Grade) -> Self {
+        match val {
+            Grade::Excellent => 6,
+            Grade::VeryGood => 5,
+            Grade::Good => 4,
+            Grade::Fair => 3,
+            Grade::Passable => 2,
+            Grade::Inadequate => 1,
+            Grade::Bad => 0,
+        }
+    }
+}
+
+impl From<Grade> for f64 {
+    fn from(val: Grade) -> Self {
+        match val {
+            Grade::Excellent => 6.0,
+            Grade::VeryGood => 5.0,
+            Grade::Good => 4.0,
+            Grade::Fair => 3.0,
+            Grade::Passable => 2.0,
+            Grade::Inadequate => 1.0,
+            Grade::Bad => 0.0,
+        }
+    }
+}
+
+impl From<i32> for Grade {
+    fn from(val: i32) -> Self {
+        match val {
+            6 => Grade::Excellent,
+            5 => Grade::VeryGood,
+            4 => Grade::Good,
+            3 => Grade::Fair,
+            2 => Grade::Passable,
+            1 => Grade::Inadequate,
+            0 => Grade::Bad,
+            _ => panic!("Invalid rating"),
+        }
+    }
+}
+
+impl From<usize> for Grade {
+    fn from(val: usize) -> Self {
+        match val {
+            6 => Grade::Excellent,
+            5 => Grade::VeryGood,
+            4 => Grade::Good,
+            3 => Grade::Fair,
+            2 => Grade::Passable,
+            1 => Grade::Inadequate,
+            0 => Grade::Bad,
+            _ => panic!("Invalid rating"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsualJudgmentVote {
+    pub created_by: uuid::Uuid,
+    #[serde(default)]
+    pub weight: Weight,
+    pub votes: Vec<Grade>,
+}
+
+impl_weighted_ballot!(UsualJudgmentVote);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsualJudgmentTally {
+    pub option_index: usize,
+    pub ratings: [i64; 7],
+}
+
+impl UsualJudgmentTally {
+    fn majority_grade(&self) -> Grade {
+        let vote_count = self.ratings.iter().sum::<i64>() as f64;
+        let mut sum = 0.0;
+        for (i, rating) in self.ratings.iter().enumerate() {
+            sum += (*rating as f64) / vote_count;
+            if sum >= 0.5 {
+                return i.into();
+            }
+        }
+
+        panic!("Impossible");
+    }
+
+    fn percent_for_grade(&self, grade: Grade) -> f64 {
+        let total: i64 = self.ratings.iter().sum();
+        let grade_index: usize = grade.into();
+        let count = self.ratings[grade_index];
+
+        count as f64 / total as f64
+    }
+
+    fn get_percent_above_grade(&self, target_grade: Grade) -> f64 {
+        let mut sum = 0.0;
+        for grade in Grade::iter() {
+            if grade > target_grade {
+                sum += self.percent_for_grade(grade);
+            }
+        }
+        sum
+    }
+
+    fn get_percent_below_grade(&self, target_grade: Grade) -> f64 {
+        let mut sum = 0.0;
+        for grade in Grade::iter() {
+            if grade < target_grade {
+                sum += self.percent_for_grade(grade);
+            }
+        }
+        sum
+    }
+
+    fn score_n(&self, n: f64) -> f64 {
+        let majority_grade = self.majority_grade();
+        let a: f64 = majority_grade.into();
+        let p: f64 = self.get_percent_above_grade(majority_grade).powf(n);
+        let q: f64 = self.get_percent_below_grade(majority_grade).powf(n);
+
+        let p_minus_q = p - q;
+
+        let n_a = a + 0.5 * (p_minus_q / (1.0 - p_minus_q));
+        let deviation = if n_a < a { -(a - n_a) } else { a - n_a };
+
+        info!(
+            "a:{} p:{} q:{} p-q:{} n_a:{} deviation:{}",
+            a, p, q, p_minus_q, n_a, deviation
+        );
+        deviation
+    }
+}
+
+fn highest_majority_grade(tally: &[UsualJudgmentTally]) -> Grade {
+    tally
+        .iter()
+        .map(|i| i.majority_grade())
+        .max()
+        .expect("No options in round")
+}
+
+fn options_majority_grade(tally: &[UsualJudgmentTally], majority_grade: Grade) -> Vec<usize> {
+    tally
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.majority_grade() == majority_grade)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsualJudgmentTallyCalculated {
+    pub option_index: usize,
+    pub ratings: [i64; 7],
+    pub average_grade: Grade,
+}
+
+impl From<UsualJudgmentTally> for UsualJudgmentTallyCalculated {
+    fn from(tally: UsualJudgmentTally) -> Self {
+        Self {
+            option_index: tally.option_index,
+            ratings: tally.ratings,
+            average_grade: tally.majority_grade(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Score {
+    pub option_index: usize,
+    pub score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BreakTie {
+    pub scores: Vec<Score>,
+    pub winner: usize,
+}
+
+fn break_tie(tie_breaker: &mut TieBreaker, options: &[UsualJudgmentTally]) -> BreakTie {
+    // Additional tie-breaking
+    let mut scores = Vec::new();
+    let mut level_options = Vec::new();
+    for level in 1..100 {
+        let next_scores: Vec<Score> = options
+            .iter()
+            .map(|i| {
+                info!("Getting score for {:?}", i.option_index);
+                Score {
+                    option_index: i.option_index,
+                    score: i.score_n(level as f64),
+                }
+            })
+            .collect::<Vec<_>>();
+        let best_next_score = next_scores
+            .iter()
+            .map(|i| i.score)
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap();
+        let matching_next_best_score = next_scores
+            .iter()
+            .filter(|i| i.score == best_next_score)
+            .map(|i| i.option_index)
+            .collect::<Vec<_>>();
+
+        if matching_next_best_score.len() == 1 {
+            return BreakTie {
+                scores: next_scores,
+                winner: matching_next_best_score[0],
+            };
+        }
+        scores = next_scores;
+        level_options = matching_next_best_score;
+    }
+
+    // Level at every step of the deviation so the election's policy decides
+    let winner = tie_breaker.break_tie(TieKind::Elect, &level_options, |rng| {
+        *level_options.choose(rng).unwrap()
+    });
+    BreakTie { scores, winner }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsualJudgmentResult {
+    pub options: Vec<String>,
+    pub starting_tally: Vec<UsualJudgmentTallyCalculated>,
+    pub best_grade: Grade,
+    pub tie_info: Option<BreakTie>,
+    pub winner: usize,
+    pub vote_count: usize,
+    pub votes: Vec<UsualJudgmentVote>,
+    pub ties: Vec<TieRecord>,
+}
+
+pub fn get_result(
+    election: &UsualJudgmentElection,
+    votes: &[UsualJudgmentVote],
+) -> UsualJudgmentResult {
+    let mut tally = Vec::new();
+    for i in 0..election.options.len() {
+        tally.push(UsualJudgmentTally {
+            option_index: i,
+            ratings: [0, 0, 0, 0, 0, 0, 0],
+        })
+    }
+    for (vote, weight) in weighted(votes) {
+        for (i, rating) in vote.votes.iter().enumerate() {
+            let rating_index: usize = (*rating).into();
+            tally[i].ratings[rating_index] += weight as i64;
+        }
+    }
+
+    let mut tie_breaker = TieBreaker::new(&election.tie_break, &election.seed).with_borda(|| {
+        borda_from_values(
+            election.options.len(),
+            weighted(votes).map(|(vote, weight)| (vote.votes.clone(), weight)),
+        )
+    });
+
+    let best_grade = highest_majority_grade(&tally);
+    let matching_best = options_majority_grade(&tally, best_grade);
+    let mut tie_info = None;
+    let winner = if matching_best.len() > 1 {
+        let options = matching_best
+            .iter()
+            .map(|i| tally[*i].clone())
+            .collect::<Vec<_>>();
+        tie_info = Some(break_tie(&mut tie_breaker, &options));
+        tie_info.as_ref().unwrap().winner
+    } else {
+        matching_best[0]
+    };
+
+    UsualJudgmentResult {
+        options: election.options.clone(),
+        starting_tally: tally.into_iter().map(|i| i.into()).collect(),
+        best_grade,
+        tie_info,
+        winner,
+        vote_count: votes.len(),
+        votes: votes.to_vec(),
+        ties: tie_breaker.into_ties(),
+    }
+}
+
+pub struct UsualJudgment;
+
+impl VotingSystem for UsualJudgment {
+    type Ballot = UsualJudgmentVote;
+    type Config = UsualJudgmentElection;
+    type Outcome = UsualJudgmentResult;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome {
+        get_result(config, ballots)
+    }
+
+    fn outcome(report: &Self::Outcome) -> Outcome {
+        let mut ties = Vec::new();
+        if let Some(tie_info) = &report.tie_info {
+            ties.push(tie_info.scores.iter().map(|i| i.option_index).collect());
+        }
+
+        Outcome::new(
+            vec![report.winner],
+            rank_by_score(
+                report
+                    .starting_tally
+                    .iter()
+                    .map(|i| (i.option_index, i.average_grade)),
+            ),
+            ties,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(votes: Vec<Grade>) -> UsualJudgmentVote {
+        UsualJudgmentVote {
+            created_by: uuid::Uuid::new_v4(),
+            weight: Weight::ONE,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_get_result_highest_majority_grade() {
+        use Grade::*;
+
+        let election = UsualJudgmentElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        let votes = vec![
+            vote(vec![Excellent, Fair]),
+            vote(vec![Good, Fair]),
+            vote(vec![Bad, Good]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.best_grade, Good);
+        assert_eq!(result.winner, 0);
+        assert!(result.tie_info.is_none());
+    }
+
+    #[test]
+    fn test_get_result_tie_broken_by_score() {
+        use Grade::*;
+
+        let election = UsualJudgmentElection {
+            options: vec!["A".to_string(), "B".to_string()],
+            seed: "test".to_string(),
+            tie_break: TieBreak::Method,
+        };
+
+        // Both have a majority grade of Good but A has more support above it
+        let votes = vec![
+            vote(vec![Excellent, Good]),
+            vote(vec![Good, Good]),
+            vote(vec![Good, Good]),
+            vote(vec![Fair, Bad]),
+        ];
+
+        let result = get_result(&election, &votes);
+        assert_eq!(result.best_grade, Good);
+        assert_eq!(result.winner, 0);
+        assert!(result.tie_info.is_some());
+    }
+}
@@ -0,0 +1,216 @@
+use std::cmp::Reverse;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// A tabulation method that can be run generically over its ballots
+pub trait VotingSystem {
+    /// A single voter's ballot
+    type Ballot;
+    /// The election settings the method is counted against
+    type Config;
+    /// The method's own detailed report, e.g. `StarResult`
+    type Outcome;
+
+    fn tabulate(config: &Self::Config, ballots: &[Self::Ballot]) -> Self::Outcome;
+
+    /// Reduces the detailed report to the view shared by every method
+    fn outcome(report: &Self::Outcome) -> Outcome;
+
+    fn run(config: &Self::Config, ballots: &[Self::Ballot]) -> Outcome {
+        Self::outcome(&Self::tabulate(config, ballots))
+    }
+}
+
+/// The result of any method in a common shape
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Places from first to last, options sharing a place are grouped together
+    pub ranking: Vec<Vec<usize>>,
+    /// Options that won, in the order they were elected
+    pub elected: Vec<usize>,
+    /// Groups of options the count left level, including any a tie break then split
+    pub ties: Vec<Vec<usize>>,
+}
+
+impl Outcome {
+    /// Builds the view from the method's places, moving the elected options
+    /// to the front in the order they were elected
+    pub fn new(elected: Vec<usize>, places: Vec<Vec<usize>>, ties: Vec<Vec<usize>>) -> Outcome {
+        let mut ranking: Vec<Vec<usize>> = elected.iter().map(|i| vec![*i]).collect();
+        ranking.extend(
+            places
+                .into_iter()
+                .map(|place| {
+                    place
+                        .into_iter()
+                        .filter(|i| !elected.contains(i))
+                        .collect::<Vec<usize>>()
+                })
+                .filter(|place| !place.is_empty()),
+        );
+
+        Outcome {
+            ranking,
+            elected,
+            ties,
+        }
+    }
+
+    /// Builds the view from places where every shared place counts as a tie
+    pub fn from_places(elected: Vec<usize>, places: Vec<Vec<usize>>) -> Outcome {
+        let ties = level_places(&places);
+        Outcome::new(elected, places, ties)
+    }
+
+    /// The first option elected, if any
+    pub fn winner(&self) -> Option<usize> {
+        self.elected.first().copied()
+    }
+}
+
+/// Groups options into places by score, highest score first
+pub fn rank_by_score<T: Ord>(scores: impl IntoIterator<Item = (usize, T)>) -> Vec<Vec<usize>> {
+    let mut scores = scores.into_iter().collect::<Vec<(usize, T)>>();
+    scores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut places: Vec<Vec<usize>> = Vec::new();
+    let mut last_score: Option<&T> = None;
+    for (option_index, score) in &scores {
+        if last_score == Some(score) {
+            places.last_mut().unwrap().push(*option_index);
+        } else {
+            places.push(vec![*option_index]);
+        }
+        last_score = Some(score);
+    }
+
+    places
+}
+
+/// Groups options into places by score, lowest score first
+pub fn rank_by_lowest_score<T: Ord>(
+    scores: impl IntoIterator<Item = (usize, T)>,
+) -> Vec<Vec<usize>> {
+    rank_by_score(scores.into_iter().map(|(i, score)| (i, Reverse(score))))
+}
+
+/// The places shared by more than one option
+pub fn level_places(places: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    places
+        .iter()
+        .filter(|place| place.len() > 1)
+        .cloned()
+        .collect()
+}
+
+/// Places for a count that removes options one round at a time, the options
+/// still standing at the end are ranked by `final_scores` and the removed
+/// ones follow in reverse order of removal
+pub fn rank_by_removal(
+    standing: &[usize],
+    final_scores: &[usize],
+    removed_in_order: &[usize],
+) -> Vec<Vec<usize>> {
+    let mut places = rank_by_score(standing.iter().map(|i| (*i, final_scores[*i])));
+    places.extend(removed_in_order.iter().rev().map(|i| vec![*i]));
+    places
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_by_score_groups_level_options() {
+        let places = rank_by_score(vec![(0, 3), (1, 5), (2, 3), (3, 1)]);
+        assert_eq!(places, vec![vec![1], vec![0, 2], vec![3]]);
+        assert_eq!(level_places(&places), vec![vec![0, 2]]);
+    }
+
+    #[test]
+    fn test_outcome_moves_elected_to_front() {
+        let outcome = Outcome::from_places(vec![2], vec![vec![0, 2], vec![1]]);
+        assert_eq!(outcome.ranking, vec![vec![2], vec![0], vec![1]]);
+        assert_eq!(outcome.ties, vec![vec![0, 2]]);
+        assert_eq!(outcome.winner(), Some(2));
+    }
+
+    #[cfg(all(
+        feature = "borda_count",
+        feature = "preferential_voting",
+        feature = "star"
+    ))]
+    #[test]
+    fn test_same_ballots_across_methods() {
+        use crate::{
+            borda_count::{BordaCount, BordaCountElection, BordaCountVote},
+            preferential_voting::{PreferentialElection, PreferentialVote, PreferentialVoting},
+            star::{Star, StarElection, StarVote},
+            tie_break::TieBreak,
+            weight::Weight,
+        };
+
+        fn run_all(options: &[String], rankings: &[Vec<usize>]) -> Vec<Outcome> {
+            let option_count = options.len();
+            let created_by = uuid::Uuid::nil();
+
+            vec![
+                PreferentialVoting::run(
+                    &PreferentialElection {
+                        options: options.to_vec(),
+                        seed: "test".to_string(),
+                        tie_break: TieBreak::Method,
+                    },
+                    &rankings
+                        .iter()
+                        .map(|votes| PreferentialVote {
+                            created_by,
+                            weight: Weight::ONE,
+                            votes: votes.clone(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                BordaCount::run(
+                    &BordaCountElection {
+                        options: options.to_vec(),
+                        seed: "test".to_string(),
+                        tie_break: TieBreak::Method,
+                    },
+                    &rankings
+                        .iter()
+                        .map(|votes| BordaCountVote {
+                            created_by,
+                            weight: Weight::ONE,
+                            votes: votes.iter().map(|rank| option_count - 1 - rank).collect(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                Star::run(
+                    &StarElection {
+                        options: options.to_vec(),
+                        seed: "test".to_string(),
+                        tie_break: TieBreak::Method,
+                    },
+                    &rankings
+                        .iter()
+                        .map(|votes| StarVote {
+                            created_by,
+                            weight: Weight::ONE,
+                            votes: votes.iter().map(|rank| option_count - 1 - rank).collect(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ]
+        }
+
+        let options = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        // A is first on a majority of ballots
+        let rankings = vec![vec![0, 1, 2], vec![0, 2, 1], vec![0, 1, 2], vec![2, 0, 1]];
+
+        for outcome in run_all(&options, &rankings) {
+            assert_eq!(outcome.elected, vec![0]);
+            assert_eq!(outcome.ranking[0], vec![0]);
+            assert_eq!(outcome.ranking.concat().len(), options.len());
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Ballot weights, for elections where a ballot stands for a number of shares
+//! or a delegate's membership rather than a single voter. Weights can be
+//! whole numbers or fractions, the counts scale them by the lowest common
+//! denominator so every tally stays a whole number.
+
+/// Largest numerator or denominator a weight can be parsed with
+pub const MAX_PART: u64 = 1_000_000;
+
+/// Largest scale a count puts its ballots on. When the lowest common
+/// denominator of the weights is larger the ballots are put on this scale
+/// instead and each weight is rounded down to a whole number of units
+pub const MAX_SCALE: u64 = 1_000_000_000;
+
+use std::{fmt, ops::Add, str::FromStr};
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::ToPrimitive;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weight {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Weight {
+    pub const ONE: Weight = Weight {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// A fraction in its lowest terms, None when the denominator is zero
+    pub fn new(numerator: u64, denominator: u64) -> Option<Weight> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        Some(Weight {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    pub fn whole(count: u64) -> Weight {
+        Weight {
+            numerator: count,
+            denominator: 1,
+        }
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.numerator == self.denominator
+    }
+
+    /// The weight as a whole number of `1 / scale` units, rounded down when
+    /// `scale` isn't a multiple of the denominator
+    pub fn scaled(&self, scale: u64) -> usize {
+        let units = self.numerator as u128 * scale as u128 / self.denominator as u128;
+        usize::try_from(units).unwrap_or(usize::MAX)
+    }
+}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight::ONE
+    }
+}
+
+impl Add for Weight {
+    type Output = Weight;
+
+    fn add(self, other: Weight) -> Weight {
+        let numerator = BigUint::from(self.numerator) * other.denominator
+            + BigUint::from(other.numerator) * self.denominator;
+        let denominator = BigUint::from(self.denominator) * other.denominator;
+        let divisor = numerator.gcd(&denominator);
+        let (numerator, denominator) = (numerator / &divisor, denominator / &divisor);
+        match (numerator.to_u64(), denominator.to_u64()) {
+            (Some(numerator), Some(denominator)) => Weight {
+                numerator,
+                denominator,
+            },
+            // Too fine to hold exactly, rounded down to whole `1 / MAX_SCALE` units
+            _ => Weight::new(
+                (numerator * MAX_SCALE / denominator)
+                    .to_u64()
+                    .unwrap_or(u64::MAX),
+                MAX_SCALE,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWeightError;
+
+impl fmt::Display for ParseWeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Weight must be a positive whole number or a fraction like 5/2, with \
+             neither part above {}",
+            MAX_PART
+        )
+    }
+}
+
+impl std::error::Error for ParseWeightError {}
+
+impl FromStr for Weight {
+    type Err = ParseWeightError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = match s.split_once('/') {
+            Some((numerator, denominator)) => (numerator, denominator),
+            None => (s, "1"),
+        };
+        let numerator = numerator.trim().parse().map_err(|_| ParseWeightError)?;
+        let denominator = denominator.trim().parse().map_err(|_| ParseWeightError)?;
+        if numerator == 0 || numerator > MAX_PART || denominator > MAX_PART {
+            return Err(ParseWeightError);
+        }
+        Weight::new(numerator, denominator).ok_or(ParseWeightError)
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a.max(1)
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// None when the multiple doesn't fit in a u64
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// What every ballot carries whatever the method
+pub trait WeightedBallot {
+    fn voter(&self) -> uuid::Uuid;
+    fn weight(&self) -> Weight;
+    fn set_weight(&mut self, weight: Weight);
+}
+
+macro_rules! impl_weighted_ballot {
+    ($vote:ty) => {
+        impl $crate::weight::WeightedBallot for $vote {
+            fn voter(&self) -> uuid::Uuid {
+                self.created_by
+            }
+
+            fn weight(&self) -> $crate::weight::Weight {
+                self.weight
+            }
+
+            fn set_weight(&mut self, weight: $crate::weight::Weight) {
+                self.weight = weight;
+            }
+        }
+    };
+}
+pub(crate) use impl_weighted_ballot;
+
+/// The lowest common denominator of `weights`, None when it is above
+/// `MAX_SCALE`
+pub fn checked_weight_scale(weights: impl IntoIterator<Item = Weight>) -> Option<u64> {
+    weights.into_iter().try_fold(1, |scale, weight| {
+        lcm(scale, weight.denominator).filter(|scale| *scale <= MAX_SCALE)
+    })
+}
+
+/// The lowest common denominator of the ballots' weights, or `MAX_SCALE`
+/// when that is larger
+pub fn weight_scale<B: WeightedBallot>(ballots: &[B]) -> u64 {
+    checked_weight_scale(ballots.iter().map(|ballot| ballot.weight())).unwrap_or(MAX_SCALE)
+}
+
+/// Each ballot with its weight as a whole number of `1 / weight_scale` units
+pub fn weighted<B: WeightedBallot>(ballots: &[B]) -> impl Iterator<Item = (&B, usize)> {
+    let scale = weight_scale(ballots);
+    ballots
+        .iter()
+        .map(move |ballot| (ballot, ballot.weight().scaled(scale)))
+}
+
+/// The total weight of the ballots in `1 / weight_scale` units
+pub fn total_weight<B: WeightedBallot>(ballots: &[B]) -> usize {
+    weighted(ballots).map(|(_, weight)| weight).sum()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WeightedResult<R> {
+    /// The count with every ballot at its weight
+    #[serde(flatten)]
+    pub weighted: R,
+    /// The same count with every ballot as one vote, left out when every
+    /// ballot already has a weight of one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<R>,
+    /// Weighted tallies are in units of `1 / weight_scale`
+    pub weight_scale: u64,
+}
+
+impl<R, E> WeightedResult<Result<R, E>> {
+    pub fn transpose(self) -> Result<WeightedResult<R>, E> {
+        Ok(WeightedResult {
+            weighted: self.weighted?,
+            raw: self.raw.transpose()?,
+            weight_scale: self.weight_scale,
+        })
+    }
+}
+
+/// Counts the ballots with `count` at their weights, and again at a weight of
+/// one each when any ballot is weighted
+pub fn get_weighted_result<B: WeightedBallot + Clone, R>(
+    ballots: &[B],
+    count: impl Fn(&[B]) -> R,
+) -> WeightedResult<R> {
+    let raw = if ballots.iter().all(|ballot| ballot.weight().is_one()) {
+        None
+    } else {
+        let mut unweighted = ballots.to_vec();
+        for ballot in unweighted.iter_mut() {
+            ballot.set_weight(Weight::ONE);
+        }
+        Some(count(&unweighted))
+    };
+
+    WeightedResult {
+        weighted: count(ballots),
+        raw,
+        weight_scale: weight_scale(ballots),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weight_arithmetic() {
+        assert_eq!(Weight::from_str("6/4"), Ok(Weight::new(3, 2).unwrap()));
+        assert_eq!(Weight::from_str("7"), Ok(Weight::whole(7)));
+        assert_eq!(Weight::from_str("1/0"), Err(ParseWeightError));
+        assert_eq!(Weight::from_str("-1"), Err(ParseWeightError));
+        assert_eq!(Weight::from_str("0"), Err(ParseWeightError));
+        assert_eq!(
+            Weight::new(1, 2).unwrap() + Weight::new(1, 3).unwrap(),
+            Weight::new(5, 6).unwrap()
+        );
+        assert_eq!(Weight::new(5, 2).unwrap().to_string(), "5/2");
+        assert_eq!(Weight::new(5, 2).unwrap().scaled(6), 15);
+        assert_eq!(Weight::from_str("1/1000001"), Err(ParseWeightError));
+        assert_eq!(Weight::from_str("1000001"), Err(ParseWeightError));
+    }
+
+    #[test]
+    fn test_weight_scale_overflow() {
+        // The product of the first 17 primes doesn't fit in a u64
+        let primes = [
+            2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59,
+        ];
+        let weights = primes.iter().map(|p| Weight::new(1, *p).unwrap());
+        assert_eq!(
+            checked_weight_scale(weights.clone().take(9)),
+            Some(223_092_870)
+        );
+        assert_eq!(checked_weight_scale(weights.clone()), None);
+
+        let sum = weights.fold(Weight::new(0, 1).unwrap(), |sum, weight| sum + weight);
+        // Sums too fine to hold exactly are rounded to `1 / MAX_SCALE` units
+        // before the last prime is added
+        assert_eq!(sum.denominator, 59 * MAX_SCALE);
+        assert_eq!(Weight::new(1, 3).unwrap().scaled(MAX_SCALE), 333_333_333);
+    }
+}